@@ -1,15 +1,18 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Deps, DepsMut, Empty, Env, MessageInfo, QueryResponse, Response};
+use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, QueryResponse, Reply, Response};
 
 use hpl_interface::{
-    core::mailbox::{ExecuteMsg, InstantiateMsg, MailboxHookQueryMsg, MailboxQueryMsg, QueryMsg},
+    core::mailbox::{
+        ExecuteMsg, InstantiateMsg, MailboxHookQueryMsg, MailboxQueryMsg, MigrateMsg, QueryMsg,
+    },
     to_binary,
 };
 
 use crate::{
     error::ContractError,
     event::emit_instantiated,
+    migration::MIGRATIONS,
     state::{Config, CONFIG, NONCE},
     CONTRACT_NAME, CONTRACT_VERSION,
 };
@@ -53,13 +56,53 @@ pub fn execute(
 
     match msg {
         Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
+        Pausable(msg) => Ok(hpl_pausable::handle(deps, env, info, msg)?),
 
-        SetDefaultIsm { ism } => execute::set_default_ism(deps, info, ism),
-        SetDefaultHook { hook } => execute::set_default_hook(deps, info, hook),
-        SetRequiredHook { hook } => execute::set_required_hook(deps, info, hook),
+        SetDefaultIsm { ism } => execute::set_default_ism(deps, env, info, ism),
+        SetDefaultHook { hook } => execute::set_default_hook(deps, env, info, hook),
+        SetRequiredHook { hook } => execute::set_required_hook(deps, env, info, hook),
 
-        Dispatch(msg) => execute::dispatch(deps, info, msg),
+        Dispatch(msg) => execute::dispatch(deps, env, info, msg),
+        DispatchMany(msg) => execute::dispatch_many(deps, env, info, msg),
+        DispatchSigned {
+            signer,
+            nonce,
+            signature,
+            msg,
+        } => execute::dispatch_signed(deps, env, info, signer, nonce, signature, msg),
         Process { metadata, message } => execute::process(deps, env, info, metadata, message),
+        ProcessBatch { msgs, skip_failed } => {
+            execute::process_batch(deps, env, info, msgs, skip_failed)
+        }
+
+        SetHandleIsolation { enabled } => execute::set_handle_isolation(deps, env, info, enabled),
+        SetLocalDelivery { enabled } => execute::set_local_delivery(deps, env, info, enabled),
+        SetIsmFallback { recipient, enabled } => {
+            execute::set_ism_fallback(deps, env, info, recipient, enabled)
+        }
+        RetryHandle { id } => execute::retry_handle(deps, info, id),
+
+        SetDispatchRetention { retention } => {
+            execute::set_dispatch_retention(deps, env, info, retention)
+        }
+        PruneDispatches {
+            before_nonce,
+            limit,
+        } => execute::prune_dispatches(deps, env, info, before_nonce, limit),
+
+        Pause { target } => execute::pause(deps, env, info, target),
+        Release { target } => execute::release(deps, env, info, target),
+
+        SetRecipientIsm { recipient, ism } => {
+            execute::set_recipient_ism(deps, env, info, recipient, ism)
+        }
+        UnsetRecipientIsm { recipient } => execute::unset_recipient_ism(deps, env, info, recipient),
+        SetOriginDefaultIsm { origin, ism } => {
+            execute::set_origin_default_ism(deps, env, info, origin, ism)
+        }
+        UnsetOriginDefaultIsm { origin } => {
+            execute::unset_origin_default_ism(deps, env, info, origin)
+        }
     }
 }
 
@@ -70,8 +113,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
 
     match msg {
         QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Pausable(msg) => Ok(hpl_pausable::handle_query(deps, env, msg)?),
         QueryMsg::Hook(msg) => match msg {
             MailboxHookQueryMsg::QuoteDispatch(msg) => to_binary(quote_dispatch(deps, msg)),
+            MailboxHookQueryMsg::QuoteDispatchMany(msg) => {
+                to_binary(quote_dispatch_many(deps, msg))
+            }
         },
         QueryMsg::Mailbox(msg) => match msg {
             Hrp {} => to_binary(get_hrp(deps)),
@@ -80,16 +127,83 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
             DefaultHook {} => to_binary(get_default_hook(deps)),
             RequiredHook {} => to_binary(get_required_hook(deps)),
             MessageDelivered { id } => to_binary(get_delivered(deps, id)),
-            RecipientIsm { recipient_addr } => to_binary(get_recipient_ism(deps, recipient_addr)),
+            RecipientIsm {
+                recipient_addr,
+                origin,
+            } => to_binary(get_recipient_ism(deps, recipient_addr, origin)),
             Nonce {} => to_binary(get_nonce(deps)),
+            SignedNonce { signer } => to_binary(get_signed_nonce(deps, signer)),
             LatestDispatchId {} => to_binary(get_latest_dispatch_id(deps)),
+            LatestDispatchBatch {} => to_binary(get_latest_dispatch_batch(deps)),
+            DispatchedMessage { nonce } => to_binary(get_dispatched_message(deps, nonce)),
+            DispatchedMessageById { id } => to_binary(get_dispatched_message_by_id(deps, id)),
+            DispatchedMessages {
+                offset,
+                limit,
+                order,
+            } => to_binary(list_dispatched_messages(deps, offset, limit, order)),
+            DispatchRetention {} => to_binary(get_dispatch_retention(deps)),
+            HandleIsolation {} => to_binary(get_handle_isolation(deps)),
+            LocalDelivery {} => to_binary(get_local_delivery(deps)),
+            IsmFallback { recipient } => to_binary(get_ism_fallback(deps, recipient)),
+            FailedDelivery { id } => to_binary(get_failed_delivery(deps, id)),
+            FailedDeliveries {
+                offset,
+                limit,
+                order,
+            } => to_binary(list_failed_deliveries(deps, offset, limit, order)),
+            Delivery { id } => to_binary(get_delivery(deps, id)),
+            DeliveriesByOrigin {
+                origin,
+                offset,
+                limit,
+                order,
+            } => to_binary(list_deliveries_by_origin(
+                deps, origin, offset, limit, order,
+            )),
+            DeliveriesByRecipient {
+                recipient,
+                offset,
+                limit,
+                order,
+            } => to_binary(list_deliveries_by_recipient(
+                deps, recipient, offset, limit, order,
+            )),
+            PauseState {} => to_binary(get_pause_state(deps)),
+            RecipientIsmOverrides {
+                offset,
+                limit,
+                order,
+            } => to_binary(list_recipient_ism_overrides(deps, offset, limit, order)),
+            OriginDefaultIsms {
+                offset,
+                limit,
+                order,
+            } => to_binary(list_origin_default_isms(deps, offset, limit, order)),
+            Diagnostics {} => to_binary(get_diagnostics(deps, env)),
+            Stats {
+                domains,
+                offset,
+                limit,
+                order,
+            } => to_binary(get_stats(deps, domains, offset, limit, order)),
         },
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    crate::execute::handle_reply(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        MIGRATIONS,
+    )?)
 }
 
 #[cfg(test)]
@@ -127,4 +241,26 @@ mod test {
         assert_eq!(config.default_hook, None);
         assert_eq!(config.default_ism, None);
     }
+
+    #[rstest]
+    #[case(CONTRACT_NAME, "0.0.1", None)]
+    #[case(CONTRACT_NAME, "99.0.0", Some("cannot downgrade"))]
+    #[case("hpl-hook-merkle", "0.0.1", Some("invalid contract name"))]
+    fn test_migrate(#[case] name: &str, #[case] version: &str, #[case] err: Option<&str>) {
+        let mut deps = mock_dependencies();
+
+        cw2::set_contract_version(deps.as_mut().storage, name, version).unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+
+        match err {
+            None => {
+                res.unwrap();
+
+                let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+                assert_eq!(version.version, CONTRACT_VERSION);
+            }
+            Some(err) => assert!(res.unwrap_err().to_string().contains(err)),
+        }
+    }
 }
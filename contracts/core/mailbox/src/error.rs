@@ -1,4 +1,5 @@
-use cosmwasm_std::{Coin, StdError};
+use cosmwasm_std::{Coin, StdError, Uint256};
+use hpl_interface::types::SignatureError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -9,15 +10,30 @@ pub enum ContractError {
     #[error("{0}")]
     Payment(#[from] cw_utils::PaymentError),
 
+    #[error("{0}")]
+    Signature(#[from] SignatureError),
+
     #[error("unauthorized")]
     Unauthorized {},
 
+    #[error("default_ism not set")]
+    DefaultIsmNotSet {},
+
+    #[error("default_hook not set")]
+    DefaultHookNotSet {},
+
+    #[error("required_hook not set")]
+    RequiredHookNotSet {},
+
     #[error("ism verify failed")]
     VerifyFailed {},
 
     #[error("invalid address length: {len:?}")]
     InvalidAddressLength { len: usize },
 
+    #[error("invalid message length: {len:?}")]
+    InvalidMessageLength { len: usize },
+
     #[error("invalid message version: {version:?}")]
     InvalidMessageVersion { version: u8 },
 
@@ -27,9 +43,63 @@ pub enum ContractError {
     #[error("insufficient funds. required: {required}, received: {received}")]
     InsufficientFunds { required: Coin, received: Coin },
 
+    #[error("dispatch value mismatch. metadata: {metadata}, body: {body}")]
+    DispatchValueMismatch { metadata: Uint256, body: Uint256 },
+
+    #[error("process value mismatch. expected: {expected:?}, received: {received:?}")]
+    ProcessValueMismatch {
+        expected: Vec<Coin>,
+        received: Vec<Coin>,
+    },
+
     #[error("message already delivered")]
     AlreadyDeliveredMessage {},
 
     #[error("message not found")]
     MessageNotFound {},
+
+    #[error("failed delivery not found")]
+    FailedDeliveryNotFound {},
+
+    #[error("unknown reply id: {id:?}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("invalid signature")]
+    InvalidSignature {},
+
+    #[error("invalid signed nonce. expected: {expected:?}, got: {got:?}")]
+    InvalidSignedNonce { expected: u64, got: u64 },
+
+    #[error("empty batch")]
+    EmptyBatch {},
+
+    #[error("refund addresses of a batch must match")]
+    RefundAddressMismatch {},
+
+    #[error("invalid dispatch retention: {retention:?}")]
+    InvalidDispatchRetention { retention: u32 },
+
+    #[error("mailbox paused")]
+    Paused {},
+
+    #[error("dispatch paused")]
+    DispatchPaused {},
+
+    #[error("process paused")]
+    ProcessPaused {},
+
+    #[error("origin paused: {domain:?}")]
+    OriginPaused { domain: u32 },
+
+    #[error("already paused")]
+    AlreadyPaused {},
+
+    #[error("already released")]
+    AlreadyReleased {},
+
+    #[error("recipient ism not set")]
+    RecipientIsmNotSet {},
+
+    #[error("origin default ism not set: {origin:?}")]
+    OriginDefaultIsmNotSet { origin: u32 },
 }
@@ -1,5 +1,5 @@
-use cosmwasm_std::{Addr, Event, HexBinary};
-use hpl_interface::types::Message;
+use cosmwasm_std::{Addr, Coin, Event, HexBinary};
+use hpl_interface::{core::mailbox::PauseTarget, types::Message};
 
 pub fn emit_instantiated(owner: Addr) -> Event {
     Event::new("mailbox_instantiated").add_attribute("owner", owner)
@@ -23,6 +23,100 @@ pub fn emit_required_hook_set(owner: Addr, new_required_hook: Addr) -> Event {
         .add_attribute("new_required_hook", new_required_hook)
 }
 
+pub fn emit_dispatch_retention_set(owner: Addr, retention: Option<u32>) -> Event {
+    Event::new("mailbox_dispatch_retention_set")
+        .add_attribute("owner", owner)
+        .add_attribute(
+            "retention",
+            retention
+                .map(|v| v.to_string())
+                .unwrap_or("unlimited".into()),
+        )
+}
+
+pub fn emit_dispatches_pruned(owner: Addr, before_nonce: u32, pruned: u32) -> Event {
+    Event::new("mailbox_dispatches_pruned")
+        .add_attribute("owner", owner)
+        .add_attribute("before_nonce", before_nonce.to_string())
+        .add_attribute("pruned", pruned.to_string())
+}
+
+pub fn emit_handle_isolation_set(owner: Addr, enabled: bool) -> Event {
+    Event::new("mailbox_handle_isolation_set")
+        .add_attribute("owner", owner)
+        .add_attribute("enabled", enabled.to_string())
+}
+
+pub fn emit_dispatch_signed(signer: HexBinary, nonce: u64, submitter: Addr) -> Event {
+    Event::new("mailbox_dispatch_signed")
+        .add_attribute("signer", signer.to_hex())
+        .add_attribute("nonce", nonce.to_string())
+        .add_attribute("submitter", submitter)
+}
+
+pub fn emit_local_delivery_set(owner: Addr, enabled: bool) -> Event {
+    Event::new("mailbox_local_delivery_set")
+        .add_attribute("owner", owner)
+        .add_attribute("enabled", enabled.to_string())
+}
+
+pub fn emit_ism_fallback_set(owner: Addr, recipient: Addr, enabled: bool) -> Event {
+    Event::new("mailbox_ism_fallback_set")
+        .add_attribute("owner", owner)
+        .add_attribute("recipient", recipient)
+        .add_attribute("enabled", enabled.to_string())
+}
+
+pub fn emit_recipient_ism_set(owner: Addr, recipient: Addr, ism: Addr) -> Event {
+    Event::new("mailbox_recipient_ism_set")
+        .add_attribute("owner", owner)
+        .add_attribute("recipient", recipient)
+        .add_attribute("ism", ism)
+}
+
+pub fn emit_recipient_ism_unset(owner: Addr, recipient: Addr) -> Event {
+    Event::new("mailbox_recipient_ism_unset")
+        .add_attribute("owner", owner)
+        .add_attribute("recipient", recipient)
+}
+
+pub fn emit_origin_default_ism_set(owner: Addr, origin: u32, ism: Addr) -> Event {
+    Event::new("mailbox_origin_default_ism_set")
+        .add_attribute("owner", owner)
+        .add_attribute("origin", origin.to_string())
+        .add_attribute("ism", ism)
+}
+
+pub fn emit_origin_default_ism_unset(owner: Addr, origin: u32) -> Event {
+    Event::new("mailbox_origin_default_ism_unset")
+        .add_attribute("owner", owner)
+        .add_attribute("origin", origin.to_string())
+}
+
+fn pause_target_attributes(event: Event, target: PauseTarget) -> Event {
+    match target {
+        PauseTarget::Dispatch {} => event.add_attribute("target", "dispatch"),
+        PauseTarget::Process {} => event.add_attribute("target", "process"),
+        PauseTarget::Origin { domain } => event
+            .add_attribute("target", "origin")
+            .add_attribute("origin", domain.to_string()),
+    }
+}
+
+pub fn emit_paused(owner: Addr, target: PauseTarget) -> Event {
+    pause_target_attributes(
+        Event::new("mailbox_paused").add_attribute("owner", owner),
+        target,
+    )
+}
+
+pub fn emit_released(owner: Addr, target: PauseTarget) -> Event {
+    pause_target_attributes(
+        Event::new("mailbox_released").add_attribute("owner", owner),
+        target,
+    )
+}
+
 pub fn emit_dispatch_id(id: HexBinary) -> Event {
     Event::new("mailbox_dispatch_id").add_attribute("message_id", id.to_hex())
 }
@@ -35,6 +129,13 @@ pub fn emit_dispatch(msg: Message) -> Event {
         .add_attribute("message", HexBinary::from(msg).to_hex())
 }
 
+pub fn emit_dispatch_value(id: HexBinary, value: Coin) -> Event {
+    Event::new("mailbox_dispatch_value")
+        .add_attribute("message_id", id.to_hex())
+        .add_attribute("value", value.amount.to_string())
+        .add_attribute("denom", value.denom)
+}
+
 pub fn emit_process_id(id: HexBinary) -> Event {
     Event::new("mailbox_process_id").add_attribute("message_id", id.to_hex())
 }
@@ -45,3 +146,21 @@ pub fn emit_process(origin: u32, sender: HexBinary, recipient: HexBinary) -> Eve
         .add_attribute("sender", sender.to_hex())
         .add_attribute("recipient", recipient.to_hex())
 }
+
+pub fn emit_process_failed(id: HexBinary, error: String) -> Event {
+    Event::new("mailbox_process_failed")
+        .add_attribute("message_id", id.to_hex())
+        .add_attribute("error", error)
+}
+
+pub fn emit_handle_failed(id: HexBinary, error: String) -> Event {
+    Event::new("mailbox_handle_failed")
+        .add_attribute("message_id", id.to_hex())
+        .add_attribute("error", error)
+}
+
+pub fn emit_handle_retried(sender: Addr, id: HexBinary) -> Event {
+    Event::new("mailbox_handle_retried")
+        .add_attribute("sender", sender)
+        .add_attribute("message_id", id.to_hex())
+}
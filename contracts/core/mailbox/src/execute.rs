@@ -1,78 +1,97 @@
 use cosmwasm_std::{
-    coin, ensure, ensure_eq, to_binary, wasm_execute, Coin, Deps, DepsMut, Env, HexBinary,
-    MessageInfo, Response,
+    coin, ensure, ensure_eq, to_binary, wasm_execute, Addr, BankMsg, Coin, CosmosMsg, DepsMut,
+    Empty, Env, Event, HexBinary, MessageInfo, Order, Reply, ReplyOn, Response, StdResult, Storage,
+    SubMsg, SubMsgResult, Uint256, WasmMsg,
 };
-use cw_utils::PaymentError;
+use cw_storage_plus::Bound;
 use hpl_interface::{
     core::{
-        mailbox::{DispatchMsg, DispatchResponse},
+        mailbox::{
+            DispatchManyMsg, DispatchManyResponse, DispatchMsg, DispatchResponse, PauseTarget,
+            ProcessBatchResponse, ProcessMsg, ProcessResult,
+        },
         HandleMsg,
     },
-    hook::{post_dispatch, quote_dispatch},
+    hook::post_dispatch,
     ism,
-    types::Message,
+    types::{
+        bech32_to_h256, keccak256_hash, recover_eth_addr, Message, MessageValue,
+        StandardHookMetadata,
+    },
 };
 
 use hpl_ownable::get_owner;
 
 use crate::{
     event::{
-        emit_default_hook_set, emit_default_ism_set, emit_dispatch, emit_dispatch_id, emit_process,
-        emit_process_id, emit_required_hook_set,
+        emit_default_hook_set, emit_default_ism_set, emit_dispatch, emit_dispatch_id,
+        emit_dispatch_retention_set, emit_dispatch_signed, emit_dispatch_value,
+        emit_dispatches_pruned, emit_handle_failed, emit_handle_isolation_set, emit_handle_retried,
+        emit_ism_fallback_set, emit_local_delivery_set, emit_origin_default_ism_set,
+        emit_origin_default_ism_unset, emit_paused, emit_process, emit_process_failed,
+        emit_process_id, emit_recipient_ism_set, emit_recipient_ism_unset, emit_released,
+        emit_required_hook_set,
+    },
+    query::{get_dispatch_quote, get_local_dispatch_quote, merge_fees, message_value, resolve_ism},
+    state::{
+        Config, Delivery, Dispatch, FailedDelivery, PendingHandle, CONFIG, DELIVERIES,
+        DELIVERIES_BY_ORIGIN, DELIVERIES_BY_RECIPIENT, DISPATCHES, DISPATCH_NONCES,
+        DISPATCH_PAUSED, DISPATCH_RETENTION, FAILED_DELIVERIES, HANDLE_ISOLATION, HANDLE_REPLY_ID,
+        INBOUND_COUNTS, ISM_FALLBACKS, LATEST_DISPATCHED_ID, LATEST_DISPATCH_BATCH,
+        LATEST_PROCESS_BATCH, LOCAL_DELIVERY, NONCE, ORIGIN_DEFAULT_ISMS, OUTBOUND_COUNTS,
+        PAUSED_ORIGINS, PENDING_HANDLES, PROCESS_PAUSED, RECIPIENT_ISMS, SIGNED_NONCES,
     },
-    state::{Delivery, CONFIG, DELIVERIES, LATEST_DISPATCHED_ID, NONCE},
     ContractError, MAILBOX_VERSION,
 };
 
-fn get_required_value(
-    deps: Deps,
-    info: &MessageInfo,
-    hook: impl Into<String>,
-    metadata: HexBinary,
-    msg_body: HexBinary,
-) -> Result<(Option<Coin>, Option<Coin>), ContractError> {
-    let required = quote_dispatch(&deps.querier, hook, metadata, msg_body)?.gas_amount;
-    let required = match required {
-        Some(v) => v,
-        None => return Ok((None, None)),
+const DEFAULT_PRUNE_LIMIT: u32 = 100;
+// more than one, so dispatches left outside a lowered retention window drain over time
+const DISPATCH_EVICTIONS: usize = 2;
+
+/// Returns the part of `funds` left over once every coin in `fees` is covered.
+fn get_surplus(funds: &[Coin], fees: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+    let received = |denom: &str| {
+        funds
+            .iter()
+            .find(|v| v.denom == denom)
+            .map(|v| v.amount)
+            .unwrap_or_default()
     };
 
-    if info.funds.is_empty() {
-        return Ok((None, None));
-    }
+    for fee in fees {
+        let received = coin(received(&fee.denom).u128(), &fee.denom);
 
-    if info.funds.len() > 1 {
-        return Err(PaymentError::MultipleDenoms {}.into());
+        ensure!(
+            received.amount >= fee.amount,
+            ContractError::InsufficientFunds {
+                required: fee.clone(),
+                received,
+            }
+        );
     }
 
-    let received = &info.funds[0];
-
-    deps.api.debug(&format!(
-        "mailbox::dispatch: required: {:?}, received: {:?}",
-        required, received
-    ));
-
-    ensure_eq!(
-        &received.denom,
-        &required.denom,
-        PaymentError::ExtraDenom(received.clone().denom)
-    );
-
-    if received.amount <= required.amount {
-        return Ok((Some(received.clone()), None));
-    }
+    Ok(funds
+        .iter()
+        .filter_map(|fund| {
+            let fee = fees
+                .iter()
+                .find(|v| v.denom == fund.denom)
+                .map(|v| v.amount)
+                .unwrap_or_default();
+            let surplus = fund.amount - fee;
+
+            (!surplus.is_zero()).then(|| coin(surplus.u128(), &fund.denom))
+        })
+        .collect())
+}
 
-    Ok((
-        Some(required.clone()),
-        Some(coin(
-            (received.amount - required.amount).u128(),
-            required.denom,
-        )),
-    ))
+fn to_hook_funds(fee: Option<Coin>) -> Option<Vec<Coin>> {
+    fee.filter(|v| !v.amount.is_zero()).map(|v| vec![v])
 }
 
 pub fn set_default_ism(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     new_default_ism: String,
 ) -> Result<Response, ContractError> {
@@ -83,7 +102,18 @@ pub fn set_default_ism(
     );
 
     let new_default_ism = deps.api.addr_validate(&new_default_ism)?;
-    let event = emit_default_ism_set(info.sender, new_default_ism.clone());
+    let event = emit_default_ism_set(info.sender.clone(), new_default_ism.clone());
+
+    let old_default_ism = CONFIG.load(deps.storage)?.default_ism;
+
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "set_default_ism",
+        old_default_ism,
+        Some(new_default_ism.clone()),
+    )?;
 
     CONFIG.update(deps.storage, |mut config| -> Result<_, ContractError> {
         config.default_ism = Some(new_default_ism);
@@ -96,6 +126,7 @@ pub fn set_default_ism(
 
 pub fn set_default_hook(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     new_default_hook: String,
 ) -> Result<Response, ContractError> {
@@ -106,7 +137,18 @@ pub fn set_default_hook(
     );
 
     let new_default_hook = deps.api.addr_validate(&new_default_hook)?;
-    let event = emit_default_hook_set(info.sender, new_default_hook.clone());
+    let event = emit_default_hook_set(info.sender.clone(), new_default_hook.clone());
+
+    let old_default_hook = CONFIG.load(deps.storage)?.default_hook;
+
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "set_default_hook",
+        old_default_hook,
+        Some(new_default_hook.clone()),
+    )?;
 
     CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
         config.default_hook = Some(new_default_hook);
@@ -119,6 +161,7 @@ pub fn set_default_hook(
 
 pub fn set_required_hook(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     new_required_hook: String,
 ) -> Result<Response, ContractError> {
@@ -129,7 +172,18 @@ pub fn set_required_hook(
     );
 
     let new_required_hook = deps.api.addr_validate(&new_required_hook)?;
-    let event = emit_required_hook_set(info.sender, new_required_hook.clone());
+    let event = emit_required_hook_set(info.sender.clone(), new_required_hook.clone());
+
+    let old_required_hook = CONFIG.load(deps.storage)?.required_hook;
+
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "set_required_hook",
+        old_required_hook,
+        Some(new_required_hook.clone()),
+    )?;
 
     CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
         config.required_hook = Some(new_required_hook);
@@ -140,471 +194,3349 @@ pub fn set_required_hook(
     Ok(Response::new().add_event(event))
 }
 
-pub fn dispatch(
+pub fn set_recipient_ism(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    dispatch_msg: DispatchMsg,
+    recipient: String,
+    ism: String,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let nonce = NONCE.load(deps.storage)?;
-
-    ensure!(
-        dispatch_msg.recipient_addr.len() == 32,
-        ContractError::InvalidAddressLength {
-            len: dispatch_msg.recipient_addr.len()
-        }
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
     );
 
-    // calculate gas
-    let required_hook = config.get_required_hook();
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let ism = deps.api.addr_validate(&ism)?;
 
-    let msg =
-        dispatch_msg
-            .clone()
-            .to_msg(MAILBOX_VERSION, nonce, config.local_domain, &info.sender)?;
-    let msg_id = msg.id();
+    let old_ism = RECIPIENT_ISMS.may_load(deps.storage, recipient.clone())?;
 
-    let (required_hook_value, hook_value) = get_required_value(
-        deps.as_ref(),
-        &info,
-        required_hook.as_str(),
-        dispatch_msg.metadata.clone().unwrap_or_default(),
-        msg.clone().into(),
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        &format!("set_recipient_ism:{recipient}"),
+        old_ism,
+        Some(ism.clone()),
     )?;
-    let (required_hook_value, hook_value) = (
-        required_hook_value.map(|v| vec![v]),
-        hook_value.map(|v| vec![v]),
+    RECIPIENT_ISMS.save(deps.storage, recipient.clone(), &ism)?;
+
+    Ok(Response::new().add_event(emit_recipient_ism_set(info.sender, recipient, ism)))
+}
+
+pub fn unset_recipient_ism(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
     );
 
-    // interaction
-    let hook = dispatch_msg.get_hook_addr(deps.api, config.get_default_hook())?;
-    let hook_metadata = dispatch_msg.metadata.unwrap_or_default();
+    let recipient = deps.api.addr_validate(&recipient)?;
 
-    // effects
-    NONCE.save(deps.storage, &(nonce + 1))?;
-    LATEST_DISPATCHED_ID.save(deps.storage, &msg_id.to_vec())?;
+    let ism = RECIPIENT_ISMS
+        .may_load(deps.storage, recipient.clone())?
+        .ok_or(ContractError::RecipientIsmNotSet {})?;
+    RECIPIENT_ISMS.remove(deps.storage, recipient.clone());
 
-    // make message
-    let post_dispatch_msgs = vec![
-        post_dispatch(
-            required_hook,
-            hook_metadata.clone(),
-            msg.clone(),
-            required_hook_value,
-        )?,
-        post_dispatch(hook, hook_metadata, msg.clone(), hook_value)?,
-    ];
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        &format!("set_recipient_ism:{recipient}"),
+        Some(ism),
+        None::<Addr>,
+    )?;
 
-    Ok(Response::new()
-        .add_event(emit_dispatch_id(msg_id.clone()))
-        .add_event(emit_dispatch(msg))
-        .set_data(to_binary(&DispatchResponse { message_id: msg_id })?)
-        .add_messages(post_dispatch_msgs))
+    Ok(Response::new().add_event(emit_recipient_ism_unset(info.sender, recipient)))
 }
 
-pub fn process(
+pub fn set_origin_default_ism(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    metadata: HexBinary,
-    message: HexBinary,
+    origin: u32,
+    ism: String,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-
-    let decoded_msg: Message = message.into();
-    let recipient = decoded_msg.recipient_addr(&config.hrp)?;
-
     ensure_eq!(
-        decoded_msg.version,
-        MAILBOX_VERSION,
-        ContractError::InvalidMessageVersion {
-            version: decoded_msg.version
-        }
-    );
-    ensure_eq!(
-        decoded_msg.dest_domain,
-        config.local_domain,
-        ContractError::InvalidDestinationDomain {
-            domain: decoded_msg.dest_domain
-        }
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
     );
 
-    let id = decoded_msg.id();
-    let ism = ism::recipient(&deps.querier, &recipient)?.unwrap_or(config.get_default_ism());
+    let ism = deps.api.addr_validate(&ism)?;
 
-    deps.api.debug(&format!("mailbox::process: ism: {}", &ism));
+    let old_ism = ORIGIN_DEFAULT_ISMS.may_load(deps.storage, origin)?;
 
-    ensure!(
-        !DELIVERIES.has(deps.storage, id.to_vec()),
-        ContractError::AlreadyDeliveredMessage {}
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        &format!("set_origin_default_ism:{origin}"),
+        old_ism,
+        Some(ism.clone()),
+    )?;
+    ORIGIN_DEFAULT_ISMS.save(deps.storage, origin, &ism)?;
+
+    Ok(Response::new().add_event(emit_origin_default_ism_set(info.sender, origin, ism)))
+}
+
+pub fn unset_origin_default_ism(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    origin: u32,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
     );
 
-    DELIVERIES.save(
+    let ism = ORIGIN_DEFAULT_ISMS
+        .may_load(deps.storage, origin)?
+        .ok_or(ContractError::OriginDefaultIsmNotSet { origin })?;
+    ORIGIN_DEFAULT_ISMS.remove(deps.storage, origin);
+
+    audit(
         deps.storage,
-        id.to_vec(),
-        &Delivery {
-            sender: info.sender,
-            block_number: env.block.height,
-        },
+        &env,
+        &info.sender,
+        &format!("set_origin_default_ism:{origin}"),
+        Some(ism),
+        None::<Addr>,
     )?;
 
-    let verify = ism::verify(&deps.querier, ism, metadata, decoded_msg.clone().into())?;
+    Ok(Response::new().add_event(emit_origin_default_ism_unset(info.sender, origin)))
+}
 
-    deps.api
-        .debug(&format!("mailbox::process: verify: {}", verify));
+pub fn set_dispatch_retention(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    retention: Option<u32>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
+    );
 
-    ensure!(verify, ContractError::VerifyFailed {});
+    let old_retention = DISPATCH_RETENTION.may_load(deps.storage)?;
 
-    let handle_msg = wasm_execute(
-        recipient,
-        &HandleMsg {
-            origin: decoded_msg.origin_domain,
-            sender: decoded_msg.sender.clone(),
-            body: decoded_msg.body,
+    match retention {
+        Some(retention) => {
+            ensure!(
+                retention > 0,
+                ContractError::InvalidDispatchRetention { retention }
+            );
+            DISPATCH_RETENTION.save(deps.storage, &retention)?;
         }
-        .wrap(),
-        vec![],
+        None => DISPATCH_RETENTION.remove(deps.storage),
+    }
+
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "set_dispatch_retention",
+        old_retention,
+        retention,
     )?;
 
-    Ok(Response::new().add_message(handle_msg).add_events(vec![
-        emit_process_id(id),
-        emit_process(
-            config.local_domain,
-            decoded_msg.sender,
-            decoded_msg.recipient,
-        ),
-    ]))
+    Ok(Response::new().add_event(emit_dispatch_retention_set(info.sender, retention)))
 }
 
-#[cfg(test)]
-mod tests {
-    use cosmwasm_std::{
-        from_binary,
-        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-        Addr, ContractResult, OwnedDeps, QuerierResult, SystemResult, WasmQuery,
-    };
+pub fn prune_dispatches(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    before_nonce: u32,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
+    );
 
-    use hpl_interface::{
-        core::mailbox::InstantiateMsg,
-        hook::{ExpectedHookQueryMsg, HookQueryMsg, QuoteDispatchResponse},
-        ism::IsmQueryMsg,
-        types::bech32_encode,
-    };
-    use ibcx_test_utils::{addr, gen_bz};
-    use rstest::{fixture, rstest};
+    let targets = DISPATCHES
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::exclusive(before_nonce)),
+            Order::Ascending,
+        )
+        .take(limit.unwrap_or(DEFAULT_PRUNE_LIMIT) as usize)
+        .collect::<StdResult<Vec<_>>>()?;
 
-    use super::*;
+    for (nonce, dispatch) in &targets {
+        DISPATCHES.remove(deps.storage, *nonce);
+        DISPATCH_NONCES.remove(deps.storage, dispatch.id.to_vec());
+    }
 
-    use crate::{contract::instantiate, state::Config};
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "prune_dispatches",
+        None,
+        Some(before_nonce),
+    )?;
 
-    const OWNER: &str = "owner";
-    const NOT_OWNER: &str = "not_owner";
+    Ok(Response::new().add_event(emit_dispatches_pruned(
+        info.sender,
+        before_nonce,
+        targets.len() as u32,
+    )))
+}
 
-    const LOCAL_DOMAIN: u32 = 26657;
-    const DEST_DOMAIN: u32 = 11155111;
+pub fn pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target: PauseTarget,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
+    );
 
-    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+    ensure!(
+        !is_target_paused(deps.storage, &target)?,
+        ContractError::AlreadyPaused {}
+    );
 
-    fn mock_query_handler(req: &WasmQuery) -> QuerierResult {
-        let (req, _addr) = match req {
-            WasmQuery::Smart { msg, contract_addr } => (from_binary(msg).unwrap(), contract_addr),
-            _ => unreachable!("wrong query type"),
-        };
+    match target {
+        PauseTarget::Dispatch {} => DISPATCH_PAUSED.save(deps.storage, &true)?,
+        PauseTarget::Process {} => PROCESS_PAUSED.save(deps.storage, &true)?,
+        PauseTarget::Origin { domain } => PAUSED_ORIGINS.save(deps.storage, domain, &Empty {})?,
+    }
 
-        let req = match req {
-            ExpectedHookQueryMsg::Hook(HookQueryMsg::QuoteDispatch(msg)) => msg,
-            _ => unreachable!("wrong query type"),
-        };
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        &format!("paused:{}", pause_target_key(&target)),
+        Some(false),
+        Some(true),
+    )?;
 
-        let mut gas_amount = None;
+    Ok(Response::new().add_event(emit_paused(info.sender, target)))
+}
 
-        if !req.metadata.is_empty() {
-            let parsed_gas = u32::from_be_bytes(req.metadata.as_slice().try_into().unwrap());
+pub fn release(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target: PauseTarget,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
+    );
 
-            gas_amount = Some(coin(parsed_gas as u128, "utest"));
-        }
+    ensure!(
+        is_target_paused(deps.storage, &target)?,
+        ContractError::AlreadyReleased {}
+    );
 
-        let res = QuoteDispatchResponse { gas_amount };
-        let res = cosmwasm_std::to_binary(&res).unwrap();
-        SystemResult::Ok(ContractResult::Ok(res))
+    match target {
+        PauseTarget::Dispatch {} => DISPATCH_PAUSED.remove(deps.storage),
+        PauseTarget::Process {} => PROCESS_PAUSED.remove(deps.storage),
+        PauseTarget::Origin { domain } => PAUSED_ORIGINS.remove(deps.storage, domain),
     }
 
-    #[fixture]
-    fn deps(#[default("deployer")] sender: &str) -> TestDeps {
-        let mut deps = mock_dependencies();
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        &format!("paused:{}", pause_target_key(&target)),
+        Some(true),
+        Some(false),
+    )?;
 
-        instantiate(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(sender, &[]),
-            InstantiateMsg {
-                hrp: "osmo".to_string(),
-                owner: OWNER.to_string(),
-                domain: LOCAL_DOMAIN,
-            },
-        )
-        .unwrap();
+    Ok(Response::new().add_event(emit_released(info.sender, target)))
+}
 
-        deps
+fn pause_target_key(target: &PauseTarget) -> String {
+    match target {
+        PauseTarget::Dispatch {} => "dispatch".to_string(),
+        PauseTarget::Process {} => "process".to_string(),
+        PauseTarget::Origin { domain } => format!("origin:{domain}"),
     }
+}
 
-    #[rstest]
-    fn test_init(deps: TestDeps) {
-        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+/// Records a privileged change in the shared audit log. Values are stored as strings.
+fn audit<T: ToString>(
+    storage: &mut dyn Storage,
+    env: &Env,
+    actor: &Addr,
+    action: &str,
+    old_value: Option<T>,
+    new_value: Option<T>,
+) -> StdResult<u64> {
+    hpl_ownable::record_audit(
+        storage,
+        env,
+        actor,
+        action,
+        old_value.map(|v| v.to_string()),
+        new_value.map(|v| v.to_string()),
+    )
+}
 
-        assert_eq!(config.hrp, "osmo");
-        assert_eq!(config.local_domain, LOCAL_DOMAIN);
-        assert_eq!(config.default_ism, None);
-        assert_eq!(config.default_hook, None);
-        assert_eq!(config.required_hook, None);
+fn is_target_paused(storage: &dyn Storage, target: &PauseTarget) -> StdResult<bool> {
+    Ok(match target {
+        PauseTarget::Dispatch {} => DISPATCH_PAUSED.may_load(storage)?.unwrap_or_default(),
+        PauseTarget::Process {} => PROCESS_PAUSED.may_load(storage)?.unwrap_or_default(),
+        PauseTarget::Origin { domain } => PAUSED_ORIGINS.has(storage, *domain),
+    })
+}
 
-        let nonce = NONCE.load(deps.as_ref().storage).unwrap();
-        assert_eq!(nonce, 0u32);
-    }
+fn ensure_dispatch_enabled(storage: &dyn Storage) -> Result<(), ContractError> {
+    ensure!(
+        !hpl_pausable::get_pause_info(storage)?,
+        ContractError::Paused {}
+    );
+    ensure!(
+        !is_target_paused(storage, &PauseTarget::Dispatch {})?,
+        ContractError::DispatchPaused {}
+    );
 
-    #[rstest]
-    #[case(addr(OWNER), addr("default_ism"))]
-    #[should_panic(expected = "unauthorized")]
-    #[case(addr(NOT_OWNER), addr("default_ism"))]
-    fn test_set_default_ism(
-        mut deps: TestDeps,
-        #[case] sender: Addr,
-        #[case] new_default_ism: Addr,
-    ) {
-        let res = set_default_ism(
-            deps.as_mut(),
-            mock_info(sender.as_str(), &[]),
+    Ok(())
+}
+
+fn ensure_process_enabled(storage: &dyn Storage, origin_domain: u32) -> Result<(), ContractError> {
+    ensure!(
+        !hpl_pausable::get_pause_info(storage)?,
+        ContractError::Paused {}
+    );
+    ensure!(
+        !is_target_paused(storage, &PauseTarget::Process {})?,
+        ContractError::ProcessPaused {}
+    );
+    ensure!(
+        !is_target_paused(
+            storage,
+            &PauseTarget::Origin {
+                domain: origin_domain
+            }
+        )?,
+        ContractError::OriginPaused {
+            domain: origin_domain
+        }
+    );
+
+    Ok(())
+}
+
+fn save_dispatch(
+    storage: &mut dyn Storage,
+    nonce: u32,
+    dispatch: Dispatch,
+) -> Result<(), ContractError> {
+    DISPATCH_NONCES.save(storage, dispatch.id.to_vec(), &nonce)?;
+    DISPATCHES.save(storage, nonce, &dispatch)?;
+
+    // drop the oldest entries outside the retention window
+    if let Some(retention) = DISPATCH_RETENTION.may_load(storage)? {
+        if nonce >= retention {
+            let window_start = nonce - retention + 1;
+
+            let expired = DISPATCHES
+                .range(
+                    storage,
+                    None,
+                    Some(Bound::exclusive(window_start)),
+                    Order::Ascending,
+                )
+                .take(DISPATCH_EVICTIONS)
+                .collect::<StdResult<Vec<_>>>()?;
+
+            for (expired_nonce, expired_dispatch) in expired {
+                DISPATCHES.remove(storage, expired_nonce);
+                DISPATCH_NONCES.remove(storage, expired_dispatch.id.to_vec());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Message id, fees, hook calls and events of a single dispatch. Locally delivered
+/// messages pay their value to the recipient instead of a fee to the default hook.
+type DispatchOutcome = (HexBinary, Vec<Coin>, Vec<SubMsg>, Vec<Event>);
+
+/// Allocates the next nonce to `dispatch_msg`, records it and builds the hook calls.
+/// `msg_sender` is the 32-byte sender written into the message, while `sender` is the
+/// account that submitted the dispatch.
+/// Returns the message id, the fees paid to the hooks, and the resulting messages and events.
+fn dispatch_message(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    msg_sender: HexBinary,
+    config: &Config,
+    dispatch_msg: DispatchMsg,
+) -> Result<DispatchOutcome, ContractError> {
+    let nonce = NONCE.load(deps.storage)?;
+
+    ensure!(
+        dispatch_msg.recipient_addr.len() == 32,
+        ContractError::InvalidAddressLength {
+            len: dispatch_msg.recipient_addr.len()
+        }
+    );
+
+    let msg = dispatch_msg.clone().to_msg_with_sender(
+        MAILBOX_VERSION,
+        nonce,
+        config.local_domain,
+        msg_sender,
+    );
+    let msg_id = msg.id();
+
+    let hook_metadata = dispatch_msg.metadata.clone().unwrap_or_default();
+    let msg_value = StandardHookMetadata::parse(&hook_metadata)
+        .map(|v| v.msg_value)
+        .unwrap_or_default();
+
+    // the value carried by the body is what the destination enforces, so the value
+    // announced to the relayer must agree with it. a value announced without a body
+    // carrying it could never be delivered, so it's rejected as well.
+    let body_value = MessageValue::parse(&msg.body).map(|v| v.value);
+    let body = body_value
+        .as_ref()
+        .map(|v| Uint256::from(v.amount))
+        .unwrap_or_default();
+    ensure_eq!(
+        msg_value,
+        body,
+        ContractError::DispatchValueMismatch {
+            metadata: msg_value,
+            body
+        }
+    );
+
+    let required_hook = config.get_required_hook()?;
+
+    // with local delivery enabled, local-domain messages skip the default hook
+    // and are handed to the recipient within this transaction
+    if msg.dest_domain == config.local_domain
+        && LOCAL_DELIVERY.may_load(deps.storage)?.unwrap_or_default()
+    {
+        let quote = get_local_dispatch_quote(
+            deps.as_ref(),
+            required_hook.clone(),
+            hook_metadata.clone(),
+            msg.clone(),
+        )?;
+
+        save_dispatched(deps.storage, env, nonce, sender, &msg)?;
+
+        let post_msg = SubMsg::new(post_dispatch(
+            required_hook,
+            hook_metadata,
+            msg.clone(),
+            to_hook_funds(quote.required_hook_fee),
+        )?);
+
+        // the dispatcher pays the value straight to the recipient
+        let (handle_msg, process_events) =
+            deliver_local(deps, env, sender, config, msg.clone(), message_value(&msg))?;
+
+        let mut events = vec![emit_dispatch_id(msg_id.clone()), emit_dispatch(msg)];
+        events.extend(process_events);
+
+        return Ok((msg_id, quote.total, vec![post_msg, handle_msg], events));
+    }
+
+    let hook = dispatch_msg.get_hook_addr(deps.api, config.get_default_hook()?)?;
+
+    // calculate gas. each hook is quoted independently and receives exactly its quote.
+    let quote = get_dispatch_quote(
+        deps.as_ref(),
+        required_hook.clone(),
+        hook.clone(),
+        hook_metadata.clone(),
+        msg.clone(),
+    )?;
+
+    // effects
+    save_dispatched(deps.storage, env, nonce, sender, &msg)?;
+
+    // make message. a hook set as both hooks is posted to once.
+    let mut msgs = vec![SubMsg::new(post_dispatch(
+        required_hook.clone(),
+        hook_metadata.clone(),
+        msg.clone(),
+        to_hook_funds(quote.required_hook_fee),
+    )?)];
+    if hook != required_hook {
+        msgs.push(SubMsg::new(post_dispatch(
+            hook,
+            hook_metadata,
+            msg.clone(),
+            to_hook_funds(quote.hook_fee),
+        )?));
+    }
+
+    let mut events = vec![emit_dispatch_id(msg_id.clone()), emit_dispatch(msg)];
+
+    // value to be attached by the relayer when the message is processed on the destination
+    if let Some(value) = body_value.filter(|v| !v.amount.is_zero()) {
+        events.push(emit_dispatch_value(msg_id.clone(), value));
+    }
+
+    Ok((msg_id, quote.total, msgs, events))
+}
+
+fn save_dispatched(
+    storage: &mut dyn Storage,
+    env: &Env,
+    nonce: u32,
+    sender: &Addr,
+    msg: &Message,
+) -> Result<(), ContractError> {
+    let msg_id = msg.id();
+
+    NONCE.save(storage, &(nonce + 1))?;
+    LATEST_DISPATCHED_ID.save(storage, &msg_id.to_vec())?;
+    OUTBOUND_COUNTS
+        .update::<_, ContractError>(storage, msg.dest_domain, |v| Ok(v.unwrap_or_default() + 1))?;
+    save_dispatch(
+        storage,
+        nonce,
+        Dispatch {
+            id: msg_id,
+            message: msg.clone().into(),
+            sender: sender.clone(),
+            dest_domain: msg.dest_domain,
+            block_number: env.block.height,
+        },
+    )
+}
+
+/// Delivers a local-domain message directly. There is no relayer and no ISM involved,
+/// so the dispatcher is recorded as the sender of the delivery.
+fn deliver_local(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    config: &Config,
+    msg: Message,
+    value: Vec<Coin>,
+) -> Result<(SubMsg, Vec<Event>), ContractError> {
+    ensure_process_enabled(deps.storage, msg.origin_domain)?;
+
+    let id = msg.id();
+    let recipient = msg.recipient_addr(&config.hrp)?;
+
+    save_delivery(
+        deps.storage,
+        &id,
+        Delivery {
+            sender: sender.clone(),
+            block_number: env.block.height,
+            block_time: Some(env.block.time),
+            ism: None,
+            recipient: Some(recipient),
+            origin_domain: Some(msg.origin_domain),
+            value: value.clone(),
+        },
+    )?;
+
+    let handle_msg = handle_submsg(deps.storage, &config.hrp, msg.clone(), value, false)?;
+
+    Ok((
+        handle_msg,
+        vec![
+            emit_process_id(id),
+            emit_process(config.local_domain, msg.sender, msg.recipient),
+        ],
+    ))
+}
+
+/// Records a delivery along with its origin and recipient indexes.
+fn save_delivery(
+    storage: &mut dyn Storage,
+    id: &HexBinary,
+    delivery: Delivery,
+) -> Result<(), ContractError> {
+    if let Some(origin_domain) = delivery.origin_domain {
+        DELIVERIES_BY_ORIGIN.save(storage, (origin_domain, id.to_vec()), &Empty {})?;
+        INBOUND_COUNTS.update::<_, ContractError>(storage, origin_domain, |v| {
+            Ok(v.unwrap_or_default() + 1)
+        })?;
+    }
+    if let Some(recipient) = delivery.recipient.clone() {
+        DELIVERIES_BY_RECIPIENT.save(storage, (recipient, id.to_vec()), &Empty {})?;
+    }
+    DELIVERIES.save(storage, id.to_vec(), &delivery)?;
+
+    Ok(())
+}
+
+fn refund_msg(to_address: Addr, surplus: Vec<Coin>) -> Option<CosmosMsg> {
+    if surplus.is_empty() {
+        return None;
+    }
+
+    Some(
+        BankMsg::Send {
+            to_address: to_address.into(),
+            amount: surplus,
+        }
+        .into(),
+    )
+}
+
+pub fn dispatch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    dispatch_msg: DispatchMsg,
+) -> Result<Response, ContractError> {
+    ensure_dispatch_enabled(deps.storage)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let refund_addr = dispatch_msg.get_refund_addr(deps.api, info.sender.clone())?;
+
+    let msg_sender = bech32_to_h256(info.sender.as_str())?.to_vec().into();
+
+    let (msg_id, fees, msgs, events) = dispatch_message(
+        deps.branch(),
+        &env,
+        &info.sender,
+        msg_sender,
+        &config,
+        dispatch_msg,
+    )?;
+    LATEST_DISPATCH_BATCH.save(deps.storage, &vec![msg_id.clone()])?;
+    let surplus = get_surplus(&info.funds, &fees)?;
+
+    deps.api.debug(&format!(
+        "mailbox::dispatch: required: {:?}, received: {:?}",
+        fees, info.funds
+    ));
+
+    Ok(Response::new()
+        .add_events(events)
+        .set_data(to_binary(&DispatchResponse { message_id: msg_id })?)
+        .add_submessages(msgs)
+        .add_messages(refund_msg(refund_addr, surplus)))
+}
+
+/// Dispatches every message with consecutive nonces, paying all hooks from one payment.
+pub fn dispatch_many(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    dispatch_many_msg: DispatchManyMsg,
+) -> Result<Response, ContractError> {
+    ensure_dispatch_enabled(deps.storage)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let dispatch_msgs = dispatch_many_msg.into_msgs();
+
+    let refund_addr = dispatch_msgs
+        .first()
+        .ok_or(ContractError::EmptyBatch {})?
+        .get_refund_addr(deps.api, info.sender.clone())?;
+
+    // the surplus is refunded once for the whole batch
+    for dispatch_msg in dispatch_msgs.iter().skip(1) {
+        ensure_eq!(
+            dispatch_msg.get_refund_addr(deps.api, info.sender.clone())?,
+            refund_addr,
+            ContractError::RefundAddressMismatch {}
+        );
+    }
+
+    let msg_sender = HexBinary::from(bech32_to_h256(info.sender.as_str())?.to_vec());
+
+    let mut resp = Response::new();
+    let mut message_ids = Vec::with_capacity(dispatch_msgs.len());
+    let mut total_fees = vec![];
+
+    for dispatch_msg in dispatch_msgs {
+        let (msg_id, fees, msgs, events) = dispatch_message(
+            deps.branch(),
+            &env,
+            &info.sender,
+            msg_sender.clone(),
+            &config,
+            dispatch_msg,
+        )?;
+
+        merge_fees(&mut total_fees, &fees);
+        message_ids.push(msg_id);
+        resp = resp.add_events(events).add_submessages(msgs);
+    }
+
+    LATEST_DISPATCH_BATCH.save(deps.storage, &message_ids)?;
+    let surplus = get_surplus(&info.funds, &total_fees)?;
+
+    Ok(resp
+        .set_data(to_binary(&DispatchManyResponse { message_ids })?)
+        .add_messages(refund_msg(refund_addr, surplus)))
+}
+
+/// Dispatches on behalf of `signer` once its signature over the message and its next
+/// nonce checks out. The submitter pays the hook fees and receives the surplus by default.
+pub fn dispatch_signed(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    signer: HexBinary,
+    nonce: u64,
+    signature: HexBinary,
+    dispatch_msg: DispatchMsg,
+) -> Result<Response, ContractError> {
+    ensure_dispatch_enabled(deps.storage)?;
+
+    ensure!(
+        signer.len() == 20,
+        ContractError::InvalidAddressLength { len: signer.len() }
+    );
+
+    let expected = SIGNED_NONCES
+        .may_load(deps.storage, signer.to_vec())?
+        .unwrap_or_default();
+    ensure_eq!(
+        nonce,
+        expected,
+        ContractError::InvalidSignedNonce {
+            expected,
+            got: nonce
+        }
+    );
+
+    let config = CONFIG.load(deps.storage)?;
+    let mailbox = bech32_to_h256(env.contract.address.as_str())?
+        .to_vec()
+        .into();
+
+    // verify the signature against the domain-separated digest
+    let digest = dispatch_msg.signed_digest(config.local_domain, &mailbox, &signer, nonce)?;
+    ensure_eq!(
+        recover_eth_addr(deps.api, &digest, &signature)?,
+        signer,
+        ContractError::InvalidSignature {}
+    );
+
+    SIGNED_NONCES.save(deps.storage, signer.to_vec(), &(nonce + 1))?;
+
+    let refund_addr = dispatch_msg.get_refund_addr(deps.api, info.sender.clone())?;
+
+    // ethereum addresses are left-padded to 32 bytes, as on the other hyperlane deployments
+    let mut msg_sender = [0u8; 32];
+    msg_sender[12..].copy_from_slice(&signer);
+
+    let (msg_id, fees, msgs, events) = dispatch_message(
+        deps.branch(),
+        &env,
+        &info.sender,
+        msg_sender.to_vec().into(),
+        &config,
+        dispatch_msg,
+    )?;
+    LATEST_DISPATCH_BATCH.save(deps.storage, &vec![msg_id.clone()])?;
+    let surplus = get_surplus(&info.funds, &fees)?;
+
+    Ok(Response::new()
+        .add_event(emit_dispatch_signed(signer, nonce, info.sender))
+        .add_events(events)
+        .set_data(to_binary(&DispatchResponse { message_id: msg_id })?)
+        .add_submessages(msgs)
+        .add_messages(refund_msg(refund_addr, surplus)))
+}
+
+/// Builds the recipient's handle call. A body carrying value is unwrapped,
+/// so the recipient only receives its own payload along with the funds.
+fn handle_msg(hrp: &str, msg: Message, value: Vec<Coin>) -> Result<WasmMsg, ContractError> {
+    let body = MessageValue::parse(&msg.body)
+        .map(|v| v.body)
+        .unwrap_or_else(|| msg.body.clone());
+
+    Ok(wasm_execute(
+        msg.recipient_addr(hrp)?,
+        &HandleMsg {
+            origin: msg.origin_domain,
+            sender: msg.sender,
+            body,
+        }
+        .wrap(),
+        value,
+    )?)
+}
+
+/// Wraps the recipient's handle call. If handle isolation is enabled or `isolate` is set,
+/// the call is dispatched with a reply so that a failing recipient doesn't revert the delivery.
+fn handle_submsg(
+    storage: &mut dyn Storage,
+    hrp: &str,
+    msg: Message,
+    value: Vec<Coin>,
+    isolate: bool,
+) -> Result<SubMsg, ContractError> {
+    let handle_msg = handle_msg(hrp, msg.clone(), value.clone())?;
+
+    if !isolate && !HANDLE_ISOLATION.may_load(storage)?.unwrap_or_default() {
+        return Ok(SubMsg::new(handle_msg));
+    }
+
+    let reply_id = HANDLE_REPLY_ID.may_load(storage)?.unwrap_or_default();
+    HANDLE_REPLY_ID.save(storage, &(reply_id + 1))?;
+    PENDING_HANDLES.save(
+        storage,
+        reply_id,
+        &PendingHandle {
+            message: msg.into(),
+            value,
+            batch_index: None,
+        },
+    )?;
+
+    Ok(SubMsg::reply_always(handle_msg, reply_id))
+}
+
+pub fn set_handle_isolation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
+    );
+
+    let old_enabled = HANDLE_ISOLATION.may_load(deps.storage)?.unwrap_or_default();
+
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "set_handle_isolation",
+        Some(old_enabled),
+        Some(enabled),
+    )?;
+    HANDLE_ISOLATION.save(deps.storage, &enabled)?;
+
+    Ok(Response::new().add_event(emit_handle_isolation_set(info.sender, enabled)))
+}
+
+pub fn set_local_delivery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
+    );
+
+    let old_enabled = LOCAL_DELIVERY.may_load(deps.storage)?.unwrap_or_default();
+
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "set_local_delivery",
+        Some(old_enabled),
+        Some(enabled),
+    )?;
+    LOCAL_DELIVERY.save(deps.storage, &enabled)?;
+
+    Ok(Response::new().add_event(emit_local_delivery_set(info.sender, enabled)))
+}
+
+pub fn set_ism_fallback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
+    );
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let old_enabled = ISM_FALLBACKS.has(deps.storage, recipient.clone());
+
+    audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        &format!("set_ism_fallback:{recipient}"),
+        Some(old_enabled),
+        Some(enabled),
+    )?;
+    if enabled {
+        ISM_FALLBACKS.save(deps.storage, recipient.clone(), &Empty {})?;
+    } else {
+        ISM_FALLBACKS.remove(deps.storage, recipient.clone());
+    }
+
+    Ok(Response::new().add_event(emit_ism_fallback_set(info.sender, recipient, enabled)))
+}
+
+pub fn handle_reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
+    let pending = PENDING_HANDLES
+        .may_load(deps.storage, reply.id)?
+        .ok_or(ContractError::UnknownReplyId { id: reply.id })?;
+    PENDING_HANDLES.remove(deps.storage, reply.id);
+
+    let error = match reply.result {
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
+        SubMsgResult::Err(error) => error,
+    };
+
+    let id = Message::from(pending.message.clone()).id();
+
+    deps.api.debug(&format!(
+        "mailbox::reply: handle failed for {}: {}",
+        id.to_hex(),
+        error
+    ));
+
+    FAILED_DELIVERIES.save(
+        deps.storage,
+        id.to_vec(),
+        &FailedDelivery {
+            message: pending.message,
+            error: error.clone(),
+            block_number: env.block.height,
+            value: pending.value,
+        },
+    )?;
+
+    let mut resp = Response::new().add_event(emit_handle_failed(id, error.clone()));
+
+    // the data of a reply replaces the data of the batch, so the rewritten results
+    // are reported in place of the ones set by process_batch
+    if let Some(index) = pending.batch_index {
+        let mut results = LATEST_PROCESS_BATCH.load(deps.storage)?;
+        if let Some(result) = results.get_mut(index as usize) {
+            result.success = false;
+            result.error = Some(error);
+        }
+        LATEST_PROCESS_BATCH.save(deps.storage, &results)?;
+
+        resp = resp.set_data(to_binary(&ProcessBatchResponse { results })?);
+    }
+
+    Ok(resp)
+}
+
+pub fn retry_handle(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: HexBinary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let failed = FAILED_DELIVERIES
+        .may_load(deps.storage, id.to_vec())?
+        .ok_or(ContractError::FailedDeliveryNotFound {})?;
+    FAILED_DELIVERIES.remove(deps.storage, id.to_vec());
+
+    let message: Message = failed.message.into();
+
+    ensure_process_enabled(deps.storage, message.origin_domain)?;
+
+    // executed as a plain message. if the recipient fails again, the whole retry
+    // reverts and the failed delivery stays in place for another attempt.
+    let handle_msg = handle_msg(&config.hrp, message, failed.value)?;
+
+    Ok(Response::new()
+        .add_message(handle_msg)
+        .add_event(emit_handle_retried(info.sender, id)))
+}
+
+fn process_message(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    metadata: HexBinary,
+    message: HexBinary,
+    value: Vec<Coin>,
+    isolate: bool,
+) -> Result<(SubMsg, Vec<Event>), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let decoded_msg = Message::parse(&message)
+        .ok_or(ContractError::InvalidMessageLength { len: message.len() })?;
+    let recipient = decoded_msg.recipient_addr(&config.hrp)?;
+
+    ensure_process_enabled(deps.storage, decoded_msg.origin_domain)?;
+
+    ensure_eq!(
+        decoded_msg.version,
+        MAILBOX_VERSION,
+        ContractError::InvalidMessageVersion {
+            version: decoded_msg.version
+        }
+    );
+    ensure_eq!(
+        decoded_msg.dest_domain,
+        config.local_domain,
+        ContractError::InvalidDestinationDomain {
+            domain: decoded_msg.dest_domain
+        }
+    );
+
+    let id = decoded_msg.id();
+    let ism = resolve_ism(
+        deps.as_ref(),
+        &config,
+        &recipient,
+        Some(decoded_msg.origin_domain),
+    )?;
+
+    deps.api.debug(&format!("mailbox::process: ism: {}", &ism));
+
+    ensure!(
+        !DELIVERIES.has(deps.storage, id.to_vec()),
+        ContractError::AlreadyDeliveredMessage {}
+    );
+
+    let verify = ism::verify(
+        &deps.querier,
+        ism.clone(),
+        metadata,
+        decoded_msg.clone().into(),
+    )?;
+
+    deps.api
+        .debug(&format!("mailbox::process: verify: {}", verify));
+
+    ensure!(verify, ContractError::VerifyFailed {});
+
+    // the value is bound to the verified message, down to the denom
+    let expected = message_value(&decoded_msg);
+    ensure_eq!(
+        value,
+        expected,
+        ContractError::ProcessValueMismatch {
+            expected,
+            received: value
+        }
+    );
+
+    // every check has passed. state is only touched from here,
+    // so a failed message never leaves a partial write behind in batch mode.
+    save_delivery(
+        deps.storage,
+        &id,
+        Delivery {
+            sender: sender.clone(),
+            block_number: env.block.height,
+            block_time: Some(env.block.time),
+            ism: Some(ism),
+            recipient: Some(recipient),
+            origin_domain: Some(decoded_msg.origin_domain),
+            value: value.clone(),
+        },
+    )?;
+
+    let handle_msg = handle_submsg(
+        deps.storage,
+        &config.hrp,
+        decoded_msg.clone(),
+        value,
+        isolate,
+    )?;
+
+    Ok((
+        handle_msg,
+        vec![
+            emit_process_id(id),
+            emit_process(
+                config.local_domain,
+                decoded_msg.sender,
+                decoded_msg.recipient,
+            ),
+        ],
+    ))
+}
+
+pub fn process(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    metadata: HexBinary,
+    message: HexBinary,
+) -> Result<Response, ContractError> {
+    // attached funds carry the message value to the recipient
+    let (handle_msg, events) = process_message(
+        deps,
+        &env,
+        &info.sender,
+        metadata,
+        message,
+        info.funds,
+        false,
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(handle_msg)
+        .add_events(events))
+}
+
+pub fn process_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<ProcessMsg>,
+    skip_failed: bool,
+) -> Result<Response, ContractError> {
+    ensure!(!msgs.is_empty(), ContractError::EmptyBatch {});
+    // funds can't be attributed to a single message of the batch,
+    // so messages carrying value have to be processed on their own
+    cw_utils::nonpayable(&info)?;
+
+    let mut resp = Response::new();
+    let mut results = Vec::with_capacity(msgs.len());
+    let mut isolated = false;
+
+    for (index, ProcessMsg { metadata, message }) in msgs.into_iter().enumerate() {
+        // hashed as-is, so malformed messages can still be reported
+        let id = keccak256_hash(&message);
+
+        // with skip_failed, a failing recipient is recorded as a failed delivery
+        // instead of reverting the rest of the batch
+        match process_message(
+            deps.branch(),
+            &env,
+            &info.sender,
+            metadata,
+            message,
+            vec![],
+            skip_failed,
+        ) {
+            Ok((handle_msg, events)) => {
+                // the reply of a failing handle rewrites the result of its message
+                if handle_msg.reply_on == ReplyOn::Always {
+                    PENDING_HANDLES.update::<_, ContractError>(
+                        deps.storage,
+                        handle_msg.id,
+                        |pending| {
+                            let mut pending = pending
+                                .ok_or(ContractError::UnknownReplyId { id: handle_msg.id })?;
+                            pending.batch_index = Some(index as u32);
+                            Ok(pending)
+                        },
+                    )?;
+                    isolated = true;
+                }
+
+                resp = resp.add_submessage(handle_msg).add_events(events);
+                results.push(ProcessResult {
+                    message_id: id,
+                    success: true,
+                    error: None,
+                });
+            }
+            Err(err) if skip_failed => {
+                deps.api.debug(&format!(
+                    "mailbox::process_batch: skipped {}: {}",
+                    id.to_hex(),
+                    err
+                ));
+
+                resp = resp.add_event(emit_process_failed(id.clone(), err.to_string()));
+                results.push(ProcessResult {
+                    message_id: id,
+                    success: false,
+                    error: Some(err.to_string()),
+                });
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    if isolated {
+        LATEST_PROCESS_BATCH.save(deps.storage, &results)?;
+    }
+
+    Ok(resp.set_data(to_binary(&ProcessBatchResponse { results })?))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        Addr, ContractResult, OwnedDeps, QuerierResult, SubMsgResponse, SystemResult, WasmQuery,
+    };
+
+    use hpl_interface::{
+        core::mailbox::{DispatchTarget, InstantiateMsg},
+        hook::{ExpectedHookQueryMsg, HookQueryMsg, QuoteDispatchResponse},
+        ism::IsmQueryMsg,
+        ownable::AuditEntry,
+        types::{bech32_encode, eth_addr},
+    };
+    use ibcx_test_utils::{addr, gen_bz};
+    use k256::ecdsa::SigningKey;
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    use crate::contract::instantiate;
+
+    const OWNER: &str = "owner";
+    const NOT_OWNER: &str = "not_owner";
+
+    const LOCAL_DOMAIN: u32 = 26657;
+    const DEST_DOMAIN: u32 = 11155111;
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn mock_query_handler(req: &WasmQuery) -> QuerierResult {
+        let (req, contract_addr) = match req {
+            WasmQuery::Smart { msg, contract_addr } => (from_binary(msg).unwrap(), contract_addr),
+            _ => unreachable!("wrong query type"),
+        };
+
+        let req = match req {
+            ExpectedHookQueryMsg::Hook(HookQueryMsg::QuoteDispatch(msg)) => msg,
+            _ => unreachable!("wrong query type"),
+        };
+
+        let mut gas_amount = None;
+
+        // standard metadata only carries the message value here, the hooks are free
+        if !req.metadata.is_empty() && StandardHookMetadata::parse(&req.metadata).is_none() {
+            let parsed_gas = u32::from_be_bytes(req.metadata.as_slice().try_into().unwrap());
+
+            // the required hook charges in a different denom than the others
+            let denom = if contract_addr == "required_hook" {
+                "uatom"
+            } else {
+                "utest"
+            };
+
+            gas_amount = Some(coin(parsed_gas as u128, denom));
+        }
+
+        let res = QuoteDispatchResponse { gas_amount };
+        let res = cosmwasm_std::to_binary(&res).unwrap();
+        SystemResult::Ok(ContractResult::Ok(res))
+    }
+
+    #[fixture]
+    fn deps(#[default("deployer")] sender: &str) -> TestDeps {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            InstantiateMsg {
+                hrp: "osmo".to_string(),
+                owner: OWNER.to_string(),
+                domain: LOCAL_DOMAIN,
+            },
+        )
+        .unwrap();
+
+        deps
+    }
+
+    #[rstest]
+    fn test_init(deps: TestDeps) {
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+
+        assert_eq!(config.hrp, "osmo");
+        assert_eq!(config.local_domain, LOCAL_DOMAIN);
+        assert_eq!(config.default_ism, None);
+        assert_eq!(config.default_hook, None);
+        assert_eq!(config.required_hook, None);
+
+        let nonce = NONCE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(nonce, 0u32);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), addr("default_ism"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), addr("default_ism"))]
+    fn test_set_default_ism(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] new_default_ism: Addr,
+    ) {
+        let res = set_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
             new_default_ism.to_string(),
         )
         .map_err(|e| e.to_string())
         .unwrap();
 
         assert_eq!(
-            res,
-            Response::new().add_event(emit_default_ism_set(sender, new_default_ism))
+            res,
+            Response::new().add_event(emit_default_ism_set(sender, new_default_ism))
+        );
+    }
+
+    #[rstest]
+    fn test_audit_log(mut deps: TestDeps) {
+        let mut env = mock_env();
+        let owner = addr(OWNER);
+
+        set_default_ism(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            "ism_a".into(),
+        )
+        .unwrap();
+
+        env.block.height += 1;
+        set_default_ism(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            "ism_b".into(),
+        )
+        .unwrap();
+        pause(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            PauseTarget::Origin { domain: 1 },
+        )
+        .unwrap();
+
+        // rejected changes are not recorded
+        set_default_ism(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(NOT_OWNER, &[]),
+            "ism_c".into(),
+        )
+        .unwrap_err();
+
+        let entries = hpl_ownable::get_audit_log(deps.as_ref().storage, None, None, None).unwrap();
+        let entry = |id, action: &str, old: Option<&str>, new: Option<&str>, height| AuditEntry {
+            id,
+            action: action.to_string(),
+            actor: owner.clone(),
+            old_value: old.map(|v| v.to_string()),
+            new_value: new.map(|v| v.to_string()),
+            height,
+        };
+
+        let height = mock_env().block.height;
+        assert_eq!(
+            entries,
+            vec![
+                entry(0, "set_default_ism", None, Some("ism_a"), height),
+                entry(
+                    1,
+                    "set_default_ism",
+                    Some("ism_a"),
+                    Some("ism_b"),
+                    height + 1
+                ),
+                entry(
+                    2,
+                    "paused:origin:1",
+                    Some("false"),
+                    Some("true"),
+                    height + 1
+                ),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), addr("default_hook"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), addr("default_hook"))]
+    fn test_set_default_hook(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] new_default_hook: Addr,
+    ) {
+        let res = set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            new_default_hook.to_string(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_default_hook_set(sender, new_default_hook))
+        )
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), addr("required_hook"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), addr("required_hook"))]
+    fn test_set_required_hook(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] new_required_hook: Addr,
+    ) {
+        let res = set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            new_required_hook.to_string(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_required_hook_set(sender, new_required_hook))
+        )
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), addr("recipient"), addr("recipient_ism"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), addr("recipient"), addr("recipient_ism"))]
+    fn test_set_recipient_ism(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] recipient: Addr,
+        #[case] ism: Addr,
+    ) {
+        let res = set_recipient_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            recipient.to_string(),
+            ism.to_string(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_recipient_ism_set(
+                sender.clone(),
+                recipient.clone(),
+                ism.clone()
+            ))
+        );
+        assert_eq!(
+            RECIPIENT_ISMS
+                .load(deps.as_ref().storage, recipient.clone())
+                .unwrap(),
+            ism
+        );
+
+        let res = unset_recipient_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            recipient.to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_recipient_ism_unset(sender.clone(), recipient.clone()))
+        );
+        assert!(!RECIPIENT_ISMS.has(deps.as_ref().storage, recipient.clone()));
+
+        let err = unset_recipient_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            recipient.to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::RecipientIsmNotSet {});
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), DEST_DOMAIN, addr("origin_ism"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), DEST_DOMAIN, addr("origin_ism"))]
+    fn test_set_origin_default_ism(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] origin: u32,
+        #[case] ism: Addr,
+    ) {
+        let res = set_origin_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            origin,
+            ism.to_string(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_origin_default_ism_set(
+                sender.clone(),
+                origin,
+                ism.clone()
+            ))
+        );
+        assert_eq!(
+            ORIGIN_DEFAULT_ISMS
+                .load(deps.as_ref().storage, origin)
+                .unwrap(),
+            ism
+        );
+
+        let res = unset_origin_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            origin,
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_origin_default_ism_unset(sender.clone(), origin))
+        );
+        assert!(!ORIGIN_DEFAULT_ISMS.has(deps.as_ref().storage, origin));
+
+        let err = unset_origin_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            origin,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::OriginDefaultIsmNotSet { origin });
+    }
+
+    #[rstest]
+    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(32))]
+    #[should_panic(expected = "invalid address length: 33")]
+    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(33))]
+    fn test_dispatch(
+        #[values("osmo", "neutron")] hrp: &str,
+        #[case] dest_domain: u32,
+        #[case] sender: HexBinary,
+        #[case] recipient_addr: HexBinary,
+    ) {
+        let sender = bech32_encode(hrp, sender.as_slice()).unwrap();
+        let msg_body = gen_bz(123);
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(mock_query_handler);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg {
+                hrp: hrp.to_string(),
+                owner: OWNER.to_string(),
+                domain: LOCAL_DOMAIN,
+            },
+        )
+        .unwrap();
+
+        set_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_ism".into(),
+        )
+        .unwrap();
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+
+        let dispatch_msg = DispatchMsg::new(dest_domain, recipient_addr, msg_body)
+            .with_metadata(1500u32.to_be_bytes().to_vec());
+
+        let msg = dispatch_msg
+            .clone()
+            .to_msg(
+                MAILBOX_VERSION,
+                NONCE.load(deps.as_ref().storage).unwrap(),
+                LOCAL_DOMAIN,
+                &sender,
+            )
+            .unwrap();
+
+        let res = dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[coin(1500, "uatom"), coin(1500, "utest")]),
+            dispatch_msg,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(
+                    post_dispatch(
+                        "required_hook",
+                        1500u32.to_be_bytes().to_vec(),
+                        msg.clone(),
+                        Some(vec![coin(1500, "uatom")]),
+                    )
+                    .unwrap()
+                ),
+                SubMsg::new(
+                    post_dispatch(
+                        "default_hook",
+                        1500u32.to_be_bytes().to_vec(),
+                        msg.clone(),
+                        Some(vec![coin(1500, "utest")]),
+                    )
+                    .unwrap()
+                ),
+            ]
+        );
+
+        assert_eq!(NONCE.load(deps.as_ref().storage).unwrap(), 1u32);
+        assert_eq!(
+            LATEST_DISPATCHED_ID.load(deps.as_ref().storage).unwrap(),
+            msg.id().to_vec()
+        );
+
+        let stored = DISPATCHES.load(deps.as_ref().storage, 0).unwrap();
+        assert_eq!(stored.id, msg.id());
+        assert_eq!(stored.message, HexBinary::from(msg.clone()));
+        assert_eq!(stored.sender, sender);
+        assert_eq!(stored.dest_domain, dest_domain);
+        assert_eq!(stored.block_number, mock_env().block.height);
+        assert_eq!(
+            DISPATCH_NONCES
+                .load(deps.as_ref().storage, msg.id().to_vec())
+                .unwrap(),
+            0
+        );
+    }
+
+    #[rstest]
+    #[case(vec![coin(1500, "uatom"), coin(1500, "utest")], None, vec![])]
+    #[case(
+        vec![coin(1600, "uatom"), coin(1500, "utest"), coin(10, "uosmo")],
+        None,
+        vec![coin(100, "uatom"), coin(10, "uosmo")]
+    )]
+    #[case(
+        vec![coin(1500, "uatom"), coin(2000, "utest")],
+        Some("refund"),
+        vec![coin(500, "utest")]
+    )]
+    #[should_panic(expected = "insufficient funds. required: 1500utest, received: 1499utest")]
+    #[case(vec![coin(1500, "uatom"), coin(1499, "utest")], None, vec![])]
+    #[should_panic(expected = "insufficient funds. required: 1500uatom, received: 0uatom")]
+    #[case(vec![coin(3000, "utest")], None, vec![])]
+    fn test_dispatch_fees(
+        mut deps: TestDeps,
+        #[case] funds: Vec<Coin>,
+        #[case] refund_address: Option<&str>,
+        #[case] refund: Vec<Coin>,
+    ) {
+        deps.querier.update_wasm(mock_query_handler);
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+
+        let mut dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123))
+            .with_metadata(1500u32.to_be_bytes().to_vec());
+        if let Some(refund_address) = refund_address {
+            dispatch_msg = dispatch_msg.with_refund_address(refund_address);
+        }
+
+        let res = dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &funds),
+            dispatch_msg,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let refund_msgs = res
+            .messages
+            .into_iter()
+            .filter_map(|v| match v.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => Some((to_address, amount)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if refund.is_empty() {
+            assert!(refund_msgs.is_empty());
+        } else {
+            let to_address = refund_address
+                .map(|v| v.to_string())
+                .unwrap_or(sender.to_string());
+            assert_eq!(refund_msgs, vec![(to_address, refund)]);
+        }
+    }
+
+    #[rstest]
+    fn test_dispatch_required_hook_once(mut deps: TestDeps) {
+        deps.querier.update_wasm(mock_query_handler);
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+
+        // the required hook picked as the target hook is quoted, paid and posted to once
+        let dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123))
+            .with_hook("required_hook")
+            .with_metadata(1500u32.to_be_bytes().to_vec());
+
+        let quote = crate::query::quote_dispatch(deps.as_ref(), dispatch_msg.clone()).unwrap();
+        assert_eq!(quote.required_hook_fee, Some(coin(1500, "uatom")));
+        assert_eq!(quote.hook_fee, None);
+        assert_eq!(quote.total, vec![coin(1500, "uatom")]);
+
+        let res = dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &quote.total),
+            dispatch_msg,
+        )
+        .unwrap();
+
+        let message = Message::from(DISPATCHES.load(deps.as_ref().storage, 0).unwrap().message);
+        assert_eq!(
+            res.messages.into_iter().map(|v| v.msg).collect::<Vec<_>>(),
+            vec![post_dispatch(
+                "required_hook",
+                1500u32.to_be_bytes().to_vec(),
+                message,
+                Some(vec![coin(1500, "uatom")]),
+            )
+            .unwrap()
+            .into()]
+        );
+    }
+
+    #[rstest]
+    #[case(3, vec![coin(4500, "uatom"), coin(4500, "utest")], vec![])]
+    #[case(2, vec![coin(3500, "uatom"), coin(3000, "utest")], vec![coin(500, "uatom")])]
+    #[should_panic(expected = "insufficient funds. required: 4500utest, received: 4499utest")]
+    #[case(3, vec![coin(4500, "uatom"), coin(4499, "utest")], vec![])]
+    #[should_panic(expected = "empty batch")]
+    #[case(0, vec![], vec![])]
+    fn test_dispatch_many(
+        mut deps: TestDeps,
+        #[case] targets: u32,
+        #[case] funds: Vec<Coin>,
+        #[case] refund: Vec<Coin>,
+    ) {
+        deps.querier.update_wasm(mock_query_handler);
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+        let targets = (0..targets)
+            .map(|i| DispatchTarget {
+                dest_domain: DEST_DOMAIN + i,
+                recipient_addr: gen_bz(32),
+            })
+            .collect::<Vec<_>>();
+
+        let res = dispatch_many(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &funds),
+            DispatchManyMsg::Multicast {
+                targets: targets.clone(),
+                msg_body: gen_bz(123),
+                hook: None,
+                metadata: Some(1500u32.to_be_bytes().to_vec().into()),
+                refund_address: None,
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let DispatchManyResponse { message_ids } = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(message_ids.len(), targets.len());
+
+        // consecutive nonces, in the order of the targets
+        for (nonce, (id, target)) in message_ids.iter().zip(targets.iter()).enumerate() {
+            let dispatch = DISPATCHES
+                .load(deps.as_ref().storage, nonce as u32)
+                .unwrap();
+            let message = Message::from(dispatch.message);
+
+            assert_eq!(&dispatch.id, id);
+            assert_eq!(message.dest_domain, target.dest_domain);
+            assert_eq!(message.recipient, target.recipient_addr);
+        }
+
+        assert_eq!(
+            NONCE.load(deps.as_ref().storage).unwrap(),
+            targets.len() as u32
+        );
+        for target in &targets {
+            assert_eq!(
+                OUTBOUND_COUNTS
+                    .load(deps.as_ref().storage, target.dest_domain)
+                    .unwrap(),
+                1
+            );
+        }
+        assert_eq!(
+            LATEST_DISPATCHED_ID.load(deps.as_ref().storage).unwrap(),
+            message_ids.last().unwrap().to_vec()
+        );
+        assert_eq!(
+            LATEST_DISPATCH_BATCH.load(deps.as_ref().storage).unwrap(),
+            message_ids
+        );
+
+        // two hook calls per message, plus the refund
+        let refund_msgs = res.messages[targets.len() * 2..]
+            .iter()
+            .map(|v| v.msg.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            refund_msgs,
+            refund_msg(addr(sender.as_str()), refund)
+                .into_iter()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case(None, None)]
+    #[case(Some("refund"), Some("refund"))]
+    #[should_panic(expected = "refund addresses of a batch must match")]
+    #[case(Some("refund"), None)]
+    #[should_panic(expected = "refund addresses of a batch must match")]
+    #[case(Some("refund"), Some("another_refund"))]
+    fn test_dispatch_many_refund_address(
+        mut deps: TestDeps,
+        #[case] first: Option<&str>,
+        #[case] second: Option<&str>,
+    ) {
+        deps.querier.update_wasm(mock_query_handler);
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+        let msgs = [first, second]
+            .into_iter()
+            .map(|refund_address| {
+                let msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123))
+                    .with_metadata(1500u32.to_be_bytes().to_vec());
+                match refund_address {
+                    Some(v) => msg.with_refund_address(v),
+                    None => msg,
+                }
+            })
+            .collect();
+
+        let res = dispatch_many(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[coin(4000, "uatom"), coin(3000, "utest")]),
+            DispatchManyMsg::Messages { msgs },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let to_address = addr(first.unwrap_or(sender.as_str()));
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            refund_msg(to_address, vec![coin(1000, "uatom")]).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(0u128, None, false)]
+    #[case(1_000_000u128, Some(1_000_000u128), true)]
+    #[should_panic(expected = "dispatch value mismatch. metadata: 1000000, body: 999999")]
+    #[case(1_000_000u128, Some(999_999u128), true)]
+    #[should_panic(expected = "dispatch value mismatch. metadata: 0, body: 1000000")]
+    #[case(0u128, Some(1_000_000u128), false)]
+    #[should_panic(expected = "dispatch value mismatch. metadata: 1000000, body: 0")]
+    #[case(1_000_000u128, None, false)]
+    fn test_dispatch_value(
+        mut deps: TestDeps,
+        #[case] value: u128,
+        #[case] body_value: Option<u128>,
+        #[case] emitted: bool,
+    ) {
+        deps.querier.update_wasm(mock_query_handler);
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+
+        let metadata = StandardHookMetadata {
+            variant: StandardHookMetadata::VARIANT,
+            msg_value: value.into(),
+            gas_limit: 200_000u128.into(),
+            refund_address: gen_bz(20),
+        };
+
+        let body = match body_value {
+            Some(v) => HexBinary::try_from(MessageValue {
+                value: coin(v, "uosmo"),
+                body: gen_bz(123),
+            })
+            .unwrap(),
+            None => gen_bz(123),
+        };
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+
+        let res = dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            DispatchMsg::new(DEST_DOMAIN, gen_bz(32), body)
+                .with_metadata(HexBinary::try_from(metadata).unwrap()),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let DispatchResponse { message_id } = from_binary(&res.data.unwrap()).unwrap();
+
+        assert_eq!(
+            res.events
+                .into_iter()
+                .find(|v| v.ty == "mailbox_dispatch_value"),
+            emitted.then(|| emit_dispatch_value(message_id, coin(value, "uosmo")))
+        );
+    }
+
+    #[rstest]
+    #[case(true, LOCAL_DOMAIN, None, true)]
+    #[case(true, LOCAL_DOMAIN, Some(1000), true)]
+    #[case(true, DEST_DOMAIN, None, false)]
+    #[case(false, LOCAL_DOMAIN, None, false)]
+    fn test_dispatch_local(
+        mut deps: TestDeps,
+        #[case] enabled: bool,
+        #[case] dest_domain: u32,
+        #[case] value: Option<u128>,
+        #[case] local: bool,
+    ) {
+        deps.querier.update_wasm(mock_query_handler);
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+        set_local_delivery(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), enabled).unwrap();
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+        let recipient = gen_bz(32);
+        let funds = vec![coin(1500, "uatom"), coin(1500, "utest")];
+
+        // the value and the required hook's fee are paid from the dispatcher's funds,
+        // the rest is refunded
+        let (dispatch_msg, value, required_hook_fee, surplus) = match value {
+            Some(v) => {
+                let body = HexBinary::try_from(MessageValue {
+                    value: coin(v, "uatom"),
+                    body: gen_bz(123),
+                })
+                .unwrap();
+                let metadata = StandardHookMetadata {
+                    variant: StandardHookMetadata::VARIANT,
+                    msg_value: v.into(),
+                    gas_limit: 200_000u128.into(),
+                    refund_address: gen_bz(20),
+                };
+
+                (
+                    DispatchMsg::new(dest_domain, recipient.clone(), body)
+                        .with_metadata(HexBinary::try_from(metadata).unwrap()),
+                    vec![coin(v, "uatom")],
+                    None,
+                    vec![coin(1500 - v, "uatom"), coin(1500, "utest")],
+                )
+            }
+            None => (
+                DispatchMsg::new(dest_domain, recipient.clone(), gen_bz(123))
+                    .with_metadata(1500u32.to_be_bytes().to_vec()),
+                vec![],
+                Some(vec![coin(1500, "uatom")]),
+                vec![coin(1500, "utest")],
+            ),
+        };
+        let metadata = dispatch_msg.metadata.clone().unwrap_or_default();
+
+        let res = dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &funds),
+            dispatch_msg,
+        )
+        .unwrap();
+
+        let DispatchResponse { message_id } = from_binary(&res.data.unwrap()).unwrap();
+        let delivery = DELIVERIES
+            .may_load(deps.as_ref().storage, message_id.to_vec())
+            .unwrap();
+
+        // dispatches are recorded either way
+        assert_eq!(NONCE.load(deps.as_ref().storage).unwrap(), 1);
+
+        if local {
+            let delivery = delivery.unwrap();
+            assert_eq!(delivery.sender, addr(sender.as_str()));
+            assert_eq!(delivery.ism, None);
+            assert_eq!(delivery.origin_domain, Some(LOCAL_DOMAIN));
+            assert_eq!(delivery.value, value);
+
+            // the required hook still gets the message, the default hook is skipped.
+            // then the recipient handle and a refund of everything but the fee and the value
+            let msgs = res.messages.into_iter().map(|v| v.msg).collect::<Vec<_>>();
+            let message = Message::from(DISPATCHES.load(deps.as_ref().storage, 0).unwrap().message);
+            assert_eq!(
+                msgs,
+                vec![
+                    post_dispatch(
+                        addr("required_hook"),
+                        metadata,
+                        message.clone(),
+                        required_hook_fee
+                    )
+                    .unwrap()
+                    .into(),
+                    handle_msg("osmo", message, value).unwrap().into(),
+                    refund_msg(addr(sender.as_str()), surplus).unwrap(),
+                ]
+            );
+            assert!(res.events.iter().any(|v| v.ty == "mailbox_process"));
+        } else {
+            assert_eq!(delivery, None);
+            assert_eq!(res.messages.len(), 2);
+        }
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(1000))]
+    fn test_dispatch_local_quote(mut deps: TestDeps, #[case] value: Option<u128>) {
+        deps.querier.update_wasm(mock_query_handler);
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+        set_local_delivery(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), true).unwrap();
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+        let recipient = gen_bz(32);
+
+        // the metadata makes the hooks charge when they're called
+        let (dispatch_msg, required_hook_fee) = match value {
+            Some(v) => {
+                let body = HexBinary::try_from(MessageValue {
+                    value: coin(v, "uatom"),
+                    body: gen_bz(123),
+                })
+                .unwrap();
+                let metadata = StandardHookMetadata {
+                    variant: StandardHookMetadata::VARIANT,
+                    msg_value: v.into(),
+                    gas_limit: 200_000u128.into(),
+                    refund_address: gen_bz(20),
+                };
+
+                (
+                    DispatchMsg::new(LOCAL_DOMAIN, recipient, body)
+                        .with_metadata(HexBinary::try_from(metadata).unwrap()),
+                    None,
+                )
+            }
+            None => (
+                DispatchMsg::new(LOCAL_DOMAIN, recipient, gen_bz(123))
+                    .with_metadata(1500u32.to_be_bytes().to_vec()),
+                Some(coin(1500, "uatom")),
+            ),
+        };
+
+        // the quote is the required hook's fee and the body value, the default hook is skipped
+        let quote = crate::query::quote_dispatch(deps.as_ref(), dispatch_msg.clone()).unwrap();
+        assert_eq!(quote.required_hook_fee, required_hook_fee);
+        assert_eq!(quote.hook_fee, None);
+        assert_eq!(
+            quote.total,
+            required_hook_fee
+                .clone()
+                .into_iter()
+                .chain(value.map(|v| coin(v, "uatom")))
+                .collect::<Vec<_>>()
+        );
+
+        // and paying exactly the quote delivers the message without a refund
+        let res = dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &quote.total),
+            dispatch_msg.clone(),
+        )
+        .unwrap();
+
+        let msgs = res.messages.into_iter().map(|v| v.msg).collect::<Vec<_>>();
+        let message = Message::from(DISPATCHES.load(deps.as_ref().storage, 0).unwrap().message);
+        assert_eq!(
+            msgs,
+            vec![
+                post_dispatch(
+                    addr("required_hook"),
+                    dispatch_msg.metadata.unwrap_or_default(),
+                    message.clone(),
+                    required_hook_fee.map(|v| vec![v])
+                )
+                .unwrap()
+                .into(),
+                handle_msg(
+                    "osmo",
+                    message,
+                    value.map(|v| coin(v, "uatom")).into_iter().collect()
+                )
+                .unwrap()
+                .into()
+            ]
+        );
+    }
+
+    fn sign_dispatch(
+        key: &SigningKey,
+        env: &Env,
+        nonce: u64,
+        msg: &DispatchMsg,
+    ) -> (HexBinary, HexBinary) {
+        let pubkey = key.verifying_key().to_encoded_point(false);
+        let signer = eth_addr(pubkey.as_bytes().into()).unwrap();
+
+        let mailbox = bech32_to_h256(env.contract.address.as_str()).unwrap();
+        let digest = msg
+            .signed_digest(LOCAL_DOMAIN, &mailbox.to_vec().into(), &signer, nonce)
+            .unwrap();
+
+        let (sig, recovery_id) = key.sign_prehash_recoverable(&digest).unwrap();
+        let mut signature = sig.to_bytes().to_vec();
+        signature.push(recovery_id.to_byte() + 27);
+
+        (signer, signature.into())
+    }
+
+    #[rstest]
+    #[case(0, None)]
+    #[should_panic(expected = "invalid signed nonce. expected: 0, got: 1")]
+    #[case(1, None)]
+    #[should_panic(expected = "invalid signature")]
+    #[case(0, Some(gen_bz(20)))]
+    fn test_dispatch_signed(
+        mut deps: TestDeps,
+        #[case] nonce: u64,
+        #[case] signer_override: Option<HexBinary>,
+    ) {
+        deps.querier.update_wasm(mock_query_handler);
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+        )
+        .unwrap();
+
+        let mut env = mock_env();
+        env.contract.address = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+
+        let key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+        let msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123));
+        let (signer, signature) = sign_dispatch(&key, &env, nonce, &msg);
+        let signer = signer_override.unwrap_or(signer);
+
+        let submitter = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+
+        let res = dispatch_signed(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(submitter.as_str(), &[]),
+            signer.clone(),
+            nonce,
+            signature.clone(),
+            msg.clone(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let DispatchResponse { message_id } = from_binary(&res.data.unwrap()).unwrap();
+        let dispatch = DISPATCHES.load(deps.as_ref().storage, 0).unwrap();
+        let message = Message::from(dispatch.message);
+
+        // the signer is the message sender, the submitter is recorded on the dispatch
+        assert_eq!(dispatch.id, message_id);
+        assert_eq!(dispatch.sender, submitter);
+        assert_eq!(&message.sender[..12], &[0u8; 12]);
+        assert_eq!(&message.sender[12..], signer.as_slice());
+
+        assert_eq!(
+            SIGNED_NONCES
+                .load(deps.as_ref().storage, signer.to_vec())
+                .unwrap(),
+            nonce + 1
+        );
+
+        // the same signature can't be replayed
+        let err = dispatch_signed(
+            deps.as_mut(),
+            env,
+            mock_info(submitter.as_str(), &[]),
+            signer,
+            nonce,
+            signature,
+            msg,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidSignedNonce { .. }));
+    }
+
+    #[rstest]
+    fn test_set_local_delivery(mut deps: TestDeps) {
+        let err = set_local_delivery(deps.as_mut(), mock_env(), mock_info(NOT_OWNER, &[]), true)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        set_local_delivery(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), true).unwrap();
+        assert!(LOCAL_DELIVERY.load(deps.as_ref().storage).unwrap());
+    }
+
+    #[rstest]
+    fn test_set_ism_fallback(mut deps: TestDeps) {
+        let err = set_ism_fallback(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(NOT_OWNER, &[]),
+            "recipient".into(),
+            true,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        set_ism_fallback(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "recipient".into(),
+            true,
+        )
+        .unwrap();
+        assert!(ISM_FALLBACKS.has(deps.as_ref().storage, addr("recipient")));
+        assert!(!ISM_FALLBACKS.has(deps.as_ref().storage, addr("other")));
+
+        set_ism_fallback(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "recipient".into(),
+            false,
+        )
+        .unwrap();
+        assert!(!ISM_FALLBACKS.has(deps.as_ref().storage, addr("recipient")));
+    }
+
+    #[rstest]
+    fn test_process_batch_nonpayable(mut deps: TestDeps) {
+        let err = process_batch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[coin(100, "uosmo")]),
+            vec![ProcessMsg {
+                metadata: vec![1].into(),
+                message: gen_bz(100),
+            }],
+            false,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::Payment(cw_utils::PaymentError::NonPayable {})
+        );
+    }
+
+    fn mock_dispatch(nonce: u32) -> Dispatch {
+        Dispatch {
+            id: gen_bz(32),
+            message: gen_bz(100),
+            sender: addr("sender"),
+            dest_domain: DEST_DOMAIN,
+            block_number: nonce as u64,
+        }
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), Some(3))]
+    #[case(addr(OWNER), None)]
+    #[should_panic(expected = "invalid dispatch retention: 0")]
+    #[case(addr(OWNER), Some(0))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), Some(3))]
+    fn test_dispatch_retention(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] retention: Option<u32>,
+    ) {
+        set_dispatch_retention(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            retention,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let dispatches = (0..5).map(mock_dispatch).collect::<Vec<_>>();
+        for (nonce, dispatch) in dispatches.iter().enumerate() {
+            save_dispatch(deps.as_mut().storage, nonce as u32, dispatch.clone()).unwrap();
+        }
+
+        let kept = retention.unwrap_or(5) as usize;
+        for (nonce, dispatch) in dispatches.iter().enumerate() {
+            let expected = nonce >= dispatches.len() - kept;
+
+            assert_eq!(
+                DISPATCHES.has(deps.as_ref().storage, nonce as u32),
+                expected
+            );
+            assert_eq!(
+                DISPATCH_NONCES.has(deps.as_ref().storage, dispatch.id.to_vec()),
+                expected
+            );
+        }
+    }
+
+    #[rstest]
+    fn test_dispatch_retention_lowered(mut deps: TestDeps) {
+        for nonce in 0..10 {
+            save_dispatch(deps.as_mut().storage, nonce, mock_dispatch(nonce)).unwrap();
+        }
+
+        set_dispatch_retention(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), Some(3)).unwrap();
+
+        let kept = |deps: &TestDeps| {
+            DISPATCHES
+                .keys(deps.as_ref().storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()
+                .unwrap()
+        };
+
+        // the backlog drains a couple of entries per dispatch
+        save_dispatch(deps.as_mut().storage, 10, mock_dispatch(10)).unwrap();
+        assert_eq!(kept(&deps), (2..=10).collect::<Vec<_>>());
+
+        for nonce in 11..17 {
+            save_dispatch(deps.as_mut().storage, nonce, mock_dispatch(nonce)).unwrap();
+        }
+        assert_eq!(kept(&deps), vec![14, 15, 16]);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), 3, None, 3)]
+    #[case(addr(OWNER), 3, Some(2), 2)]
+    #[case(addr(OWNER), 10, None, 5)]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), 3, None, 3)]
+    fn test_prune_dispatches(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] before_nonce: u32,
+        #[case] limit: Option<u32>,
+        #[case] pruned: u32,
+    ) {
+        for nonce in 0..5 {
+            save_dispatch(deps.as_mut().storage, nonce, mock_dispatch(nonce)).unwrap();
+        }
+
+        let res = prune_dispatches(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            before_nonce,
+            limit,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_dispatches_pruned(sender, before_nonce, pruned))
+        );
+
+        for nonce in 0..5 {
+            assert_eq!(
+                DISPATCHES.has(deps.as_ref().storage, nonce),
+                nonce >= pruned
+            );
+        }
+    }
+
+    fn test_process_query_handler(query: &WasmQuery) -> QuerierResult {
+        match query {
+            WasmQuery::Smart { contract_addr, msg } => {
+                if let Ok(req) = cosmwasm_std::from_binary::<ism::ExpectedIsmSpecifierQueryMsg>(msg)
+                {
+                    match req {
+                        hpl_interface::ism::ExpectedIsmSpecifierQueryMsg::IsmSpecifier(
+                            ism::IsmSpecifierQueryMsg::InterchainSecurityModule(),
+                        ) => {
+                            return SystemResult::Ok(
+                                cosmwasm_std::to_binary(&ism::InterchainSecurityModuleResponse {
+                                    ism: Some(addr("default_ism")),
+                                })
+                                .into(),
+                            );
+                        }
+                    }
+                }
+
+                if let Ok(req) = cosmwasm_std::from_binary::<ism::ExpectedIsmQueryMsg>(msg) {
+                    assert_eq!(contract_addr, &addr("default_ism"));
+
+                    match req {
+                        ism::ExpectedIsmQueryMsg::Ism(IsmQueryMsg::Verify { metadata, .. }) => {
+                            return SystemResult::Ok(
+                                cosmwasm_std::to_binary(&ism::VerifyResponse {
+                                    verified: metadata[0] == 1,
+                                })
+                                .into(),
+                            );
+                        }
+                        _ => unreachable!("not in test coverage"),
+                    }
+                }
+
+                unreachable!("not in test coverage")
+            }
+            _ => unimplemented!("only for smart query"),
+        }
+    }
+
+    #[rstest]
+    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), false, true)]
+    #[should_panic(expected = "invalid message version: 99")]
+    #[case(99, LOCAL_DOMAIN, gen_bz(32), false, true)]
+    #[should_panic(expected = "message already delivered")]
+    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), true, true)]
+    #[should_panic(expected = "invalid destination domain: 11155111")]
+    #[case(MAILBOX_VERSION, DEST_DOMAIN, gen_bz(32), false, true)]
+    #[should_panic(expected = "ism verify failed")]
+    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), false, false)]
+    fn test_process(
+        #[values("osmo", "neutron")] hrp: &str,
+        #[case] version: u8,
+        #[case] dest_domain: u32,
+        #[case] recipient_addr: HexBinary,
+        #[case] duplicate: bool,
+        #[case] verified: bool,
+    ) {
+        let sender = gen_bz(32);
+        let sender_addr = bech32_encode(hrp, &sender).unwrap();
+        let msg_body = gen_bz(123);
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(test_process_query_handler);
+
+        hpl_pausable::initialize(deps.as_mut().storage, &false).unwrap();
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new(hrp, LOCAL_DOMAIN)
+                    .with_hook(addr("default_hook"), addr("required_hook"))
+                    .with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        let msg = Message {
+            version,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender,
+            dest_domain,
+            recipient: recipient_addr,
+            body: msg_body,
+        };
+        let msg_id = msg.id();
+
+        if duplicate {
+            DELIVERIES
+                .save(
+                    deps.as_mut().storage,
+                    msg.id().to_vec(),
+                    &Delivery {
+                        sender: sender_addr.clone(),
+                        block_number: mock_env().block.height,
+                        block_time: None,
+                        ism: None,
+                        recipient: None,
+                        origin_domain: None,
+                        value: vec![],
+                    },
+                )
+                .unwrap();
+        }
+
+        let _res = process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender_addr.as_str(), &[]),
+            vec![verified.into()].into(),
+            msg.clone().into(),
+        )
+        .map_err(|v| v.to_string())
+        .unwrap();
+
+        let recipient = msg.recipient_addr(hrp).unwrap();
+
+        let delivery = DELIVERIES
+            .load(deps.as_ref().storage, msg_id.to_vec())
+            .unwrap();
+        assert_eq!(
+            delivery,
+            Delivery {
+                sender: sender_addr,
+                block_number: mock_env().block.height,
+                block_time: Some(mock_env().block.time),
+                ism: Some(addr("default_ism")),
+                recipient: Some(recipient.clone()),
+                origin_domain: Some(DEST_DOMAIN),
+                value: vec![],
+            }
+        );
+
+        assert!(DELIVERIES_BY_ORIGIN.has(deps.as_ref().storage, (DEST_DOMAIN, msg_id.to_vec())));
+        assert!(DELIVERIES_BY_RECIPIENT.has(deps.as_ref().storage, (recipient, msg_id.to_vec())));
+        assert_eq!(
+            INBOUND_COUNTS
+                .load(deps.as_ref().storage, DEST_DOMAIN)
+                .unwrap(),
+            1
+        );
+    }
+
+    #[rstest]
+    #[case(vec![(MAILBOX_VERSION, true), (MAILBOX_VERSION, true)], false)]
+    #[case(vec![(MAILBOX_VERSION, true), (99, true), (MAILBOX_VERSION, false)], true)]
+    #[should_panic(expected = "invalid message version: 99")]
+    #[case(vec![(MAILBOX_VERSION, true), (99, true)], false)]
+    #[should_panic(expected = "ism verify failed")]
+    #[case(vec![(MAILBOX_VERSION, false), (MAILBOX_VERSION, true)], false)]
+    fn test_process_batch(#[case] items: Vec<(u8, bool)>, #[case] skip_failed: bool) {
+        let hrp = "osmo";
+        let relayer = bech32_encode(hrp, &gen_bz(32)).unwrap();
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(test_process_query_handler);
+
+        hpl_pausable::initialize(deps.as_mut().storage, &false).unwrap();
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new(hrp, LOCAL_DOMAIN)
+                    .with_hook(addr("default_hook"), addr("required_hook"))
+                    .with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        let msgs = items
+            .iter()
+            .enumerate()
+            .map(|(i, (version, verified))| {
+                let msg = Message {
+                    version: *version,
+                    nonce: i as u32,
+                    origin_domain: DEST_DOMAIN,
+                    sender: gen_bz(32),
+                    dest_domain: LOCAL_DOMAIN,
+                    recipient: gen_bz(32),
+                    body: gen_bz(123),
+                };
+
+                ProcessMsg {
+                    metadata: vec![(*verified).into()].into(),
+                    message: msg.into(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let res = process_batch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(relayer.as_str(), &[]),
+            msgs.clone(),
+            skip_failed,
+        )
+        .map_err(|v| v.to_string())
+        .unwrap();
+
+        let ProcessBatchResponse { results } = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(results.len(), items.len());
+
+        for ((version, verified), (result, msg)) in items.iter().zip(results.iter().zip(msgs)) {
+            let success = *version == MAILBOX_VERSION && *verified;
+            let msg_id = Message::from(msg.message).id();
+
+            assert_eq!(result.message_id, msg_id);
+            assert_eq!(result.success, success);
+            assert_eq!(result.error.is_none(), success);
+            assert_eq!(
+                DELIVERIES.has(deps.as_ref().storage, msg_id.to_vec()),
+                success
+            );
+        }
+
+        assert_eq!(
+            res.messages.len(),
+            results.iter().filter(|v| v.success).count()
+        );
+    }
+
+    #[rstest]
+    #[case(true)]
+    #[should_panic(expected = "invalid message length: 40")]
+    #[case(false)]
+    fn test_process_batch_malformed(#[case] skip_failed: bool) {
+        let hrp = "osmo";
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(test_process_query_handler);
+
+        hpl_pausable::initialize(deps.as_mut().storage, &false).unwrap();
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new(hrp, LOCAL_DOMAIN)
+                    .with_hook(addr("default_hook"), addr("required_hook"))
+                    .with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 0,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: gen_bz(123),
+        };
+        let malformed = gen_bz(40);
+
+        let res = process_batch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            vec![
+                ProcessMsg {
+                    metadata: vec![1].into(),
+                    message: malformed.clone(),
+                },
+                ProcessMsg {
+                    metadata: vec![1].into(),
+                    message: msg.clone().into(),
+                },
+            ],
+            skip_failed,
+        )
+        .map_err(|v| v.to_string())
+        .unwrap();
+
+        let ProcessBatchResponse { results } = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                ProcessResult {
+                    message_id: keccak256_hash(&malformed),
+                    success: false,
+                    error: Some("invalid message length: 40".to_string()),
+                },
+                ProcessResult {
+                    message_id: msg.id(),
+                    success: true,
+                    error: None,
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(None, vec![])]
+    #[case(Some(coin(100, "uosmo")), vec![coin(100, "uosmo")])]
+    #[should_panic(expected = "process value mismatch")]
+    #[case(Some(coin(100, "uosmo")), vec![coin(99, "uosmo")])]
+    #[should_panic(expected = "process value mismatch")]
+    #[case(Some(coin(100, "uosmo")), vec![coin(100, "uatom")])]
+    #[should_panic(expected = "process value mismatch")]
+    #[case(Some(coin(100, "uosmo")), vec![coin(100, "uosmo"), coin(100, "uatom")])]
+    #[should_panic(expected = "process value mismatch")]
+    #[case(Some(coin(100, "uosmo")), vec![])]
+    #[should_panic(expected = "process value mismatch")]
+    #[case(None, vec![coin(100, "uosmo")])]
+    fn test_process_value(
+        mut deps: TestDeps,
+        #[case] msg_value: Option<Coin>,
+        #[case] funds: Vec<Coin>,
+    ) {
+        deps.querier.update_wasm(test_process_query_handler);
+
+        set_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_ism".into(),
+        )
+        .unwrap();
+
+        // the value is committed in the body, so the relayer can't change it
+        let app_body = gen_bz(123);
+        let body = match msg_value {
+            Some(value) => HexBinary::try_from(MessageValue {
+                value,
+                body: app_body.clone(),
+            })
+            .unwrap(),
+            None => app_body.clone(),
+        };
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body,
+        };
+
+        let res = process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &funds),
+            vec![1].into(),
+            msg.clone().into(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        // the recipient gets its own payload along with the funds
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(
+                wasm_execute(
+                    msg.recipient_addr("osmo").unwrap(),
+                    &HandleMsg {
+                        origin: msg.origin_domain,
+                        sender: msg.sender.clone(),
+                        body: app_body,
+                    }
+                    .wrap(),
+                    funds.clone(),
+                )
+                .unwrap()
+            )]
+        );
+        assert_eq!(
+            DELIVERIES
+                .load(deps.as_ref().storage, msg.id().to_vec())
+                .unwrap()
+                .value,
+            funds
+        );
+    }
+
+    #[rstest]
+    fn test_process_batch_value(mut deps: TestDeps) {
+        deps.querier.update_wasm(test_process_query_handler);
+
+        set_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_ism".into(),
+        )
+        .unwrap();
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: HexBinary::try_from(MessageValue {
+                value: coin(100, "uosmo"),
+                body: gen_bz(123),
+            })
+            .unwrap(),
+        };
+
+        // a batch carries no funds, so messages with value are skipped
+        let res = process_batch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            vec![ProcessMsg {
+                metadata: vec![1].into(),
+                message: msg.clone().into(),
+            }],
+            true,
+        )
+        .unwrap();
+
+        let ProcessBatchResponse { results } = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(
+            results,
+            vec![ProcessResult {
+                message_id: msg.id(),
+                success: false,
+                error: Some(
+                    ContractError::ProcessValueMismatch {
+                        expected: vec![coin(100, "uosmo")],
+                        received: vec![],
+                    }
+                    .to_string()
+                ),
+            }]
+        );
+        assert!(!DELIVERIES.has(deps.as_ref().storage, msg.id().to_vec()));
+    }
+
+    #[rstest]
+    #[case(false, None)]
+    #[case(true, None)]
+    #[case(true, Some("recipient failed"))]
+    fn test_process_isolated(
+        mut deps: TestDeps,
+        #[case] isolation: bool,
+        #[case] handle_error: Option<&str>,
+    ) {
+        deps.querier.update_wasm(test_process_query_handler);
+
+        set_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_ism".into(),
+        )
+        .unwrap();
+        set_handle_isolation(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), isolation).unwrap();
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: HexBinary::try_from(MessageValue {
+                value: coin(100, "uosmo"),
+                body: gen_bz(123),
+            })
+            .unwrap(),
+        };
+        let msg_id = msg.id();
+
+        let value = vec![coin(100, "uosmo")];
+
+        let res = process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &value),
+            vec![1].into(),
+            msg.clone().into(),
+        )
+        .unwrap();
+
+        let expected_handle = handle_msg("osmo", msg.clone(), value.clone()).unwrap();
+        if !isolation {
+            assert_eq!(res.messages, vec![SubMsg::new(expected_handle)]);
+            return;
+        }
+        assert_eq!(res.messages, vec![SubMsg::reply_always(expected_handle, 0)]);
+
+        let result = match handle_error {
+            Some(error) => SubMsgResult::Err(error.to_string()),
+            None => SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        handle_reply(deps.as_mut(), mock_env(), Reply { id: 0, result }).unwrap();
+
+        assert!(!PENDING_HANDLES.has(deps.as_ref().storage, 0));
+        assert!(DELIVERIES.has(deps.as_ref().storage, msg_id.to_vec()));
+
+        let failed = FAILED_DELIVERIES
+            .may_load(deps.as_ref().storage, msg_id.to_vec())
+            .unwrap();
+        let error = match handle_error {
+            Some(error) => error,
+            None => {
+                assert_eq!(failed, None);
+                return;
+            }
+        };
+
+        let failed = failed.unwrap();
+        assert_eq!(failed.error, error);
+        assert_eq!(failed.message, HexBinary::from(msg.clone()));
+        assert_eq!(failed.value, value);
+
+        let res = retry_handle(deps.as_mut(), mock_info("anyone", &[]), msg_id.clone()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(handle_msg("osmo", msg, value).unwrap())]
         );
+        assert!(!FAILED_DELIVERIES.has(deps.as_ref().storage, msg_id.to_vec()));
+
+        let err = retry_handle(deps.as_mut(), mock_info("anyone", &[]), msg_id).unwrap_err();
+        assert_eq!(err, ContractError::FailedDeliveryNotFound {});
     }
 
     #[rstest]
-    #[case(addr(OWNER), addr("default_hook"))]
-    #[should_panic(expected = "unauthorized")]
-    #[case(addr(NOT_OWNER), addr("default_hook"))]
-    fn test_set_default_hook(
-        mut deps: TestDeps,
-        #[case] sender: Addr,
-        #[case] new_default_hook: Addr,
-    ) {
-        let res = set_default_hook(
+    #[case(false)]
+    #[case(true)]
+    fn test_process_batch_failing_recipient(#[case] skip_failed: bool) {
+        let hrp = "osmo";
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(test_process_query_handler);
+
+        hpl_pausable::initialize(deps.as_mut().storage, &false).unwrap();
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new(hrp, LOCAL_DOMAIN)
+                    .with_hook(addr("default_hook"), addr("required_hook"))
+                    .with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        let msgs = (0..2)
+            .map(|nonce| Message {
+                version: MAILBOX_VERSION,
+                nonce,
+                origin_domain: DEST_DOMAIN,
+                sender: gen_bz(32),
+                dest_domain: LOCAL_DOMAIN,
+                recipient: gen_bz(32),
+                body: gen_bz(123),
+            })
+            .collect::<Vec<_>>();
+
+        let res = process_batch(
             deps.as_mut(),
-            mock_info(sender.as_str(), &[]),
-            new_default_hook.to_string(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            msgs.iter()
+                .map(|msg| ProcessMsg {
+                    metadata: vec![1].into(),
+                    message: msg.clone().into(),
+                })
+                .collect(),
+            skip_failed,
         )
-        .map_err(|e| e.to_string())
         .unwrap();
 
-        assert_eq!(
-            res,
-            Response::new().add_event(emit_default_hook_set(sender, new_default_hook))
-        )
-    }
+        // handle isolation is off, so only skip_failed dispatches the handles with a reply
+        let expected = msgs
+            .iter()
+            .enumerate()
+            .map(|(i, msg)| {
+                let handle_msg = handle_msg(hrp, msg.clone(), vec![]).unwrap();
+                match skip_failed {
+                    true => SubMsg::reply_always(handle_msg, i as u64),
+                    false => SubMsg::new(handle_msg),
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(res.messages, expected);
 
-    #[rstest]
-    #[case(addr(OWNER), addr("required_hook"))]
-    #[should_panic(expected = "unauthorized")]
-    #[case(addr(NOT_OWNER), addr("required_hook"))]
-    fn test_set_required_hook(
-        mut deps: TestDeps,
-        #[case] sender: Addr,
-        #[case] new_required_hook: Addr,
-    ) {
-        let res = set_required_hook(
+        if !skip_failed {
+            return;
+        }
+
+        // the second recipient fails, the first delivery stands
+        let res = handle_reply(
             deps.as_mut(),
-            mock_info(sender.as_str(), &[]),
-            new_required_hook.to_string(),
+            mock_env(),
+            Reply {
+                id: 0,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
         )
-        .map_err(|e| e.to_string())
         .unwrap();
+        assert_eq!(res.data, None);
+        let res = handle_reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Err("recipient failed".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.events,
+            vec![emit_handle_failed(
+                msgs[1].id(),
+                "recipient failed".to_string()
+            )]
+        );
 
+        // the reply reports the failure in place of the result of the batch
+        let ProcessBatchResponse { results } = from_binary(&res.data.unwrap()).unwrap();
         assert_eq!(
-            res,
-            Response::new().add_event(emit_required_hook_set(sender, new_required_hook))
-        )
+            results,
+            vec![
+                ProcessResult {
+                    message_id: msgs[0].id(),
+                    success: true,
+                    error: None,
+                },
+                ProcessResult {
+                    message_id: msgs[1].id(),
+                    success: false,
+                    error: Some("recipient failed".to_string()),
+                },
+            ]
+        );
+
+        for msg in msgs.iter() {
+            assert!(DELIVERIES.has(deps.as_ref().storage, msg.id().to_vec()));
+        }
+        assert!(!FAILED_DELIVERIES.has(deps.as_ref().storage, msgs[0].id().to_vec()));
+
+        let failed = FAILED_DELIVERIES
+            .load(deps.as_ref().storage, msgs[1].id().to_vec())
+            .unwrap();
+        assert_eq!(failed.error, "recipient failed");
+        assert_eq!(failed.message, HexBinary::from(msgs[1].clone()));
     }
 
     #[rstest]
-    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(32))]
-    #[should_panic(expected = "invalid address length: 33")]
-    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(33))]
-    fn test_dispatch(
-        #[values("osmo", "neutron")] hrp: &str,
-        #[case] dest_domain: u32,
-        #[case] sender: HexBinary,
-        #[case] recipient_addr: HexBinary,
-    ) {
-        let sender = bech32_encode(hrp, sender.as_slice()).unwrap();
-        let msg_body = gen_bz(123);
+    #[case(PauseTarget::Dispatch {})]
+    #[case(PauseTarget::Process {})]
+    #[case(PauseTarget::Origin { domain: DEST_DOMAIN })]
+    fn test_pause_release(mut deps: TestDeps, #[case] target: PauseTarget) {
+        let err = pause(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(NOT_OWNER, &[]),
+            target.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
 
-        let mut deps = mock_dependencies();
+        let res = pause(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            target.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_paused(addr(OWNER), target.clone()))
+        );
+        assert!(is_target_paused(deps.as_ref().storage, &target).unwrap());
 
-        deps.querier.update_wasm(mock_query_handler);
+        let err = pause(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            target.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AlreadyPaused {});
 
-        instantiate(
+        let res = release(
             deps.as_mut(),
             mock_env(),
             mock_info(OWNER, &[]),
-            InstantiateMsg {
-                hrp: hrp.to_string(),
-                owner: OWNER.to_string(),
-                domain: LOCAL_DOMAIN,
-            },
+            target.clone(),
         )
         .unwrap();
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_released(addr(OWNER), target.clone()))
+        );
+        assert!(!is_target_paused(deps.as_ref().storage, &target).unwrap());
 
-        set_default_ism(deps.as_mut(), mock_info(OWNER, &[]), "default_ism".into()).unwrap();
-        set_default_hook(deps.as_mut(), mock_info(OWNER, &[]), "default_hook".into()).unwrap();
-        set_required_hook(deps.as_mut(), mock_info(OWNER, &[]), "required_hook".into()).unwrap();
-
-        let dispatch_msg = DispatchMsg::new(dest_domain, recipient_addr, msg_body)
-            .with_metadata(1500u32.to_be_bytes().to_vec());
+        let err = release(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), target).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyReleased {});
+    }
 
-        let msg = dispatch_msg
-            .clone()
-            .to_msg(
-                MAILBOX_VERSION,
-                NONCE.load(deps.as_ref().storage).unwrap(),
-                LOCAL_DOMAIN,
-                &sender,
-            )
-            .unwrap();
+    #[rstest]
+    fn test_pause_state_missing(mut deps: TestDeps) {
+        deps.querier.update_wasm(mock_query_handler);
 
-        let _res = dispatch(
+        set_default_hook(
             deps.as_mut(),
-            mock_info(sender.as_str(), &[coin(1500, "utest")]),
-            dispatch_msg,
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
         )
-        .map_err(|e| e.to_string())
         .unwrap();
 
-        assert_eq!(NONCE.load(deps.as_ref().storage).unwrap(), 1u32);
-        assert_eq!(
-            LATEST_DISPATCHED_ID.load(deps.as_ref().storage).unwrap(),
-            msg.id().to_vec()
-        );
-    }
+        // mailboxes deployed before pausable have no pause state at all
+        deps.storage.remove(b"pause");
 
-    fn test_process_query_handler(query: &WasmQuery) -> QuerierResult {
-        match query {
-            WasmQuery::Smart { contract_addr, msg } => {
-                if let Ok(req) = cosmwasm_std::from_binary::<ism::ExpectedIsmSpecifierQueryMsg>(msg)
-                {
-                    match req {
-                        hpl_interface::ism::ExpectedIsmSpecifierQueryMsg::IsmSpecifier(
-                            ism::IsmSpecifierQueryMsg::InterchainSecurityModule(),
-                        ) => {
-                            return SystemResult::Ok(
-                                cosmwasm_std::to_binary(&ism::InterchainSecurityModuleResponse {
-                                    ism: Some(addr("default_ism")),
-                                })
-                                .into(),
-                            );
-                        }
-                    }
-                }
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
 
-                if let Ok(req) = cosmwasm_std::from_binary::<ism::ExpectedIsmQueryMsg>(msg) {
-                    assert_eq!(contract_addr, &addr("default_ism"));
+        dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123)),
+        )
+        .unwrap();
 
-                    match req {
-                        ism::ExpectedIsmQueryMsg::Ism(IsmQueryMsg::Verify { metadata, .. }) => {
-                            return SystemResult::Ok(
-                                cosmwasm_std::to_binary(&ism::VerifyResponse {
-                                    verified: metadata[0] == 1,
-                                })
-                                .into(),
-                            );
-                        }
-                        _ => unreachable!("not in test coverage"),
-                    }
-                }
+        hpl_pausable::pause(deps.as_mut().storage, &addr(OWNER)).unwrap();
 
-                unreachable!("not in test coverage")
-            }
-            _ => unimplemented!("only for smart query"),
-        }
+        let err = dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123)),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Paused {});
     }
 
     #[rstest]
-    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), false, true)]
-    #[should_panic(expected = "invalid message version: 99")]
-    #[case(99, LOCAL_DOMAIN, gen_bz(32), false, true)]
-    #[should_panic(expected = "message already delivered")]
-    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), true, true)]
-    #[should_panic(expected = "invalid destination domain: 11155111")]
-    #[case(MAILBOX_VERSION, DEST_DOMAIN, gen_bz(32), false, true)]
-    #[should_panic(expected = "ism verify failed")]
-    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), false, false)]
-    fn test_process(
-        #[values("osmo", "neutron")] hrp: &str,
-        #[case] version: u8,
-        #[case] dest_domain: u32,
-        #[case] recipient_addr: HexBinary,
-        #[case] duplicate: bool,
-        #[case] verified: bool,
+    #[case(false, None, None, None)]
+    #[case(true, None, Some(ContractError::Paused {}), Some(ContractError::Paused {}))]
+    #[case(
+        false,
+        Some(PauseTarget::Dispatch {}),
+        Some(ContractError::DispatchPaused {}),
+        None
+    )]
+    #[case(
+        false,
+        Some(PauseTarget::Process {}),
+        None,
+        Some(ContractError::ProcessPaused {})
+    )]
+    #[case(
+        false,
+        Some(PauseTarget::Origin { domain: DEST_DOMAIN }),
+        None,
+        Some(ContractError::OriginPaused { domain: DEST_DOMAIN })
+    )]
+    #[case(false, Some(PauseTarget::Origin { domain: 1 }), None, None)]
+    fn test_paused_paths(
+        mut deps: TestDeps,
+        #[case] global: bool,
+        #[case] target: Option<PauseTarget>,
+        #[case] dispatch_err: Option<ContractError>,
+        #[case] process_err: Option<ContractError>,
     ) {
-        let sender = gen_bz(32);
-        let sender_addr = bech32_encode(hrp, &sender).unwrap();
-        let msg_body = gen_bz(123);
+        deps.querier.update_wasm(test_process_query_handler);
 
-        let mut deps = mock_dependencies();
+        set_default_ism(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            "default_ism".into(),
+        )
+        .unwrap();
 
-        deps.querier.update_wasm(test_process_query_handler);
+        if global {
+            hpl_pausable::pause(deps.as_mut().storage, &addr(OWNER)).unwrap();
+        }
+        if let Some(target) = target {
+            pause(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), target).unwrap();
+        }
 
-        CONFIG
-            .save(
-                deps.as_mut().storage,
-                &Config::new(hrp, LOCAL_DOMAIN)
-                    .with_hook(addr("default_hook"), addr("required_hook"))
-                    .with_ism(addr("default_ism")),
-            )
-            .unwrap();
+        // the dispatch path is checked before any hook is touched
+        let res = dispatch(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[]),
+            DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123)),
+        );
+        if let Some(err) = dispatch_err {
+            assert_eq!(res.unwrap_err(), err);
+        }
 
         let msg = Message {
-            version,
+            version: MAILBOX_VERSION,
             nonce: 123,
             origin_domain: DEST_DOMAIN,
-            sender,
-            dest_domain,
-            recipient: recipient_addr,
-            body: msg_body,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: gen_bz(123),
         };
-        let msg_id = msg.id();
-
-        if duplicate {
-            DELIVERIES
-                .save(
-                    deps.as_mut().storage,
-                    msg.id().to_vec(),
-                    &Delivery {
-                        sender: sender_addr.clone(),
-                        block_number: mock_env().block.height,
-                    },
-                )
-                .unwrap();
-        }
 
-        let _res = process(
+        let res = process(
             deps.as_mut(),
             mock_env(),
-            mock_info(sender_addr.as_str(), &[]),
-            vec![verified.into()].into(),
+            mock_info("relayer", &[]),
+            vec![1].into(),
             msg.into(),
-        )
-        .map_err(|v| v.to_string())
-        .unwrap();
-
-        let delivery = DELIVERIES
-            .load(deps.as_ref().storage, msg_id.to_vec())
-            .unwrap();
-        assert_eq!(delivery.sender, sender_addr);
+        );
+        match process_err {
+            Some(err) => assert_eq!(res.unwrap_err(), err),
+            None => assert!(res.is_ok()),
+        }
     }
 }
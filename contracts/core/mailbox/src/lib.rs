@@ -2,6 +2,7 @@ pub mod contract;
 mod error;
 mod event;
 pub mod execute;
+mod migration;
 pub mod query;
 mod state;
 
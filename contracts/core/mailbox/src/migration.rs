@@ -0,0 +1,50 @@
+use cosmwasm_std::{StdResult, Storage};
+use hpl_migration::Migration;
+
+use crate::state::{NONCE, STATS_START_NONCE};
+
+/// State migrations of the mailbox, ordered by version.
+///
+/// `Config` and `DELIVERIES` keep their layout across these versions. the fields added
+/// to `Delivery` are optional, so existing deliveries are read as-is.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: "0.0.6-rc7",
+    migrate: migrate_0_0_6_rc7,
+}];
+
+fn migrate_0_0_6_rc7(storage: &mut dyn Storage) -> StdResult<()> {
+    // mailboxes deployed before pausable have no pause state
+    let paused = hpl_pausable::get_pause_info(storage)?;
+    hpl_pausable::initialize(storage, &paused)?;
+
+    // per-domain counters can't be rebuilt from history, they count from here on
+    let nonce = NONCE.may_load(storage)?.unwrap_or_default();
+    STATS_START_NONCE.save(storage, &nonce)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::MockStorage;
+
+    use super::*;
+
+    #[test]
+    fn test_migrate_0_0_6_rc7() {
+        let mut storage = MockStorage::new();
+
+        NONCE.save(&mut storage, &42).unwrap();
+
+        migrate_0_0_6_rc7(&mut storage).unwrap();
+
+        assert!(storage.get(b"pause").is_some());
+        assert!(!hpl_pausable::get_pause_info(&storage).unwrap());
+        assert_eq!(STATS_START_NONCE.load(&storage).unwrap(), 42);
+
+        // a paused mailbox stays paused
+        hpl_pausable::initialize(&mut storage, &true).unwrap();
+        migrate_0_0_6_rc7(&mut storage).unwrap();
+        assert!(hpl_pausable::get_pause_info(&storage).unwrap());
+    }
+}
@@ -1,19 +1,39 @@
-use cosmwasm_std::{Deps, HexBinary};
+use cosmwasm_std::{Addr, Coin, Deps, Env, HexBinary, StdResult};
+use cw_storage_plus::Map;
 use hpl_interface::{
     core::mailbox::{
-        DefaultHookResponse, DefaultIsmResponse, DispatchMsg, HrpResponse,
-        LatestDispatchedIdResponse, LocalDomainResponse, MessageDeliveredResponse, NonceResponse,
-        RecipientIsmResponse, RequiredHookResponse,
+        DefaultHookResponse, DefaultIsmResponse, DeliveriesResponse, DeliveryResponse,
+        DiagnosticsResponse, DispatchManyMsg, DispatchManyQuoteResponse, DispatchMsg,
+        DispatchQuoteResponse, DispatchRetentionResponse, DispatchedMessageResponse,
+        DispatchedMessagesResponse, DomainCount, FailedDeliveriesResponse, FailedDeliveryResponse,
+        HandleIsolationResponse, HookDiagnostic, HrpResponse, IsmDiagnostic, IsmFallbackResponse,
+        LatestDispatchBatchResponse, LatestDispatchedIdResponse, LocalDeliveryResponse,
+        LocalDomainResponse, MessageDeliveredResponse, NonceResponse, OriginDefaultIsm,
+        OriginDefaultIsmsResponse, OriginIsmDiagnostic, PauseStateResponse, RecipientIsmDiagnostic,
+        RecipientIsmOverride, RecipientIsmOverridesResponse, RecipientIsmResponse,
+        RequiredHookResponse, SignedNonceResponse, StatsResponse,
     },
-    hook::{self, QuoteDispatchResponse},
-    ism,
+    hook::{self, ExpectedHookQueryMsg, HookQueryMsg, MailboxResponse},
+    ism::{self, IsmQueryMsg, ModuleTypeResponse},
+    range_option,
+    types::{Message, MessageValue},
+    Order,
 };
 
 use crate::{
-    state::{CONFIG, DELIVERIES, LATEST_DISPATCHED_ID, NONCE},
-    ContractError,
+    state::{
+        Config, Delivery, Dispatch, FailedDelivery, CONFIG, DELIVERIES, DELIVERIES_BY_ORIGIN,
+        DELIVERIES_BY_RECIPIENT, DISPATCHES, DISPATCH_NONCES, DISPATCH_PAUSED, DISPATCH_RETENTION,
+        FAILED_DELIVERIES, HANDLE_ISOLATION, INBOUND_COUNTS, ISM_FALLBACKS, LATEST_DISPATCHED_ID,
+        LATEST_DISPATCH_BATCH, LOCAL_DELIVERY, NONCE, ORIGIN_DEFAULT_ISMS, OUTBOUND_COUNTS,
+        PAUSED_ORIGINS, PROCESS_PAUSED, RECIPIENT_ISMS, SIGNED_NONCES, STATS_START_NONCE,
+    },
+    ContractError, MAILBOX_VERSION,
 };
 
+// answered by hooks that accept messages from any mailbox
+const UNRESTRICTED_MAILBOX: &str = "unrestricted";
+
 pub fn get_hrp(deps: Deps) -> Result<HrpResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -32,7 +52,7 @@ pub fn get_default_ism(deps: Deps) -> Result<DefaultIsmResponse, ContractError>
     let config = CONFIG.load(deps.storage)?;
 
     Ok(DefaultIsmResponse {
-        default_ism: config.get_default_ism().into(),
+        default_ism: config.get_default_ism()?.into(),
     })
 }
 
@@ -40,7 +60,7 @@ pub fn get_default_hook(deps: Deps) -> Result<DefaultHookResponse, ContractError
     let config = CONFIG.load(deps.storage)?;
 
     Ok(DefaultHookResponse {
-        default_hook: config.get_default_hook().into(),
+        default_hook: config.get_default_hook()?.into(),
     })
 }
 
@@ -48,7 +68,7 @@ pub fn get_required_hook(deps: Deps) -> Result<RequiredHookResponse, ContractErr
     let config = CONFIG.load(deps.storage)?;
 
     Ok(RequiredHookResponse {
-        required_hook: config.get_required_hook().into(),
+        required_hook: config.get_required_hook()?.into(),
     })
 }
 
@@ -58,25 +78,186 @@ pub fn get_delivered(deps: Deps, id: HexBinary) -> Result<MessageDeliveredRespon
     Ok(MessageDeliveredResponse { delivered })
 }
 
+fn to_delivery_response(id: Vec<u8>, delivery: Delivery) -> DeliveryResponse {
+    DeliveryResponse {
+        id: id.into(),
+        sender: delivery.sender.into(),
+        block_number: delivery.block_number,
+        block_time: delivery.block_time,
+        ism: delivery.ism.map(|v| v.into()),
+        recipient: delivery.recipient.map(|v| v.into()),
+        origin_domain: delivery.origin_domain,
+        value: delivery.value,
+    }
+}
+
+pub fn get_delivery(deps: Deps, id: HexBinary) -> Result<DeliveryResponse, ContractError> {
+    let delivery = DELIVERIES
+        .may_load(deps.storage, id.to_vec())?
+        .ok_or(ContractError::MessageNotFound {})?;
+
+    Ok(to_delivery_response(id.to_vec(), delivery))
+}
+
+pub fn list_deliveries_by_origin(
+    deps: Deps,
+    origin: u32,
+    offset: Option<HexBinary>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<DeliveriesResponse, ContractError> {
+    let ((min, max), limit, order) = range_option(offset.map(|v| v.to_vec()), limit, order)?;
+
+    let deliveries = DELIVERIES_BY_ORIGIN
+        .prefix(origin)
+        .keys(deps.storage, min, max, order.into())
+        .take(limit)
+        .map(|id| {
+            let id = id?;
+            let delivery = DELIVERIES.load(deps.storage, id.clone())?;
+
+            Ok(to_delivery_response(id, delivery))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DeliveriesResponse { deliveries })
+}
+
+pub fn list_deliveries_by_recipient(
+    deps: Deps,
+    recipient: String,
+    offset: Option<HexBinary>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<DeliveriesResponse, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let ((min, max), limit, order) = range_option(offset.map(|v| v.to_vec()), limit, order)?;
+
+    let deliveries = DELIVERIES_BY_RECIPIENT
+        .prefix(recipient)
+        .keys(deps.storage, min, max, order.into())
+        .take(limit)
+        .map(|id| {
+            let id = id?;
+            let delivery = DELIVERIES.load(deps.storage, id.clone())?;
+
+            Ok(to_delivery_response(id, delivery))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DeliveriesResponse { deliveries })
+}
+
 pub fn get_nonce(deps: Deps) -> Result<NonceResponse, ContractError> {
     let nonce = NONCE.load(deps.storage)?;
 
     Ok(NonceResponse { nonce })
 }
 
+pub fn get_signed_nonce(
+    deps: Deps,
+    signer: HexBinary,
+) -> Result<SignedNonceResponse, ContractError> {
+    let nonce = SIGNED_NONCES
+        .may_load(deps.storage, signer.to_vec())?
+        .unwrap_or_default();
+
+    Ok(SignedNonceResponse { nonce })
+}
+
+/// Resolves the ism for `recipient`. The lookup order is the owner override,
+/// the ism the recipient specifies, the default ism for `origin`
+/// and then the global default ism. Recipients that fail the specifier query
+/// only fall through to the defaults when the ism fallback is enabled for them.
+pub fn resolve_ism(
+    deps: Deps,
+    config: &Config,
+    recipient: &Addr,
+    origin: Option<u32>,
+) -> Result<Addr, ContractError> {
+    if let Some(ism) = RECIPIENT_ISMS.may_load(deps.storage, recipient.clone())? {
+        return Ok(ism);
+    }
+
+    let fallback = ISM_FALLBACKS.has(deps.storage, recipient.clone());
+    if let Some(ism) = ism::recipient(&deps.querier, recipient, fallback)? {
+        return Ok(ism);
+    }
+
+    if let Some(origin) = origin {
+        if let Some(ism) = ORIGIN_DEFAULT_ISMS.may_load(deps.storage, origin)? {
+            return Ok(ism);
+        }
+    }
+
+    config.get_default_ism()
+}
+
 pub fn get_recipient_ism(
     deps: Deps,
     recipient: String,
+    origin: Option<u32>,
 ) -> Result<RecipientIsmResponse, ContractError> {
-    let default_ism = CONFIG.load(deps.storage)?.get_default_ism();
+    let config = CONFIG.load(deps.storage)?;
 
     let recipient = deps.api.addr_validate(&recipient)?;
 
-    let ism = ism::recipient(&deps.querier, recipient)?.unwrap_or(default_ism);
+    let ism = resolve_ism(deps, &config, &recipient, origin)?;
 
     Ok(RecipientIsmResponse { ism: ism.into() })
 }
 
+pub fn list_recipient_ism_overrides(
+    deps: Deps,
+    offset: Option<String>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<RecipientIsmOverridesResponse, ContractError> {
+    let offset = offset.map(|v| deps.api.addr_validate(&v)).transpose()?;
+
+    let ((min, max), limit, order) = range_option(offset, limit, order)?;
+
+    let overrides = RECIPIENT_ISMS
+        .range(deps.storage, min, max, order.into())
+        .take(limit)
+        .map(|item| {
+            let (recipient, ism) = item?;
+
+            Ok(RecipientIsmOverride {
+                recipient: recipient.into(),
+                ism: ism.into(),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RecipientIsmOverridesResponse { overrides })
+}
+
+pub fn list_origin_default_isms(
+    deps: Deps,
+    offset: Option<u32>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<OriginDefaultIsmsResponse, ContractError> {
+    let ((min, max), limit, order) = range_option(offset, limit, order)?;
+
+    let isms = ORIGIN_DEFAULT_ISMS
+        .range(deps.storage, min, max, order.into())
+        .take(limit)
+        .map(|item| {
+            let (origin, ism) = item?;
+
+            Ok(OriginDefaultIsm {
+                origin,
+                ism: ism.into(),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(OriginDefaultIsmsResponse { isms })
+}
+
 pub fn get_latest_dispatch_id(deps: Deps) -> Result<LatestDispatchedIdResponse, ContractError> {
     let latest_dispatched_id = LATEST_DISPATCHED_ID.load(deps.storage)?.into();
 
@@ -85,63 +266,475 @@ pub fn get_latest_dispatch_id(deps: Deps) -> Result<LatestDispatchedIdResponse,
     })
 }
 
+pub fn get_latest_dispatch_batch(deps: Deps) -> Result<LatestDispatchBatchResponse, ContractError> {
+    let message_ids = LATEST_DISPATCH_BATCH
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    Ok(LatestDispatchBatchResponse { message_ids })
+}
+
+fn to_dispatched_message_response(nonce: u32, dispatch: Dispatch) -> DispatchedMessageResponse {
+    DispatchedMessageResponse {
+        nonce,
+        id: dispatch.id,
+        message: dispatch.message,
+        sender: dispatch.sender.into(),
+        dest_domain: dispatch.dest_domain,
+        block_number: dispatch.block_number,
+    }
+}
+
+pub fn get_dispatched_message(
+    deps: Deps,
+    nonce: u32,
+) -> Result<DispatchedMessageResponse, ContractError> {
+    let dispatch = DISPATCHES
+        .may_load(deps.storage, nonce)?
+        .ok_or(ContractError::MessageNotFound {})?;
+
+    Ok(to_dispatched_message_response(nonce, dispatch))
+}
+
+pub fn get_dispatched_message_by_id(
+    deps: Deps,
+    id: HexBinary,
+) -> Result<DispatchedMessageResponse, ContractError> {
+    let nonce = DISPATCH_NONCES
+        .may_load(deps.storage, id.to_vec())?
+        .ok_or(ContractError::MessageNotFound {})?;
+
+    get_dispatched_message(deps, nonce)
+}
+
+pub fn list_dispatched_messages(
+    deps: Deps,
+    offset: Option<u32>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<DispatchedMessagesResponse, ContractError> {
+    let ((min, max), limit, order) = range_option(offset, limit, order)?;
+
+    let messages = DISPATCHES
+        .range(deps.storage, min, max, order.into())
+        .take(limit)
+        .map(|item| {
+            let (nonce, dispatch) = item?;
+
+            Ok(to_dispatched_message_response(nonce, dispatch))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DispatchedMessagesResponse { messages })
+}
+
+pub fn get_dispatch_retention(deps: Deps) -> Result<DispatchRetentionResponse, ContractError> {
+    let retention = DISPATCH_RETENTION.may_load(deps.storage)?;
+
+    Ok(DispatchRetentionResponse { retention })
+}
+
+pub fn get_handle_isolation(deps: Deps) -> Result<HandleIsolationResponse, ContractError> {
+    let enabled = HANDLE_ISOLATION.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(HandleIsolationResponse { enabled })
+}
+
+pub fn get_local_delivery(deps: Deps) -> Result<LocalDeliveryResponse, ContractError> {
+    let enabled = LOCAL_DELIVERY.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(LocalDeliveryResponse { enabled })
+}
+
+pub fn get_ism_fallback(
+    deps: Deps,
+    recipient: String,
+) -> Result<IsmFallbackResponse, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let enabled = ISM_FALLBACKS.has(deps.storage, recipient);
+
+    Ok(IsmFallbackResponse { enabled })
+}
+
+fn to_failed_delivery_response(id: Vec<u8>, failed: FailedDelivery) -> FailedDeliveryResponse {
+    FailedDeliveryResponse {
+        id: id.into(),
+        message: failed.message,
+        error: failed.error,
+        block_number: failed.block_number,
+        value: failed.value,
+    }
+}
+
+pub fn get_failed_delivery(
+    deps: Deps,
+    id: HexBinary,
+) -> Result<FailedDeliveryResponse, ContractError> {
+    let failed = FAILED_DELIVERIES
+        .may_load(deps.storage, id.to_vec())?
+        .ok_or(ContractError::FailedDeliveryNotFound {})?;
+
+    Ok(to_failed_delivery_response(id.to_vec(), failed))
+}
+
+pub fn list_failed_deliveries(
+    deps: Deps,
+    offset: Option<HexBinary>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<FailedDeliveriesResponse, ContractError> {
+    let ((min, max), limit, order) = range_option(offset.map(|v| v.to_vec()), limit, order)?;
+
+    let deliveries = FAILED_DELIVERIES
+        .range(deps.storage, min, max, order.into())
+        .take(limit)
+        .map(|item| {
+            let (id, failed) = item?;
+
+            Ok(to_failed_delivery_response(id, failed))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FailedDeliveriesResponse { deliveries })
+}
+
+pub fn get_pause_state(deps: Deps) -> Result<PauseStateResponse, ContractError> {
+    let paused_origins = PAUSED_ORIGINS
+        .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PauseStateResponse {
+        paused: hpl_pausable::get_pause_info(deps.storage)?,
+        dispatch_paused: DISPATCH_PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+        process_paused: PROCESS_PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+        paused_origins,
+    })
+}
+
+fn diagnose_hook(deps: Deps, env: &Env, hook: Result<Addr, ContractError>) -> HookDiagnostic {
+    let hook = match hook {
+        Ok(hook) => hook,
+        Err(err) => {
+            return HookDiagnostic {
+                hook: None,
+                mailbox: None,
+                error: Some(err.to_string()),
+            }
+        }
+    };
+
+    let res = deps.querier.query_wasm_smart::<MailboxResponse>(
+        &hook,
+        &ExpectedHookQueryMsg::Hook(HookQueryMsg::Mailbox {}),
+    );
+
+    match res {
+        Ok(MailboxResponse { mailbox }) => {
+            let bound = mailbox == env.contract.address.as_str() || mailbox == UNRESTRICTED_MAILBOX;
+
+            HookDiagnostic {
+                hook: Some(hook.into()),
+                error: (!bound).then(|| format!("hook is bound to another mailbox: {mailbox}")),
+                mailbox: Some(mailbox),
+            }
+        }
+        Err(err) => HookDiagnostic {
+            hook: Some(hook.into()),
+            mailbox: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+fn diagnose_ism(deps: Deps, ism: Result<Addr, ContractError>) -> IsmDiagnostic {
+    let ism = match ism {
+        Ok(ism) => ism,
+        Err(err) => {
+            return IsmDiagnostic {
+                ism: None,
+                module_type: None,
+                error: Some(err.to_string()),
+            }
+        }
+    };
+
+    let res = deps
+        .querier
+        .query_wasm_smart::<ModuleTypeResponse>(&ism, &IsmQueryMsg::ModuleType {}.wrap());
+
+    match res {
+        Ok(ModuleTypeResponse { typ }) => IsmDiagnostic {
+            ism: Some(ism.into()),
+            module_type: Some(typ),
+            error: None,
+        },
+        Err(err) => IsmDiagnostic {
+            ism: Some(ism.into()),
+            module_type: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+pub fn get_stats(
+    deps: Deps,
+    domains: Option<Vec<u32>>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<StatsResponse, ContractError> {
+    let ((min, max), limit, order) = range_option(offset, limit, order)?;
+    let order = order.into();
+
+    let counts = |map: Map<u32, u64>| -> StdResult<Vec<DomainCount>> {
+        match &domains {
+            Some(domains) => domains
+                .iter()
+                .map(|domain| {
+                    let count = map.may_load(deps.storage, *domain)?.unwrap_or_default();
+
+                    Ok(DomainCount {
+                        domain: *domain,
+                        count,
+                    })
+                })
+                .collect(),
+            None => map
+                .range(deps.storage, min.clone(), max.clone(), order)
+                .take(limit)
+                .map(|item| item.map(|(domain, count)| DomainCount { domain, count }))
+                .collect(),
+        }
+    };
+
+    Ok(StatsResponse {
+        outbound: counts(OUTBOUND_COUNTS)?,
+        inbound: counts(INBOUND_COUNTS)?,
+        start_nonce: STATS_START_NONCE
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+    })
+}
+
+pub fn get_diagnostics(deps: Deps, env: Env) -> Result<DiagnosticsResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let default_ism = diagnose_ism(deps, config.get_default_ism());
+    let default_hook = diagnose_hook(deps, &env, config.get_default_hook());
+    let required_hook = diagnose_hook(deps, &env, config.get_required_hook());
+
+    let origin_default_isms = ORIGIN_DEFAULT_ISMS
+        .range(deps.storage, None, None, Order::Asc.into())
+        .map(|item| {
+            let (origin, ism) = item?;
+
+            Ok(OriginIsmDiagnostic {
+                origin,
+                ism: diagnose_ism(deps, Ok(ism)),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let recipient_isms = RECIPIENT_ISMS
+        .range(deps.storage, None, None, Order::Asc.into())
+        .map(|item| {
+            let (recipient, ism) = item?;
+
+            Ok(RecipientIsmDiagnostic {
+                recipient: recipient.into(),
+                ism: diagnose_ism(deps, Ok(ism)),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let healthy = default_ism.error.is_none()
+        && default_hook.error.is_none()
+        && required_hook.error.is_none()
+        && origin_default_isms.iter().all(|v| v.ism.error.is_none())
+        && recipient_isms.iter().all(|v| v.ism.error.is_none());
+
+    Ok(DiagnosticsResponse {
+        healthy,
+        default_ism,
+        default_hook,
+        required_hook,
+        origin_default_isms,
+        recipient_isms,
+    })
+}
+
 pub fn quote_dispatch(
     deps: Deps,
     msg: DispatchMsg,
-) -> Result<QuoteDispatchResponse, ContractError> {
+) -> Result<DispatchQuoteResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let nonce = NONCE.load(deps.storage)?;
+
+    quote_dispatch_at(deps, &config, nonce, msg)
+}
+
+pub fn quote_dispatch_many(
+    deps: Deps,
+    msg: DispatchManyMsg,
+) -> Result<DispatchManyQuoteResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
+    let nonce = NONCE.load(deps.storage)?;
 
-    let target_hook = msg.get_hook_addr(deps.api, config.get_default_hook())?;
-    let required_hook = config.get_required_hook();
+    let mut total = vec![];
+    let quotes = msg
+        .into_msgs()
+        .into_iter()
+        .enumerate()
+        .map(|(i, msg)| {
+            let quote = quote_dispatch_at(deps, &config, nonce + i as u32, msg)?;
+            merge_fees(&mut total, &quote.total);
 
-    let mut required_gas = hook::quote_dispatch(
-        &deps.querier,
-        required_hook,
-        msg.metadata.clone().unwrap(),
-        msg.msg_body.clone(),
-    )?
-    .gas_amount
-    .expect("failed to quote required gas");
-
-    let target_gas = hook::quote_dispatch(
-        &deps.querier,
-        target_hook,
-        msg.metadata.clone().unwrap(),
-        msg.msg_body,
-    )?
-    .gas_amount;
-
-    if let Some(gas) = target_gas {
-        required_gas.amount += gas.amount;
-    }
-
-    Ok(QuoteDispatchResponse {
-        gas_amount: Some(required_gas),
+            Ok(quote)
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    Ok(DispatchManyQuoteResponse { quotes, total })
+}
+
+fn quote_dispatch_at(
+    deps: Deps,
+    config: &Config,
+    nonce: u32,
+    msg: DispatchMsg,
+) -> Result<DispatchQuoteResponse, ContractError> {
+    // the sender is unknown at quote time. hooks price the message by its
+    // destination and size, so an empty sender yields the same quote.
+    let message = Message {
+        version: MAILBOX_VERSION,
+        nonce,
+        origin_domain: config.local_domain,
+        sender: vec![0u8; 32].into(),
+        dest_domain: msg.dest_domain,
+        recipient: msg.recipient_addr.clone(),
+        body: msg.msg_body.clone(),
+    };
+
+    if message.dest_domain == config.local_domain
+        && LOCAL_DELIVERY.may_load(deps.storage)?.unwrap_or_default()
+    {
+        return get_local_dispatch_quote(
+            deps,
+            config.get_required_hook()?,
+            msg.metadata.unwrap_or_default(),
+            message,
+        );
+    }
+
+    let hook = msg.get_hook_addr(deps.api, config.get_default_hook()?)?;
+
+    get_dispatch_quote(
+        deps,
+        config.get_required_hook()?,
+        hook,
+        msg.metadata.unwrap_or_default(),
+        message,
+    )
+}
+
+pub fn get_dispatch_quote(
+    deps: Deps,
+    required_hook: Addr,
+    hook: Addr,
+    metadata: HexBinary,
+    message: Message,
+) -> Result<DispatchQuoteResponse, ContractError> {
+    // a hook that's both the required and the target hook is posted to, and paid, once
+    let hook_fee = match hook == required_hook {
+        true => None,
+        false => {
+            hook::quote_dispatch(&deps.querier, hook, metadata.clone(), message.clone())?.gas_amount
+        }
+    };
+    let required_hook_fee =
+        hook::quote_dispatch(&deps.querier, required_hook, metadata, message)?.gas_amount;
+
+    let mut total: Vec<Coin> = vec![];
+    merge_fees(&mut total, required_hook_fee.iter().chain(hook_fee.iter()));
+
+    Ok(DispatchQuoteResponse {
+        required_hook_fee,
+        hook_fee,
+        total,
     })
 }
 
+/// Quotes a locally delivered message. It skips the default hook, so the dispatcher
+/// pays the required hook and the value carried to the recipient.
+pub fn get_local_dispatch_quote(
+    deps: Deps,
+    required_hook: Addr,
+    metadata: HexBinary,
+    message: Message,
+) -> Result<DispatchQuoteResponse, ContractError> {
+    let required_hook_fee =
+        hook::quote_dispatch(&deps.querier, required_hook, metadata, message.clone())?.gas_amount;
+
+    let mut total: Vec<Coin> = vec![];
+    merge_fees(
+        &mut total,
+        required_hook_fee
+            .iter()
+            .chain(message_value(&message).iter()),
+    );
+
+    Ok(DispatchQuoteResponse {
+        required_hook_fee,
+        hook_fee: None,
+        total,
+    })
+}
+
+/// Returns the value `msg` carries in its body, if any.
+pub fn message_value(msg: &Message) -> Vec<Coin> {
+    MessageValue::parse(&msg.body)
+        .map(|v| v.value)
+        .filter(|v| !v.amount.is_zero())
+        .into_iter()
+        .collect()
+}
+
+/// Adds `fees` to `total`, one entry per denom.
+pub fn merge_fees<'a>(total: &mut Vec<Coin>, fees: impl IntoIterator<Item = &'a Coin>) {
+    for fee in fees {
+        if fee.amount.is_zero() {
+            continue;
+        }
+
+        match total.iter_mut().find(|v| v.denom == fee.denom) {
+            Some(v) => v.amount += fee.amount,
+            None => total.push(fee.clone()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
 
     use cosmwasm_std::{
-        from_binary,
+        coin, from_binary,
         testing::{mock_dependencies, mock_env},
-        Addr,
+        Addr, ContractResult, Empty, QuerierResult, SystemResult, WasmQuery,
     };
-    use hpl_interface::core::mailbox::MailboxQueryMsg;
+    use hpl_interface::core::mailbox::{MailboxHookQueryMsg, MailboxQueryMsg, QueryMsg};
     use ibcx_test_utils::{gen_addr, gen_bz, hex};
     use rstest::rstest;
     use serde::de::DeserializeOwned;
 
-    use crate::{
-        contract,
-        state::{Config, Delivery},
-    };
+    use crate::{contract, state::Dispatch};
 
     use super::*;
 
     fn query<T: DeserializeOwned>(deps: Deps, req: MailboxQueryMsg) -> T {
-        from_binary(&contract::query(deps, mock_env(), req.wrap()).unwrap()).unwrap()
+        let res = contract::query(deps, mock_env(), req.wrap())
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        from_binary(&res).unwrap()
     }
 
     fn query_hrp(deps: Deps) -> HrpResponse {
@@ -240,6 +833,11 @@ mod test {
                     &Delivery {
                         sender: Addr::unchecked("sender"),
                         block_number: 123,
+                        block_time: None,
+                        ism: None,
+                        recipient: None,
+                        origin_domain: None,
+                        value: vec![],
                     },
                 )
                 .unwrap();
@@ -265,4 +863,566 @@ mod test {
             query(deps.as_ref(), MailboxQueryMsg::LatestDispatchId {});
         assert_eq!(res.message_id, rand_id);
     }
+
+    #[rstest]
+    fn test_query_latest_dispatch_batch() {
+        let mut deps = mock_dependencies();
+
+        let res: LatestDispatchBatchResponse =
+            query(deps.as_ref(), MailboxQueryMsg::LatestDispatchBatch {});
+        assert!(res.message_ids.is_empty());
+
+        let ids = vec![gen_bz(32), gen_bz(32)];
+        LATEST_DISPATCH_BATCH
+            .save(deps.as_mut().storage, &ids)
+            .unwrap();
+
+        let res: LatestDispatchBatchResponse =
+            query(deps.as_ref(), MailboxQueryMsg::LatestDispatchBatch {});
+        assert_eq!(res.message_ids, ids);
+    }
+
+    #[rstest]
+    #[case(None, None, None, vec![0, 1, 2, 3, 4])]
+    #[case(Some(1), Some(2), None, vec![2, 3])]
+    #[case(Some(3), None, Some(Order::Desc), vec![2, 1, 0])]
+    fn test_query_dispatched_messages(
+        #[case] offset: Option<u32>,
+        #[case] limit: Option<u32>,
+        #[case] order: Option<Order>,
+        #[case] expected: Vec<u32>,
+    ) {
+        let mut deps = mock_dependencies();
+
+        let dispatches = (0..5u32)
+            .map(|nonce| Dispatch {
+                id: gen_bz(32),
+                message: gen_bz(100),
+                sender: Addr::unchecked("sender"),
+                dest_domain: 1,
+                block_number: nonce as u64,
+            })
+            .collect::<Vec<_>>();
+
+        for (nonce, dispatch) in dispatches.iter().enumerate() {
+            DISPATCHES
+                .save(deps.as_mut().storage, nonce as u32, dispatch)
+                .unwrap();
+            DISPATCH_NONCES
+                .save(deps.as_mut().storage, dispatch.id.to_vec(), &(nonce as u32))
+                .unwrap();
+        }
+
+        let res: DispatchedMessagesResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::DispatchedMessages {
+                offset,
+                limit,
+                order,
+            },
+        );
+        assert_eq!(
+            res.messages.iter().map(|v| v.nonce).collect::<Vec<_>>(),
+            expected
+        );
+
+        let res: DispatchedMessageResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::DispatchedMessage { nonce: 2 },
+        );
+        assert_eq!(res.id, dispatches[2].id);
+        assert_eq!(res.message, dispatches[2].message);
+
+        let res: DispatchedMessageResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::DispatchedMessageById {
+                id: dispatches[4].id.clone(),
+            },
+        );
+        assert_eq!(res.nonce, 4);
+        assert_eq!(res.block_number, 4);
+    }
+
+    #[rstest]
+    fn test_query_deliveries() {
+        let mut deps = mock_dependencies();
+
+        // (id, origin, recipient)
+        let deliveries = [
+            (hex("01"), 1u32, "recipient_a"),
+            (hex("02"), 2u32, "recipient_a"),
+            (hex("03"), 1u32, "recipient_b"),
+            (hex("04"), 1u32, "recipient_a"),
+        ];
+
+        for (id, origin, recipient) in deliveries.iter() {
+            let recipient = Addr::unchecked(*recipient);
+
+            DELIVERIES
+                .save(
+                    deps.as_mut().storage,
+                    id.to_vec(),
+                    &Delivery {
+                        sender: Addr::unchecked("relayer"),
+                        block_number: 123,
+                        block_time: Some(mock_env().block.time),
+                        ism: Some(Addr::unchecked("ism")),
+                        recipient: Some(recipient.clone()),
+                        origin_domain: Some(*origin),
+                        value: vec![],
+                    },
+                )
+                .unwrap();
+            DELIVERIES_BY_ORIGIN
+                .save(deps.as_mut().storage, (*origin, id.to_vec()), &Empty {})
+                .unwrap();
+            DELIVERIES_BY_RECIPIENT
+                .save(deps.as_mut().storage, (recipient, id.to_vec()), &Empty {})
+                .unwrap();
+        }
+
+        let res: DeliveryResponse =
+            query(deps.as_ref(), MailboxQueryMsg::Delivery { id: hex("03") });
+        assert_eq!(res.sender, "relayer");
+        assert_eq!(res.ism, Some("ism".to_string()));
+        assert_eq!(res.recipient, Some("recipient_b".to_string()));
+        assert_eq!(res.origin_domain, Some(1));
+
+        let res: DeliveriesResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::DeliveriesByOrigin {
+                origin: 1,
+                offset: Some(hex("01")),
+                limit: None,
+                order: None,
+            },
+        );
+        assert_eq!(
+            res.deliveries.into_iter().map(|v| v.id).collect::<Vec<_>>(),
+            vec![hex("03"), hex("04")]
+        );
+
+        let res: DeliveriesResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::DeliveriesByRecipient {
+                recipient: "recipient_a".to_string(),
+                offset: None,
+                limit: Some(2),
+                order: Some(Order::Desc),
+            },
+        );
+        assert_eq!(
+            res.deliveries.into_iter().map(|v| v.id).collect::<Vec<_>>(),
+            vec![hex("04"), hex("02")]
+        );
+    }
+
+    #[rstest]
+    fn test_query_pause_state() {
+        let mut deps = mock_dependencies();
+
+        hpl_pausable::initialize(deps.as_mut().storage, &true).unwrap();
+        PROCESS_PAUSED.save(deps.as_mut().storage, &true).unwrap();
+        for domain in [3u32, 1, 2] {
+            PAUSED_ORIGINS
+                .save(deps.as_mut().storage, domain, &Empty {})
+                .unwrap();
+        }
+
+        let res: PauseStateResponse = query(deps.as_ref(), MailboxQueryMsg::PauseState {});
+        assert_eq!(
+            res,
+            PauseStateResponse {
+                paused: true,
+                dispatch_paused: false,
+                process_paused: true,
+                paused_origins: vec![1, 2, 3],
+            }
+        );
+    }
+
+    #[rstest]
+    #[case(None, None, None, None, vec![(1, 3), (5, 1)], vec![(2, 7)], None)]
+    #[case(None, Some(1), None, None, vec![(5, 1)], vec![(2, 7)], None)]
+    #[case(None, None, Some(1), Some(Order::Desc), vec![(5, 1)], vec![(2, 7)], None)]
+    #[case(Some(vec![5, 2]), None, None, None, vec![(5, 1), (2, 0)], vec![(5, 0), (2, 7)], Some(12))]
+    fn test_query_stats(
+        #[case] domains: Option<Vec<u32>>,
+        #[case] offset: Option<u32>,
+        #[case] limit: Option<u32>,
+        #[case] order: Option<Order>,
+        #[case] outbound: Vec<(u32, u64)>,
+        #[case] inbound: Vec<(u32, u64)>,
+        #[case] start_nonce: Option<u32>,
+    ) {
+        let mut deps = mock_dependencies();
+
+        if let Some(start_nonce) = start_nonce {
+            STATS_START_NONCE
+                .save(deps.as_mut().storage, &start_nonce)
+                .unwrap();
+        }
+
+        for (domain, count) in [(1u32, 3u64), (5, 1)] {
+            OUTBOUND_COUNTS
+                .save(deps.as_mut().storage, domain, &count)
+                .unwrap();
+        }
+        INBOUND_COUNTS.save(deps.as_mut().storage, 2, &7).unwrap();
+
+        let to_counts = |v: Vec<(u32, u64)>| {
+            v.into_iter()
+                .map(|(domain, count)| DomainCount { domain, count })
+                .collect::<Vec<_>>()
+        };
+
+        let res: StatsResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::Stats {
+                domains,
+                offset,
+                limit,
+                order,
+            },
+        );
+        assert_eq!(
+            res,
+            StatsResponse {
+                outbound: to_counts(outbound),
+                inbound: to_counts(inbound),
+                start_nonce: start_nonce.unwrap_or_default(),
+            }
+        );
+    }
+
+    fn recipient_ism_handler(
+        res: ContractResult<Option<&'static str>>,
+    ) -> impl Fn(&WasmQuery) -> QuerierResult {
+        move |_| match res.clone() {
+            ContractResult::Ok(ism) => {
+                let res = ism::InterchainSecurityModuleResponse {
+                    ism: ism.map(Addr::unchecked),
+                };
+
+                SystemResult::Ok(cosmwasm_std::to_json_binary(&res).into())
+            }
+            ContractResult::Err(err) => SystemResult::Ok(ContractResult::Err(err)),
+        }
+    }
+
+    #[rstest]
+    #[case(None, ContractResult::Ok(None), false, "default_ism")]
+    #[case(
+        None,
+        ContractResult::Ok(Some("recipient_ism")),
+        false,
+        "recipient_ism"
+    )]
+    #[case(
+        None,
+        ContractResult::Err("codespace: wasm, code: 9".into()),
+        true,
+        "default_ism"
+    )]
+    #[should_panic(expected = "codespace: wasm, code: 9")]
+    #[case(
+        None,
+        ContractResult::Err("codespace: wasm, code: 9".into()),
+        false,
+        "default_ism"
+    )]
+    #[case(
+        Some("override_ism"),
+        ContractResult::Ok(Some("recipient_ism")),
+        false,
+        "override_ism"
+    )]
+    #[case(
+        Some("override_ism"),
+        ContractResult::Err("out of gas".into()),
+        false,
+        "override_ism"
+    )]
+    fn test_query_recipient_ism(
+        #[case] override_ism: Option<&str>,
+        #[case] recipient_res: ContractResult<Option<&'static str>>,
+        #[case] fallback: bool,
+        #[case] expected: &str,
+    ) {
+        let mut deps = mock_dependencies();
+
+        deps.querier
+            .update_wasm(recipient_ism_handler(recipient_res));
+
+        if fallback {
+            ISM_FALLBACKS
+                .save(
+                    deps.as_mut().storage,
+                    Addr::unchecked("recipient"),
+                    &Empty {},
+                )
+                .unwrap();
+        }
+
+        let res: IsmFallbackResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::IsmFallback {
+                recipient: "recipient".to_string(),
+            },
+        );
+        assert_eq!(res.enabled, fallback);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", 123).with_ism(Addr::unchecked("default_ism")),
+            )
+            .unwrap();
+
+        if let Some(ism) = override_ism {
+            RECIPIENT_ISMS
+                .save(
+                    deps.as_mut().storage,
+                    Addr::unchecked("recipient"),
+                    &Addr::unchecked(ism),
+                )
+                .unwrap();
+        }
+
+        let res: RecipientIsmResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::RecipientIsm {
+                recipient_addr: "recipient".to_string(),
+                origin: None,
+            },
+        );
+        assert_eq!(res.ism, expected);
+
+        let res: RecipientIsmOverridesResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::RecipientIsmOverrides {
+                offset: None,
+                limit: None,
+                order: None,
+            },
+        );
+        assert_eq!(
+            res.overrides,
+            override_ism
+                .map(|ism| RecipientIsmOverride {
+                    recipient: "recipient".to_string(),
+                    ism: ism.to_string(),
+                })
+                .into_iter()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case(None, Some(1), "origin_ism")]
+    #[case(None, Some(2), "default_ism")]
+    #[case(None, None, "default_ism")]
+    #[case(Some("recipient_ism"), Some(1), "recipient_ism")]
+    fn test_query_origin_default_ism(
+        #[case] recipient_ism: Option<&'static str>,
+        #[case] origin: Option<u32>,
+        #[case] expected: &str,
+    ) {
+        let mut deps = mock_dependencies();
+
+        deps.querier
+            .update_wasm(recipient_ism_handler(ContractResult::Ok(recipient_ism)));
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", 123).with_ism(Addr::unchecked("default_ism")),
+            )
+            .unwrap();
+
+        ORIGIN_DEFAULT_ISMS
+            .save(deps.as_mut().storage, 1, &Addr::unchecked("origin_ism"))
+            .unwrap();
+        ORIGIN_DEFAULT_ISMS
+            .save(deps.as_mut().storage, 3, &Addr::unchecked("other_ism"))
+            .unwrap();
+
+        let res: RecipientIsmResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::RecipientIsm {
+                recipient_addr: "recipient".to_string(),
+                origin,
+            },
+        );
+        assert_eq!(res.ism, expected);
+
+        let res: OriginDefaultIsmsResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::OriginDefaultIsms {
+                offset: None,
+                limit: None,
+                order: Some(Order::Desc),
+            },
+        );
+        assert_eq!(
+            res.isms,
+            vec![
+                OriginDefaultIsm {
+                    origin: 3,
+                    ism: "other_ism".to_string()
+                },
+                OriginDefaultIsm {
+                    origin: 1,
+                    ism: "origin_ism".to_string()
+                },
+            ]
+        );
+    }
+
+    fn diagnostics_handler(query: &WasmQuery) -> QuerierResult {
+        let (contract_addr, msg) = match query {
+            WasmQuery::Smart { contract_addr, msg } => (contract_addr.as_str(), msg),
+            _ => unreachable!("wrong query type"),
+        };
+
+        let res = match contract_addr {
+            "broken" => return SystemResult::Ok(ContractResult::Err("broken".into())),
+            "hook" | "unrestricted_hook" | "foreign_hook" => {
+                let _: ExpectedHookQueryMsg = from_binary(msg).unwrap();
+
+                let mailbox = match contract_addr {
+                    "hook" => mock_env().contract.address.to_string(),
+                    "unrestricted_hook" => UNRESTRICTED_MAILBOX.to_string(),
+                    _ => "another_mailbox".to_string(),
+                };
+                cosmwasm_std::to_binary(&MailboxResponse { mailbox })
+            }
+            _ => {
+                let _: ism::ExpectedIsmQueryMsg = from_binary(msg).unwrap();
+
+                cosmwasm_std::to_binary(&ModuleTypeResponse {
+                    typ: ism::IsmType::MessageIdMultisig,
+                })
+            }
+        };
+
+        SystemResult::Ok(res.into())
+    }
+
+    #[rstest]
+    #[case(Some("ism"), Some("hook"), Some("unrestricted_hook"), None, true)]
+    #[case(None, Some("hook"), Some("hook"), None, false)]
+    #[case(Some("ism"), Some("foreign_hook"), Some("hook"), None, false)]
+    #[case(Some("ism"), Some("hook"), None, None, false)]
+    #[case(Some("broken"), Some("hook"), Some("hook"), None, false)]
+    #[case(Some("ism"), Some("hook"), Some("hook"), Some("broken"), false)]
+    fn test_query_diagnostics(
+        #[case] default_ism: Option<&str>,
+        #[case] default_hook: Option<&str>,
+        #[case] required_hook: Option<&str>,
+        #[case] origin_ism: Option<&str>,
+        #[case] healthy: bool,
+    ) {
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(diagnostics_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    default_ism: default_ism.map(Addr::unchecked),
+                    default_hook: default_hook.map(Addr::unchecked),
+                    required_hook: required_hook.map(Addr::unchecked),
+                    ..Config::new("osmo", 123)
+                },
+            )
+            .unwrap();
+
+        if let Some(ism) = origin_ism {
+            ORIGIN_DEFAULT_ISMS
+                .save(deps.as_mut().storage, 1, &Addr::unchecked(ism))
+                .unwrap();
+        }
+
+        let res: DiagnosticsResponse = query(deps.as_ref(), MailboxQueryMsg::Diagnostics {});
+        assert_eq!(res.healthy, healthy);
+
+        match default_ism {
+            Some("ism") => assert_eq!(
+                res.default_ism.module_type,
+                Some(ism::IsmType::MessageIdMultisig)
+            ),
+            Some(_) => assert!(res.default_ism.error.is_some()),
+            None => assert_eq!(
+                res.default_ism.error,
+                Some("default_ism not set".to_string())
+            ),
+        }
+
+        if required_hook.is_none() {
+            assert_eq!(
+                res.required_hook.error,
+                Some("required_hook not set".to_string())
+            );
+        }
+
+        if default_hook == Some("foreign_hook") {
+            assert_eq!(
+                res.default_hook.mailbox,
+                Some("another_mailbox".to_string())
+            );
+            assert!(res.default_hook.error.is_some());
+        }
+
+        assert_eq!(res.origin_default_isms.len(), origin_ism.iter().count());
+    }
+
+    #[rstest]
+    #[case("uosmo", vec![coin(300, "uosmo")])]
+    #[case("uatom", vec![coin(100, "uosmo"), coin(200, "uatom")])]
+    fn test_quote_dispatch(#[case] hook_denom: &'static str, #[case] expected: Vec<Coin>) {
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } => {
+                let gas_amount = match contract_addr.as_str() {
+                    "required_hook" => coin(100, "uosmo"),
+                    _ => coin(200, hook_denom),
+                };
+                let res = hook::QuoteDispatchResponse {
+                    gas_amount: Some(gas_amount),
+                };
+
+                SystemResult::Ok(cosmwasm_std::to_json_binary(&res).into())
+            }
+            _ => unreachable!("unexpected query"),
+        });
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", 123).with_hook(
+                    Addr::unchecked("default_hook"),
+                    Addr::unchecked("required_hook"),
+                ),
+            )
+            .unwrap();
+        NONCE.save(deps.as_mut().storage, &0).unwrap();
+
+        let msg = DispatchMsg::new(1, gen_bz(32), gen_bz(123));
+        let res: DispatchQuoteResponse = from_binary(
+            &contract::query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Hook(MailboxHookQueryMsg::QuoteDispatch(msg)),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(res.required_hook_fee, Some(coin(100, "uosmo")));
+        assert_eq!(res.hook_fee, Some(coin(200, hook_denom)));
+        assert_eq!(res.total, expected);
+    }
 }
@@ -1,6 +1,9 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Coin, Empty, HexBinary, Timestamp};
 use cw_storage_plus::{Item, Map};
+use hpl_interface::core::mailbox::ProcessResult;
+
+use crate::ContractError;
 
 #[cw_serde]
 #[derive(Default)]
@@ -33,16 +36,22 @@ impl Config {
         self
     }
 
-    pub fn get_default_ism(&self) -> Addr {
-        self.default_ism.clone().expect("default_ism not set")
+    pub fn get_default_ism(&self) -> Result<Addr, ContractError> {
+        self.default_ism
+            .clone()
+            .ok_or(ContractError::DefaultIsmNotSet {})
     }
 
-    pub fn get_default_hook(&self) -> Addr {
-        self.default_hook.clone().expect("default_hook not set")
+    pub fn get_default_hook(&self) -> Result<Addr, ContractError> {
+        self.default_hook
+            .clone()
+            .ok_or(ContractError::DefaultHookNotSet {})
     }
 
-    pub fn get_required_hook(&self) -> Addr {
-        self.required_hook.clone().expect("required_hook not set")
+    pub fn get_required_hook(&self) -> Result<Addr, ContractError> {
+        self.required_hook
+            .clone()
+            .ok_or(ContractError::RequiredHookNotSet {})
     }
 }
 
@@ -50,6 +59,42 @@ impl Config {
 pub struct Delivery {
     pub sender: Addr,
     pub block_number: u64,
+    // not recorded for deliveries made before these fields were introduced
+    pub block_time: Option<Timestamp>,
+    pub ism: Option<Addr>,
+    pub recipient: Option<Addr>,
+    pub origin_domain: Option<u32>,
+    // funds forwarded to the recipient's handle
+    #[serde(default)]
+    pub value: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct Dispatch {
+    pub id: HexBinary,
+    pub message: HexBinary,
+    pub sender: Addr,
+    pub dest_domain: u32,
+    pub block_number: u64,
+}
+
+#[cw_serde]
+pub struct FailedDelivery {
+    pub message: HexBinary,
+    pub error: String,
+    pub block_number: u64,
+    // funds returned by the failed handle, forwarded again on retry
+    #[serde(default)]
+    pub value: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct PendingHandle {
+    pub message: HexBinary,
+    pub value: Vec<Coin>,
+    // position of the message in the latest process batch, if it was delivered in one
+    #[serde(default)]
+    pub batch_index: Option<u32>,
 }
 
 pub const CONFIG_KEY: &str = "config";
@@ -61,5 +106,73 @@ pub const NONCE: Item<u32> = Item::new(NONCE_KEY);
 pub const LATEST_DISPATCHED_ID_KEY: &str = "latest_dispatched_id";
 pub const LATEST_DISPATCHED_ID: Item<Vec<u8>> = Item::new(LATEST_DISPATCHED_ID_KEY);
 
+// ids of every message of the latest dispatch call, for hooks called on a batch
+pub const LATEST_DISPATCH_BATCH_KEY: &str = "latest_dispatch_batch";
+pub const LATEST_DISPATCH_BATCH: Item<Vec<HexBinary>> = Item::new(LATEST_DISPATCH_BATCH_KEY);
+
+pub const LATEST_PROCESS_BATCH_KEY: &str = "latest_process_batch";
+pub const LATEST_PROCESS_BATCH: Item<Vec<ProcessResult>> = Item::new(LATEST_PROCESS_BATCH_KEY);
+
 pub const DELIVERIES_PREFIX: &str = "deliveries";
 pub const DELIVERIES: Map<Vec<u8>, Delivery> = Map::new(DELIVERIES_PREFIX);
+
+pub const DELIVERIES_BY_ORIGIN_PREFIX: &str = "deliveries_by_origin";
+pub const DELIVERIES_BY_ORIGIN: Map<(u32, Vec<u8>), Empty> = Map::new(DELIVERIES_BY_ORIGIN_PREFIX);
+
+pub const DELIVERIES_BY_RECIPIENT_PREFIX: &str = "deliveries_by_recipient";
+pub const DELIVERIES_BY_RECIPIENT: Map<(Addr, Vec<u8>), Empty> =
+    Map::new(DELIVERIES_BY_RECIPIENT_PREFIX);
+
+pub const DISPATCHES_PREFIX: &str = "dispatches";
+pub const DISPATCHES: Map<u32, Dispatch> = Map::new(DISPATCHES_PREFIX);
+
+pub const DISPATCH_NONCES_PREFIX: &str = "dispatch_nonces";
+pub const DISPATCH_NONCES: Map<Vec<u8>, u32> = Map::new(DISPATCH_NONCES_PREFIX);
+
+pub const DISPATCH_RETENTION_KEY: &str = "dispatch_retention";
+pub const DISPATCH_RETENTION: Item<u32> = Item::new(DISPATCH_RETENTION_KEY);
+
+pub const HANDLE_ISOLATION_KEY: &str = "handle_isolation";
+pub const HANDLE_ISOLATION: Item<bool> = Item::new(HANDLE_ISOLATION_KEY);
+
+pub const OUTBOUND_COUNTS_PREFIX: &str = "outbound_counts";
+pub const OUTBOUND_COUNTS: Map<u32, u64> = Map::new(OUTBOUND_COUNTS_PREFIX);
+
+pub const INBOUND_COUNTS_PREFIX: &str = "inbound_counts";
+pub const INBOUND_COUNTS: Map<u32, u64> = Map::new(INBOUND_COUNTS_PREFIX);
+
+pub const STATS_START_NONCE_KEY: &str = "stats_start_nonce";
+pub const STATS_START_NONCE: Item<u32> = Item::new(STATS_START_NONCE_KEY);
+
+pub const SIGNED_NONCES_PREFIX: &str = "signed_nonces";
+pub const SIGNED_NONCES: Map<Vec<u8>, u64> = Map::new(SIGNED_NONCES_PREFIX);
+
+pub const LOCAL_DELIVERY_KEY: &str = "local_delivery";
+pub const LOCAL_DELIVERY: Item<bool> = Item::new(LOCAL_DELIVERY_KEY);
+
+pub const ISM_FALLBACKS_PREFIX: &str = "ism_fallbacks";
+pub const ISM_FALLBACKS: Map<Addr, Empty> = Map::new(ISM_FALLBACKS_PREFIX);
+
+pub const HANDLE_REPLY_ID_KEY: &str = "handle_reply_id";
+pub const HANDLE_REPLY_ID: Item<u64> = Item::new(HANDLE_REPLY_ID_KEY);
+
+pub const PENDING_HANDLES_PREFIX: &str = "pending_handles";
+pub const PENDING_HANDLES: Map<u64, PendingHandle> = Map::new(PENDING_HANDLES_PREFIX);
+
+pub const FAILED_DELIVERIES_PREFIX: &str = "failed_deliveries";
+pub const FAILED_DELIVERIES: Map<Vec<u8>, FailedDelivery> = Map::new(FAILED_DELIVERIES_PREFIX);
+
+pub const DISPATCH_PAUSED_KEY: &str = "dispatch_paused";
+pub const DISPATCH_PAUSED: Item<bool> = Item::new(DISPATCH_PAUSED_KEY);
+
+pub const PROCESS_PAUSED_KEY: &str = "process_paused";
+pub const PROCESS_PAUSED: Item<bool> = Item::new(PROCESS_PAUSED_KEY);
+
+pub const PAUSED_ORIGINS_PREFIX: &str = "paused_origins";
+pub const PAUSED_ORIGINS: Map<u32, Empty> = Map::new(PAUSED_ORIGINS_PREFIX);
+
+pub const RECIPIENT_ISMS_PREFIX: &str = "recipient_isms";
+pub const RECIPIENT_ISMS: Map<Addr, Addr> = Map::new(RECIPIENT_ISMS_PREFIX);
+
+pub const ORIGIN_DEFAULT_ISMS_PREFIX: &str = "origin_default_isms";
+pub const ORIGIN_DEFAULT_ISMS: Map<u32, Addr> = Map::new(ORIGIN_DEFAULT_ISMS_PREFIX);
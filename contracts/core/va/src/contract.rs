@@ -1,7 +1,7 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, ensure_eq, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo, Order,
+    ensure, ensure_eq, Binary, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo,
     QueryResponse, Response, StdResult,
 };
 
@@ -9,20 +9,31 @@ use hpl_interface::{
     core::{
         mailbox::{self, MailboxQueryMsg},
         va::{
-            ExecuteMsg, GetAnnounceStorageLocationsResponse, GetAnnouncedValidatorsResponse,
-            InstantiateMsg, QueryMsg,
+            Checkpoint, CheckpointResponse, ExecuteMsg, GetAnnounceStorageLocationsResponse,
+            GetAnnouncedValidatorsResponse, InstantiateMsg, ListCheckpointsResponse, MigrateMsg,
+            QueryMsg, SignedCheckpoint, ValidatorInfo, ValidatorKeyType,
         },
     },
-    to_binary,
-    types::{bech32_decode, eth_addr, eth_hash, keccak256_hash},
+    get_and_check_limit, range_option, to_binary,
+    types::{
+        bech32_decode, eth_hash, keccak256_hash, pub_to_addr, recover_eth_addr, sha256_digest,
+    },
+    Order, DEFAULT_LIMIT, MAX_LIMIT,
 };
 
 use crate::{
     error::ContractError,
-    state::{LOCAL_DOMAIN, MAILBOX, REPLAY_PROTECITONS, STORAGE_LOCATIONS, VALIDATORS},
+    state::{
+        CosmosValidator, CHECKPOINTS, COSMOS_VALIDATORS, LATEST_CHECKPOINT, LOCAL_DOMAIN, MAILBOX,
+        MAX_STORAGE_LOCATIONS, REPLAY_PROTECITONS, STORAGE_LOCATIONS, VALIDATORS,
+    },
     CONTRACT_NAME, CONTRACT_VERSION,
 };
 
+const ANNOUNCEMENT_TAG: &str = "HYPERLANE_ANNOUNCEMENT";
+const REVOCATION_TAG: &str = "HYPERLANE_ANNOUNCEMENT_REVOKE";
+const REPLACEMENT_TAG: &str = "HYPERLANE_ANNOUNCEMENT_REPLACE";
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -67,43 +78,203 @@ pub fn execute(
             storage_location,
             signature,
         } => announce(deps, info, validator, storage_location, signature),
+        ExecuteMsg::AnnounceCosmos {
+            validator,
+            pubkey,
+            signature,
+            storage_location,
+        } => announce_cosmos(deps, info, validator, pubkey, storage_location, signature),
+        ExecuteMsg::Revoke {
+            validator,
+            storage_location,
+            signature,
+        } => revoke(deps, info, validator, storage_location, signature),
+        ExecuteMsg::Replace {
+            validator,
+            old_storage_location,
+            new_storage_location,
+            signature,
+        } => replace(
+            deps,
+            info,
+            validator,
+            old_storage_location,
+            new_storage_location,
+            signature,
+        ),
+        ExecuteMsg::PostCheckpoint {
+            validator,
+            checkpoint,
+            signature,
+        } => post_checkpoint(deps, info, validator, checkpoint, signature),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
-        QueryMsg::GetAnnounceStorageLocations { validators } => {
-            to_binary(get_announce(deps, validators))
-        }
-        QueryMsg::GetAnnouncedValidators {} => to_binary(get_validators(deps)),
+        QueryMsg::GetAnnounceStorageLocations {
+            validators,
+            offset,
+            limit,
+            order,
+        } => to_binary(get_announce(deps, validators, offset, limit, order)),
+        QueryMsg::GetAnnouncedValidators {
+            offset,
+            limit,
+            order,
+        } => to_binary(get_validators(deps, offset, limit, order)),
+        QueryMsg::GetCheckpoint {
+            validator,
+            merkle_tree_hook,
+            index,
+        } => to_binary(get_checkpoint(deps, validator, merkle_tree_hook, index)),
+        QueryMsg::LatestCheckpoint {
+            validator,
+            merkle_tree_hook,
+        } => to_binary(get_latest_checkpoint(deps, validator, merkle_tree_hook)),
+        QueryMsg::ListCheckpoints {
+            validator,
+            merkle_tree_hook,
+            offset,
+            limit,
+            order,
+        } => to_binary(list_checkpoints(
+            deps,
+            validator,
+            merkle_tree_hook,
+            offset,
+            limit,
+            order,
+        )),
     }
 }
 
 fn get_announce(
     deps: Deps,
-    validators: Vec<HexBinary>,
+    validators: Option<Vec<HexBinary>>,
+    offset: Option<HexBinary>,
+    limit: Option<u32>,
+    order: Option<Order>,
 ) -> Result<GetAnnounceStorageLocationsResponse, ContractError> {
-    let storage_locations = validators
-        .into_iter()
+    let storage_locations = match validators {
+        Some(validators) => {
+            get_and_check_limit(Some(validators.len() as u32), MAX_LIMIT, DEFAULT_LIMIT)?;
+
+            validators
+                .into_iter()
+                .map(|v| {
+                    let storage_locations = STORAGE_LOCATIONS
+                        .may_load(deps.storage, v.to_vec())?
+                        .unwrap_or_default();
+                    Ok((v.to_hex(), storage_locations))
+                })
+                .collect::<StdResult<Vec<_>>>()?
+        }
+        None => {
+            let ((min, max), limit, order) =
+                range_option(offset.map(|v| v.to_vec()), limit, order)?;
+
+            STORAGE_LOCATIONS
+                .range(deps.storage, min, max, order.into())
+                .take(limit)
+                .map(|item| {
+                    let (validator, storage_locations) = item?;
+
+                    Ok((HexBinary::from(validator).to_hex(), storage_locations))
+                })
+                .collect::<StdResult<Vec<_>>>()?
+        }
+    };
+
+    Ok(GetAnnounceStorageLocationsResponse { storage_locations })
+}
+
+fn get_validators(
+    deps: Deps,
+    offset: Option<HexBinary>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<GetAnnouncedValidatorsResponse, ContractError> {
+    let ((min, max), limit, order) = range_option(offset.map(|v| v.to_vec()), limit, order)?;
+
+    let validator_infos = VALIDATORS
+        .keys(deps.storage, min, max, order.into())
+        .take(limit)
         .map(|v| {
-            let storage_locations = STORAGE_LOCATIONS
-                .may_load(deps.storage, v.to_vec())?
-                .unwrap_or_default();
-            Ok((v.to_hex(), storage_locations))
+            let v = v?;
+
+            Ok(match COSMOS_VALIDATORS.may_load(deps.storage, v.clone())? {
+                Some(cosmos) => ValidatorInfo {
+                    validator: HexBinary::from(v).to_hex(),
+                    key_type: ValidatorKeyType::Cosmos,
+                    address: Some(cosmos.address),
+                },
+                None => ValidatorInfo {
+                    validator: HexBinary::from(v).to_hex(),
+                    key_type: ValidatorKeyType::Ethereum,
+                    address: None,
+                },
+            })
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    Ok(GetAnnounceStorageLocationsResponse { storage_locations })
+    Ok(GetAnnouncedValidatorsResponse {
+        validators: validator_infos
+            .iter()
+            .map(|v| v.validator.clone())
+            .collect(),
+        validator_infos,
+    })
+}
+
+fn get_checkpoint(
+    deps: Deps,
+    validator: HexBinary,
+    merkle_tree_hook: HexBinary,
+    index: u32,
+) -> Result<CheckpointResponse, ContractError> {
+    let checkpoint = CHECKPOINTS.may_load(
+        deps.storage,
+        (validator.to_vec(), merkle_tree_hook.to_vec(), index),
+    )?;
+
+    Ok(CheckpointResponse { checkpoint })
+}
+
+fn get_latest_checkpoint(
+    deps: Deps,
+    validator: HexBinary,
+    merkle_tree_hook: HexBinary,
+) -> Result<CheckpointResponse, ContractError> {
+    let key = (validator.to_vec(), merkle_tree_hook.to_vec());
+
+    let checkpoint = match LATEST_CHECKPOINT.may_load(deps.storage, key.clone())? {
+        Some(index) => CHECKPOINTS.may_load(deps.storage, (key.0, key.1, index))?,
+        None => None,
+    };
+
+    Ok(CheckpointResponse { checkpoint })
 }
 
-fn get_validators(deps: Deps) -> Result<GetAnnouncedValidatorsResponse, ContractError> {
-    let validators = VALIDATORS
-        .keys(deps.storage, None, None, Order::Ascending)
-        .map(|v| v.map(HexBinary::from).map(|v| v.to_hex()))
+fn list_checkpoints(
+    deps: Deps,
+    validator: HexBinary,
+    merkle_tree_hook: HexBinary,
+    offset: Option<u32>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<ListCheckpointsResponse, ContractError> {
+    let ((min, max), limit, order) = range_option(offset, limit, order)?;
+
+    let checkpoints = CHECKPOINTS
+        .prefix((validator.to_vec(), merkle_tree_hook.to_vec()))
+        .range(deps.storage, min, max, order.into())
+        .take(limit)
+        .map(|item| item.map(|(_, checkpoint)| checkpoint))
         .collect::<StdResult<Vec<_>>>()?;
 
-    Ok(GetAnnouncedValidatorsResponse { validators })
+    Ok(ListCheckpointsResponse { checkpoints })
 }
 
 fn replay_hash(validator: &HexBinary, storage_location: &str) -> StdResult<HexBinary> {
@@ -114,11 +285,11 @@ fn replay_hash(validator: &HexBinary, storage_location: &str) -> StdResult<HexBi
     ))
 }
 
-fn domain_hash(local_domain: u32, mailbox: HexBinary) -> StdResult<HexBinary> {
+fn domain_hash(local_domain: u32, mailbox: HexBinary, tag: &str) -> StdResult<HexBinary> {
     let mut bz = vec![];
     bz.append(&mut local_domain.to_be_bytes().to_vec());
     bz.append(&mut mailbox.to_vec());
-    bz.append(&mut "HYPERLANE_ANNOUNCEMENT".as_bytes().to_vec());
+    bz.append(&mut tag.as_bytes().to_vec());
 
     let hash = keccak256_hash(&bz);
 
@@ -133,6 +304,107 @@ fn announcement_hash(mut domain_hash: Vec<u8>, storage_location: &str) -> HexBin
     keccak256_hash(&bz)
 }
 
+fn replacement_hash(mut domain_hash: Vec<u8>, old_location: &str, new_location: &str) -> HexBinary {
+    let mut bz = vec![];
+    bz.append(&mut domain_hash);
+    bz.append(&mut keccak256_hash(old_location.as_bytes()).to_vec());
+    bz.append(&mut new_location.as_bytes().to_vec());
+
+    keccak256_hash(&bz)
+}
+
+/// Digest of a merkle checkpoint, as signed by validators for the multisig ISMs.
+fn checkpoint_hash(local_domain: u32, checkpoint: &Checkpoint) -> StdResult<HexBinary> {
+    let mut bz = vec![];
+    bz.append(
+        &mut domain_hash(
+            local_domain,
+            checkpoint.merkle_tree_hook.clone(),
+            "HYPERLANE",
+        )?
+        .to_vec(),
+    );
+    bz.append(&mut checkpoint.root.to_vec());
+    bz.append(&mut checkpoint.index.to_be_bytes().to_vec());
+    bz.append(&mut checkpoint.message_id.to_vec());
+
+    Ok(keccak256_hash(&bz))
+}
+
+/// Builds the domain hash for `tag` from the stored local domain and mailbox.
+fn load_domain_hash(deps: Deps, tag: &str) -> StdResult<Vec<u8>> {
+    let local_domain = LOCAL_DOMAIN.load(deps.storage)?;
+    let mailbox_addr = MAILBOX.load(deps.storage)?;
+
+    Ok(domain_hash(local_domain, mailbox_addr.into(), tag)?.to_vec())
+}
+
+/// Sign bytes of an ADR-36 `MsgSignData` carrying `data`, as produced by cosmos wallets.
+fn adr36_sign_bytes(signer: &str, data: &[u8]) -> Vec<u8> {
+    format!(
+        concat!(
+            r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","#,
+            r#""msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"#,
+            r#""sequence":"0"}}"#
+        ),
+        Binary::from(data).to_base64(),
+        signer
+    )
+    .into_bytes()
+}
+
+/// Checks `signature` over `digest` was made by `validator`. Ethereum validators sign the
+/// eth-prefixed digest, while cosmos validators sign it through ADR-36.
+fn verify_signature(
+    deps: Deps,
+    validator: &HexBinary,
+    digest: HexBinary,
+    signature: &HexBinary,
+) -> Result<(), ContractError> {
+    if let Some(cosmos) = COSMOS_VALIDATORS.may_load(deps.storage, validator.to_vec())? {
+        let sign_bytes = adr36_sign_bytes(&cosmos.address, &digest);
+
+        ensure!(
+            signature.len() == 64
+                && deps.api.secp256k1_verify(
+                    &sha256_digest(sign_bytes)?,
+                    signature,
+                    &cosmos.pubkey
+                )?,
+            ContractError::VerifyFailed {}
+        );
+
+        return Ok(());
+    }
+
+    let message_hash = eth_hash(digest)?;
+
+    // recover the signer & verify
+    ensure_eq!(
+        &recover_eth_addr(deps.api, &message_hash, signature)?,
+        validator,
+        ContractError::VerifyFailed {}
+    );
+
+    Ok(())
+}
+
+/// Marks the location as announced, so that the same announcement can't be used again.
+fn check_replay(
+    deps: DepsMut,
+    validator: &HexBinary,
+    storage_location: &str,
+) -> Result<(), ContractError> {
+    let replay_id = replay_hash(validator, storage_location)?;
+    ensure!(
+        !REPLAY_PROTECITONS.has(deps.storage, replay_id.to_vec()),
+        ContractError::Unauthorized {}
+    );
+    REPLAY_PROTECITONS.save(deps.storage, replay_id.to_vec(), &Empty {})?;
+
+    Ok(())
+}
+
 fn announce(
     deps: DepsMut,
     info: MessageInfo,
@@ -140,37 +412,66 @@ fn announce(
     storage_location: String,
     signature: HexBinary,
 ) -> Result<Response, ContractError> {
-    // check replay protection
-    let replay_id = replay_hash(&validator, &storage_location)?;
+    let event = add_storage_location(deps, validator, storage_location, signature)?
+        .add_attribute("sender", info.sender);
+
+    Ok(Response::new().add_event(event))
+}
+
+fn announce_cosmos(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    pubkey: HexBinary,
+    storage_location: String,
+    signature: HexBinary,
+) -> Result<Response, ContractError> {
+    // the address has to belong to the pubkey
+    let validator = pub_to_addr(pubkey.clone())?;
+    ensure_eq!(
+        bech32_decode(&address)?,
+        validator.to_vec(),
+        ContractError::VerifyFailed {}
+    );
+
+    // an identity can't switch between key types
     ensure!(
-        !REPLAY_PROTECITONS.has(deps.storage, replay_id.to_vec()),
+        COSMOS_VALIDATORS.has(deps.storage, validator.to_vec())
+            || !VALIDATORS.has(deps.storage, validator.to_vec()),
         ContractError::Unauthorized {}
     );
-    REPLAY_PROTECITONS.save(deps.storage, replay_id.to_vec(), &Empty {})?;
+    COSMOS_VALIDATORS.save(
+        deps.storage,
+        validator.to_vec(),
+        &CosmosValidator {
+            address: address.clone(),
+            pubkey,
+        },
+    )?;
 
-    // make announcement digest
-    let local_domain = LOCAL_DOMAIN.load(deps.storage)?;
-    let mailbox_addr = MAILBOX.load(deps.storage)?;
+    let event = add_storage_location(deps, validator, storage_location, signature)?
+        .add_attribute("sender", info.sender)
+        .add_attribute("address", address);
 
-    // make digest
-    let message_hash = eth_hash(announcement_hash(
-        domain_hash(local_domain, mailbox_addr.into())?.to_vec(),
-        &storage_location,
-    ))?;
-
-    // recover pubkey from signature & verify
-    let pubkey = deps.api.secp256k1_recover_pubkey(
-        &message_hash,
-        &signature.as_slice()[..64],
-        // We subs 27 according to this - https://eips.ethereum.org/EIPS/eip-155
-        signature[64] - 27,
-    )?;
+    Ok(Response::new().add_event(event))
+}
 
-    ensure_eq!(
-        eth_addr(pubkey.into())?,
-        validator,
-        ContractError::VerifyFailed {}
+/// Verifies an announcement and appends its location. Returns the announcement event.
+fn add_storage_location(
+    mut deps: DepsMut,
+    validator: HexBinary,
+    storage_location: String,
+    signature: HexBinary,
+) -> Result<Event, ContractError> {
+    // check replay protection
+    check_replay(deps.branch(), &validator, &storage_location)?;
+
+    // make digest & verify
+    let digest = announcement_hash(
+        load_domain_hash(deps.as_ref(), ANNOUNCEMENT_TAG)?,
+        &storage_location,
     );
+    verify_signature(deps.as_ref(), &validator, digest, &signature)?;
 
     // save validator if not saved yet
     if !VALIDATORS.has(deps.storage, validator.to_vec()) {
@@ -181,17 +482,175 @@ fn announce(
     let mut storage_locations = STORAGE_LOCATIONS
         .may_load(deps.storage, validator.to_vec())?
         .unwrap_or_default();
+    ensure!(
+        storage_locations.len() < MAX_STORAGE_LOCATIONS,
+        ContractError::TooManyStorageLocations {
+            max: MAX_STORAGE_LOCATIONS
+        }
+    );
     storage_locations.push(storage_location.clone());
     STORAGE_LOCATIONS.save(deps.storage, validator.to_vec(), &storage_locations)?;
 
+    Ok(Event::new("validator-announcement")
+        .add_attribute("validator", validator.to_string())
+        .add_attribute("storage-location", storage_location))
+}
+
+fn revoke(
+    deps: DepsMut,
+    info: MessageInfo,
+    validator: HexBinary,
+    storage_location: String,
+    signature: HexBinary,
+) -> Result<Response, ContractError> {
+    let digest = announcement_hash(
+        load_domain_hash(deps.as_ref(), REVOCATION_TAG)?,
+        &storage_location,
+    );
+    verify_signature(deps.as_ref(), &validator, digest, &signature)?;
+
+    let mut storage_locations = STORAGE_LOCATIONS
+        .may_load(deps.storage, validator.to_vec())?
+        .unwrap_or_default();
+    let position = storage_locations
+        .iter()
+        .position(|v| v == &storage_location)
+        .ok_or(ContractError::StorageLocationNotFound {
+            location: storage_location.clone(),
+        })?;
+    storage_locations.remove(position);
+
+    // the validator is no longer listed once it has nothing announced
+    if storage_locations.is_empty() {
+        STORAGE_LOCATIONS.remove(deps.storage, validator.to_vec());
+        VALIDATORS.remove(deps.storage, validator.to_vec());
+    } else {
+        STORAGE_LOCATIONS.save(deps.storage, validator.to_vec(), &storage_locations)?;
+    }
+
     Ok(Response::new().add_event(
-        Event::new("validator-announcement")
+        Event::new("validator-announcement-revoke")
             .add_attribute("sender", info.sender)
             .add_attribute("validator", validator.to_string())
             .add_attribute("storage-location", storage_location),
     ))
 }
 
+fn replace(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    validator: HexBinary,
+    old_storage_location: String,
+    new_storage_location: String,
+    signature: HexBinary,
+) -> Result<Response, ContractError> {
+    // the new location counts as announced
+    check_replay(deps.branch(), &validator, &new_storage_location)?;
+
+    let digest = replacement_hash(
+        load_domain_hash(deps.as_ref(), REPLACEMENT_TAG)?,
+        &old_storage_location,
+        &new_storage_location,
+    );
+    verify_signature(deps.as_ref(), &validator, digest, &signature)?;
+
+    let mut storage_locations = STORAGE_LOCATIONS
+        .may_load(deps.storage, validator.to_vec())?
+        .unwrap_or_default();
+    let location = storage_locations
+        .iter_mut()
+        .find(|v| *v == &old_storage_location)
+        .ok_or(ContractError::StorageLocationNotFound {
+            location: old_storage_location.clone(),
+        })?;
+    *location = new_storage_location.clone();
+    STORAGE_LOCATIONS.save(deps.storage, validator.to_vec(), &storage_locations)?;
+
+    Ok(Response::new().add_event(
+        Event::new("validator-announcement-replace")
+            .add_attribute("sender", info.sender)
+            .add_attribute("validator", validator.to_string())
+            .add_attribute("old-storage-location", old_storage_location)
+            .add_attribute("new-storage-location", new_storage_location),
+    ))
+}
+
+fn post_checkpoint(
+    deps: DepsMut,
+    info: MessageInfo,
+    validator: HexBinary,
+    checkpoint: Checkpoint,
+    signature: HexBinary,
+) -> Result<Response, ContractError> {
+    ensure!(
+        VALIDATORS.has(deps.storage, validator.to_vec()),
+        ContractError::ValidatorNotAnnounced {
+            validator: validator.to_hex()
+        }
+    );
+
+    ensure!(
+        !COSMOS_VALIDATORS.has(deps.storage, validator.to_vec()),
+        ContractError::CosmosValidatorCheckpoint {
+            validator: validator.to_hex()
+        }
+    );
+
+    ensure!(
+        checkpoint.merkle_tree_hook.len() == 32,
+        ContractError::InvalidMerkleTreeHook {
+            len: checkpoint.merkle_tree_hook.len()
+        }
+    );
+
+    let index = checkpoint.index;
+    let tree = (validator.to_vec(), checkpoint.merkle_tree_hook.to_vec());
+    let key = (tree.0.clone(), tree.1.clone(), index);
+
+    // a posted checkpoint can't be overwritten
+    ensure!(
+        !CHECKPOINTS.has(deps.storage, key.clone()),
+        ContractError::CheckpointAlreadyPosted { index }
+    );
+
+    let digest = checkpoint_hash(LOCAL_DOMAIN.load(deps.storage)?, &checkpoint)?;
+    verify_signature(deps.as_ref(), &validator, digest, &signature)?;
+
+    let latest = LATEST_CHECKPOINT.may_load(deps.storage, tree.clone())?;
+    if !matches!(latest, Some(latest) if latest >= index) {
+        LATEST_CHECKPOINT.save(deps.storage, tree, &index)?;
+    }
+
+    let event = Event::new("validator-checkpoint")
+        .add_attribute("sender", info.sender)
+        .add_attribute("validator", validator.to_string())
+        .add_attribute("merkle-tree-hook", checkpoint.merkle_tree_hook.to_string())
+        .add_attribute("index", index.to_string())
+        .add_attribute("root", checkpoint.root.to_string())
+        .add_attribute("message-id", checkpoint.message_id.to_string());
+
+    CHECKPOINTS.save(
+        deps.storage,
+        key,
+        &SignedCheckpoint {
+            checkpoint,
+            signature,
+        },
+    )?;
+
+    Ok(Response::new().add_event(event))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
@@ -199,7 +658,10 @@ mod test {
         ContractResult, QuerierResult, SystemResult, WasmQuery,
     };
 
-    use hpl_interface::build_test_querier;
+    use hpl_interface::{
+        build_test_querier,
+        types::{bech32_encode, eth_addr},
+    };
     use ibcx_test_utils::{gen_addr, gen_bz, hex};
     use k256::{
         ecdsa::{RecoveryId, Signature, SigningKey},
@@ -263,7 +725,9 @@ mod test {
 
             // make announcement data
             let verify_digest = eth_hash(announcement_hash(
-                domain_hash(local_domain, mailbox.clone()).unwrap().to_vec(),
+                domain_hash(local_domain, mailbox.clone(), ANNOUNCEMENT_TAG)
+                    .unwrap()
+                    .to_vec(),
                 storage_location,
             ))
             .unwrap();
@@ -326,7 +790,10 @@ mod test {
     }
 
     #[rstest]
-    fn test_queries(#[values(0, 4)] validators_len: usize, #[values(0, 4)] locations_len: usize) {
+    fn test_queries(
+        #[values(0, 4, 12)] validators_len: usize,
+        #[values(0, 4)] locations_len: usize,
+    ) {
         let mut deps = mock_dependencies();
 
         let validators = (0..validators_len).map(|_| gen_bz(20)).collect::<Vec<_>>();
@@ -344,20 +811,120 @@ mod test {
                 .unwrap();
         }
 
-        let GetAnnouncedValidatorsResponse { validators } =
-            test_query(deps.as_ref(), QueryMsg::GetAnnouncedValidators {});
+        let GetAnnouncedValidatorsResponse { validators, .. } = test_query(
+            deps.as_ref(),
+            QueryMsg::GetAnnouncedValidators {
+                offset: None,
+                limit: Some(hpl_interface::MAX_LIMIT),
+                order: None,
+            },
+        );
         assert_eq!(validators.len(), validators_len);
 
+        // without pagination args, only the first page is returned
+        let GetAnnouncedValidatorsResponse {
+            validators: page, ..
+        } = test_query(
+            deps.as_ref(),
+            QueryMsg::GetAnnouncedValidators {
+                offset: None,
+                limit: None,
+                order: None,
+            },
+        );
+        assert_eq!(
+            page,
+            validators
+                .iter()
+                .take(hpl_interface::DEFAULT_LIMIT as usize)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        // paginated in key order
+        let GetAnnouncedValidatorsResponse {
+            validators: page, ..
+        } = test_query(
+            deps.as_ref(),
+            QueryMsg::GetAnnouncedValidators {
+                offset: validators.first().map(|v| hex(v)),
+                limit: Some(2),
+                order: None,
+            },
+        );
+        assert_eq!(
+            page,
+            validators
+                .iter()
+                .skip(1)
+                .take(2)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        let GetAnnounceStorageLocationsResponse { storage_locations } = test_query(
+            deps.as_ref(),
+            QueryMsg::GetAnnounceStorageLocations {
+                validators: None,
+                offset: None,
+                limit: Some(hpl_interface::MAX_LIMIT),
+                order: Some(Order::Desc),
+            },
+        );
+        assert_eq!(
+            storage_locations
+                .into_iter()
+                .map(|(validator, _)| validator)
+                .collect::<Vec<_>>(),
+            validators.iter().rev().cloned().collect::<Vec<_>>()
+        );
+
+        let GetAnnounceStorageLocationsResponse { storage_locations } = test_query(
+            deps.as_ref(),
+            QueryMsg::GetAnnounceStorageLocations {
+                validators: None,
+                offset: validators.first().map(|v| hex(v)),
+                limit: Some(2),
+                order: None,
+            },
+        );
+        assert_eq!(
+            storage_locations
+                .into_iter()
+                .map(|(validator, _)| validator)
+                .collect::<Vec<_>>(),
+            validators
+                .iter()
+                .skip(1)
+                .take(2)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        // the given validators are answered in the order asked
+        let asked = validators.iter().rev().cloned().collect::<Vec<_>>();
         let GetAnnounceStorageLocationsResponse { storage_locations } = test_query(
             deps.as_ref(),
             QueryMsg::GetAnnounceStorageLocations {
-                validators: validators.iter().map(|v| hex(v)).collect(),
+                validators: Some(asked.iter().map(|v| hex(v)).collect()),
+                offset: None,
+                limit: None,
+                order: None,
             },
         );
-        for (validator, locations) in storage_locations {
-            assert!(validators.contains(&validator));
+        assert_eq!(
+            storage_locations
+                .iter()
+                .map(|(validator, _)| validator.clone())
+                .collect::<Vec<_>>(),
+            asked
+        );
+        for (_, locations) in storage_locations {
             assert_eq!(locations.len(), locations_len);
         }
+
+        let oversized = (0..=hpl_interface::MAX_LIMIT).map(|_| gen_bz(20)).collect();
+        get_announce(deps.as_ref(), Some(oversized), None, None, None).unwrap_err();
     }
 
     #[rstest]
@@ -407,4 +974,432 @@ mod test {
             vec![announcement.location]
         );
     }
+
+    struct Signer {
+        key: SigningKey,
+        validator: HexBinary,
+    }
+
+    impl Signer {
+        fn new() -> Self {
+            let key = SigningKey::from(SecretKey::random(&mut OsRng));
+            let pubkey = key.verifying_key().to_encoded_point(false);
+            let validator = eth_addr(pubkey.as_bytes().into()).unwrap();
+
+            Self { key, validator }
+        }
+
+        fn sign(&self, digest: HexBinary) -> HexBinary {
+            let digest = eth_hash(digest).unwrap();
+
+            pack_signature(self.key.sign_prehash_recoverable(&digest).unwrap())
+        }
+    }
+
+    struct CosmosSigner {
+        key: SigningKey,
+        pubkey: HexBinary,
+        validator: HexBinary,
+        address: String,
+    }
+
+    impl CosmosSigner {
+        fn new() -> Self {
+            let key = SigningKey::from(SecretKey::random(&mut OsRng));
+            let pubkey: HexBinary = key.verifying_key().to_encoded_point(true).as_bytes().into();
+            let validator = pub_to_addr(pubkey.clone()).unwrap();
+            let address = bech32_encode("osmo", &validator).unwrap().to_string();
+
+            Self {
+                key,
+                pubkey,
+                validator,
+                address,
+            }
+        }
+
+        fn sign(&self, digest: HexBinary) -> HexBinary {
+            let sign_bytes = adr36_sign_bytes(&self.address, &digest);
+            let (signature, _) = self
+                .key
+                .sign_prehash_recoverable(&sha256_digest(sign_bytes).unwrap())
+                .unwrap();
+
+            signature.to_vec().into()
+        }
+    }
+
+    fn setup_announce(deps: DepsMut) {
+        LOCAL_DOMAIN.save(deps.storage, &26657).unwrap();
+        MAILBOX.save(deps.storage, &gen_bz(32).to_vec()).unwrap();
+    }
+
+    fn locations(deps: Deps, validator: &HexBinary) -> Vec<String> {
+        STORAGE_LOCATIONS
+            .may_load(deps.storage, validator.to_vec())
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    #[rstest]
+    fn test_revoke_and_replace() {
+        let mut deps = mock_dependencies();
+        setup_announce(deps.as_mut());
+
+        let signer = Signer::new();
+        let validator = signer.validator.clone();
+        let [announcement, revocation, replacement] =
+            [ANNOUNCEMENT_TAG, REVOCATION_TAG, REPLACEMENT_TAG]
+                .map(|tag| load_domain_hash(deps.as_ref(), tag).unwrap());
+
+        let sig_a = signer.sign(announcement_hash(announcement.clone(), "file://a"));
+        let sig_b = signer.sign(announcement_hash(announcement, "file://b"));
+        let revoke_sig = signer.sign(announcement_hash(revocation.clone(), "file://a"));
+        let replace_sig = signer.sign(replacement_hash(replacement, "file://b", "file://c"));
+
+        for (location, signature) in [("file://a", sig_a.clone()), ("file://b", sig_b)] {
+            announce(
+                deps.as_mut(),
+                mock_info("someone", &[]),
+                validator.clone(),
+                location.to_string(),
+                signature,
+            )
+            .unwrap();
+        }
+
+        // an announcement signature doesn't authorize a revocation
+        let err = revoke(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            validator.clone(),
+            "file://a".to_string(),
+            sig_a.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::VerifyFailed {}));
+
+        revoke(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            validator.clone(),
+            "file://a".to_string(),
+            revoke_sig.clone(),
+        )
+        .unwrap();
+        assert_eq!(locations(deps.as_ref(), &validator), vec!["file://b"]);
+
+        // revoked locations stay revoked
+        let err = revoke(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            validator.clone(),
+            "file://a".to_string(),
+            revoke_sig,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::StorageLocationNotFound { .. }));
+
+        let err = announce(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            validator.clone(),
+            "file://a".to_string(),
+            sig_a,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        replace(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            validator.clone(),
+            "file://b".to_string(),
+            "file://c".to_string(),
+            replace_sig,
+        )
+        .unwrap();
+        assert_eq!(locations(deps.as_ref(), &validator), vec!["file://c"]);
+        assert!(VALIDATORS.has(deps.as_ref().storage, validator.to_vec()));
+
+        // revoking the last location unlists the validator
+        let revoke_sig = signer.sign(announcement_hash(revocation, "file://c"));
+        revoke(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            validator.clone(),
+            "file://c".to_string(),
+            revoke_sig,
+        )
+        .unwrap();
+        assert!(locations(deps.as_ref(), &validator).is_empty());
+        assert!(!VALIDATORS.has(deps.as_ref().storage, validator.to_vec()));
+    }
+
+    #[rstest]
+    fn test_announce_cap() {
+        let mut deps = mock_dependencies();
+        setup_announce(deps.as_mut());
+
+        let signer = Signer::new();
+        let domain = load_domain_hash(deps.as_ref(), ANNOUNCEMENT_TAG).unwrap();
+
+        for i in 0..=MAX_STORAGE_LOCATIONS {
+            let location = format!("file://foo/{i}");
+            let res = announce(
+                deps.as_mut(),
+                mock_info("someone", &[]),
+                signer.validator.clone(),
+                location.clone(),
+                signer.sign(announcement_hash(domain.clone(), &location)),
+            );
+
+            if i < MAX_STORAGE_LOCATIONS {
+                res.unwrap();
+            } else {
+                assert!(matches!(
+                    res.unwrap_err(),
+                    ContractError::TooManyStorageLocations { .. }
+                ));
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_announce_cosmos() {
+        let mut deps = mock_dependencies();
+        setup_announce(deps.as_mut());
+
+        let signer = CosmosSigner::new();
+        let eth_signer = Signer::new();
+        let [announcement, revocation] = [ANNOUNCEMENT_TAG, REVOCATION_TAG]
+            .map(|tag| load_domain_hash(deps.as_ref(), tag).unwrap());
+
+        // the address has to be derived from the pubkey
+        let err = announce_cosmos(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            bech32_encode("osmo", &gen_bz(20)).unwrap().to_string(),
+            signer.pubkey.clone(),
+            "file://a".to_string(),
+            signer.sign(announcement_hash(announcement.clone(), "file://a")),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::VerifyFailed {}));
+
+        // an eth-style signature isn't accepted for a cosmos identity
+        let err = announce_cosmos(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            signer.address.clone(),
+            signer.pubkey.clone(),
+            "file://eth".to_string(),
+            pack_signature(
+                signer
+                    .key
+                    .sign_prehash_recoverable(
+                        &eth_hash(announcement_hash(announcement.clone(), "file://eth")).unwrap(),
+                    )
+                    .unwrap(),
+            ),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::VerifyFailed {}));
+
+        let res = announce_cosmos(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            signer.address.clone(),
+            signer.pubkey.clone(),
+            "file://a".to_string(),
+            signer.sign(announcement_hash(announcement.clone(), "file://a")),
+        )
+        .unwrap();
+        assert_eq!(
+            res.events[0].attributes.last().unwrap().value,
+            signer.address
+        );
+        assert_eq!(
+            locations(deps.as_ref(), &signer.validator),
+            vec!["file://a"]
+        );
+
+        announce(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            eth_signer.validator.clone(),
+            "file://b".to_string(),
+            eth_signer.sign(announcement_hash(announcement, "file://b")),
+        )
+        .unwrap();
+
+        // both identities are listed side by side
+        let mut expected = vec![
+            ValidatorInfo {
+                validator: signer.validator.to_hex(),
+                key_type: ValidatorKeyType::Cosmos,
+                address: Some(signer.address.clone()),
+            },
+            ValidatorInfo {
+                validator: eth_signer.validator.to_hex(),
+                key_type: ValidatorKeyType::Ethereum,
+                address: None,
+            },
+        ];
+        expected.sort_by(|a, b| a.validator.cmp(&b.validator));
+
+        let res = get_validators(deps.as_ref(), None, None, None).unwrap();
+        assert_eq!(res.validator_infos, expected);
+        assert_eq!(
+            res.validators,
+            expected
+                .into_iter()
+                .map(|v| v.validator)
+                .collect::<Vec<_>>()
+        );
+
+        // the multisig ism can't verify their checkpoints, so they can't post any
+        let checkpoint = Checkpoint {
+            merkle_tree_hook: gen_bz(32),
+            root: gen_bz(32),
+            index: 0,
+            message_id: gen_bz(32),
+        };
+        let err = post_checkpoint(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            signer.validator.clone(),
+            checkpoint.clone(),
+            signer.sign(checkpoint_hash(26657, &checkpoint).unwrap()),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::CosmosValidatorCheckpoint { .. }
+        ));
+
+        // revocations of cosmos validators are verified through ADR-36 as well
+        revoke(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            signer.validator.clone(),
+            "file://a".to_string(),
+            signer.sign(announcement_hash(revocation, "file://a")),
+        )
+        .unwrap();
+        assert!(locations(deps.as_ref(), &signer.validator).is_empty());
+    }
+
+    #[rstest]
+    fn test_post_checkpoint() {
+        let mut deps = mock_dependencies();
+        setup_announce(deps.as_mut());
+
+        let signer = Signer::new();
+        let hook = gen_bz(32);
+        let checkpoint = |index: u32| Checkpoint {
+            merkle_tree_hook: hook.clone(),
+            root: gen_bz(32),
+            index,
+            message_id: gen_bz(32),
+        };
+        let post = |deps: DepsMut, checkpoint: Checkpoint| {
+            let signature = signer.sign(checkpoint_hash(26657, &checkpoint).unwrap());
+
+            post_checkpoint(
+                deps,
+                mock_info("someone", &[]),
+                signer.validator.clone(),
+                checkpoint,
+                signature,
+            )
+        };
+
+        // only announced validators can post
+        let err = post(deps.as_mut(), checkpoint(1)).unwrap_err();
+        assert!(matches!(err, ContractError::ValidatorNotAnnounced { .. }));
+
+        let domain = load_domain_hash(deps.as_ref(), ANNOUNCEMENT_TAG).unwrap();
+        announce(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            signer.validator.clone(),
+            "cosmwasm://va".to_string(),
+            signer.sign(announcement_hash(domain, "cosmwasm://va")),
+        )
+        .unwrap();
+
+        let posted = [checkpoint(5), checkpoint(3)];
+        for checkpoint in posted.clone() {
+            post(deps.as_mut(), checkpoint).unwrap();
+        }
+
+        let err = post(deps.as_mut(), checkpoint(5)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::CheckpointAlreadyPosted { index: 5 }
+        ));
+
+        // signed by someone else
+        let other = Signer::new();
+        let err = post_checkpoint(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            signer.validator.clone(),
+            checkpoint(6),
+            other.sign(checkpoint_hash(26657, &checkpoint(6)).unwrap()),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::VerifyFailed {}));
+
+        let err = post(
+            deps.as_mut(),
+            Checkpoint {
+                merkle_tree_hook: gen_bz(20),
+                ..checkpoint(6)
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidMerkleTreeHook { len: 20 }
+        ));
+
+        // the same index of another merkle tree hook is kept apart
+        let other_hook = gen_bz(32);
+        let other_checkpoint = Checkpoint {
+            merkle_tree_hook: other_hook.clone(),
+            ..checkpoint(5)
+        };
+        post(deps.as_mut(), other_checkpoint.clone()).unwrap();
+
+        let res = get_checkpoint(deps.as_ref(), signer.validator.clone(), other_hook, 5).unwrap();
+        assert_eq!(res.checkpoint.unwrap().checkpoint, other_checkpoint);
+
+        let latest = get_latest_checkpoint(deps.as_ref(), signer.validator.clone(), hook.clone())
+            .unwrap()
+            .checkpoint
+            .unwrap();
+        assert_eq!(latest.checkpoint, posted[0]);
+
+        let res = get_checkpoint(deps.as_ref(), signer.validator.clone(), hook.clone(), 3).unwrap();
+        assert_eq!(res.checkpoint.unwrap().checkpoint, posted[1]);
+
+        let res = list_checkpoints(
+            deps.as_ref(),
+            signer.validator.clone(),
+            hook,
+            None,
+            None,
+            Some(Order::Desc),
+        )
+        .unwrap();
+        assert_eq!(
+            res.checkpoints
+                .into_iter()
+                .map(|v| v.checkpoint)
+                .collect::<Vec<_>>(),
+            posted.to_vec()
+        );
+    }
 }
@@ -1,4 +1,5 @@
-use cosmwasm_std::{RecoverPubkeyError, StdError};
+use cosmwasm_std::{RecoverPubkeyError, StdError, VerificationError};
+use hpl_interface::types::SignatureError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,9 +10,33 @@ pub enum ContractError {
     #[error("{0}")]
     RecoverPubkeyError(#[from] RecoverPubkeyError),
 
+    #[error("{0}")]
+    VerificationError(#[from] VerificationError),
+
+    #[error("{0}")]
+    Signature(#[from] SignatureError),
+
     #[error("unauthorized")]
     Unauthorized {},
 
     #[error("verify failed")]
     VerifyFailed {},
+
+    #[error("storage location not found: {location}")]
+    StorageLocationNotFound { location: String },
+
+    #[error("too many storage locations. max: {max}")]
+    TooManyStorageLocations { max: usize },
+
+    #[error("validator not announced: {validator}")]
+    ValidatorNotAnnounced { validator: String },
+
+    #[error("checkpoints of cosmos validators can't be verified by the multisig ism: {validator}")]
+    CosmosValidatorCheckpoint { validator: String },
+
+    #[error("checkpoint already posted. index: {index}")]
+    CheckpointAlreadyPosted { index: u32 },
+
+    #[error("invalid merkle tree hook. expected 32 bytes, got: {len}")]
+    InvalidMerkleTreeHook { len: usize },
 }
@@ -1,5 +1,13 @@
-use cosmwasm_std::Empty;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Empty, HexBinary};
 use cw_storage_plus::{Item, Map};
+use hpl_interface::core::va::SignedCheckpoint;
+
+#[cw_serde]
+pub struct CosmosValidator {
+    pub address: String,
+    pub pubkey: HexBinary,
+}
 
 pub const MAILBOX_KEY: &str = "mailbox";
 pub const MAILBOX: Item<Vec<u8>> = Item::new(MAILBOX_KEY);
@@ -10,8 +18,21 @@ pub const LOCAL_DOMAIN: Item<u32> = Item::new(LOCAL_DOMAIN_KEY);
 pub const VALIDATORS_PREFIX: &str = "validators";
 pub const VALIDATORS: Map<Vec<u8>, Empty> = Map::new(VALIDATORS_PREFIX);
 
+pub const COSMOS_VALIDATORS_PREFIX: &str = "cosmos_validators";
+pub const COSMOS_VALIDATORS: Map<Vec<u8>, CosmosValidator> = Map::new(COSMOS_VALIDATORS_PREFIX);
+
+pub const MAX_STORAGE_LOCATIONS: usize = 10;
+
 pub const STORAGE_LOCATIONS_PREFIX: &str = "storage_locations";
 pub const STORAGE_LOCATIONS: Map<Vec<u8>, Vec<String>> = Map::new(STORAGE_LOCATIONS_PREFIX);
 
 pub const REPLAY_PROTECTIONS_PREFIX: &str = "replay_protections";
 pub const REPLAY_PROTECITONS: Map<Vec<u8>, Empty> = Map::new(REPLAY_PROTECTIONS_PREFIX);
+
+pub const CHECKPOINTS_PREFIX: &str = "checkpoints";
+/// keyed by (validator, merkle tree hook, index)
+pub const CHECKPOINTS: Map<(Vec<u8>, Vec<u8>, u32), SignedCheckpoint> =
+    Map::new(CHECKPOINTS_PREFIX);
+
+pub const LATEST_CHECKPOINT_PREFIX: &str = "latest_checkpoint";
+pub const LATEST_CHECKPOINT: Map<(Vec<u8>, Vec<u8>), u32> = Map::new(LATEST_CHECKPOINT_PREFIX);
@@ -10,7 +10,9 @@ use cw_storage_plus::Item;
 use error::ContractError;
 use hpl_interface::{
     hook::{
-        aggregate::{AggregateHookQueryMsg, ExecuteMsg, HooksResponse, InstantiateMsg, QueryMsg},
+        aggregate::{
+            AggregateHookQueryMsg, ExecuteMsg, HooksResponse, InstantiateMsg, MigrateMsg, QueryMsg,
+        },
         post_dispatch, HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg,
         QuoteDispatchResponse,
     },
@@ -184,3 +186,13 @@ fn get_hooks(deps: Deps) -> Result<HooksResponse, ContractError> {
             .collect(),
     })
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
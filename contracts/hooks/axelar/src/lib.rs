@@ -1,17 +1,20 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, ensure_eq, Addr, Deps, DepsMut, Env, Event, HexBinary, MessageInfo, QueryResponse,
-    Response, StdError, StdResult, Uint128,
+    coin, ensure, ensure_eq, Addr, Deps, DepsMut, Env, Event, HexBinary, MessageInfo,
+    QueryResponse, Response, StdError, StdResult, Uint128,
 };
 use cw_storage_plus::Item;
 use ethabi::{encode, Token};
 use hpl_interface::{
-    core::mailbox::{LatestDispatchedIdResponse, MailboxQueryMsg},
+    core::mailbox::{
+        in_latest_dispatch_batch, record_posted_nonce, seed_latest_posted_nonce,
+        skip_repeated_post, LatestDispatchedIdResponse, MailboxQueryMsg, PostedNonce,
+    },
     hook::{
         axelar::{
             AxelarGeneralMessage, AxelarInfoResponse, AxelarQueryMsg, ExecuteMsg, InstantiateMsg,
-            QueryMsg, RegisterDestinationISMMsg,
+            MigrateMsg, QueryMsg, RegisterDestinationISMMsg,
         },
         HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
     },
@@ -53,6 +56,9 @@ pub const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
 pub const GAS_TOKEN_KEY: &str = "gas_token";
 pub const GAS_TOKEN: Item<String> = Item::new(GAS_TOKEN_KEY);
 
+pub const LATEST_POSTED_NONCE_KEY: &str = "latest_posted_nonce";
+pub const LATEST_POSTED_NONCE: Item<u32> = Item::new(LATEST_POSTED_NONCE_KEY);
+
 fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_hook_axelar::{}", name))
 }
@@ -79,6 +85,9 @@ pub enum ContractError {
 
     #[error("last_dispatch id mismatch")]
     LastDispatchIDMismatch { got: HexBinary, expected: HexBinary },
+
+    #[error("message already posted. nonce: {nonce}")]
+    AlreadyPosted { nonce: u32 },
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -128,9 +137,7 @@ pub fn execute(
             Err(e) => Err(e.into()),
         },
         ExecuteMsg::PostDispatch(msg) => post_dispatch(deps, env, info, msg),
-        ExecuteMsg::RegisterDestinationISM(msg) => {
-            register_destination_ism(deps, info, msg)
-        }
+        ExecuteMsg::RegisterDestinationISM(msg) => register_destination_ism(deps, info, msg),
     }
 }
 
@@ -149,8 +156,7 @@ fn register_destination_ism(
     DESTINATION_ISM.save(deps.storage, destination_ism)?;
 
     Ok(Response::new().add_event(
-        new_event("register_destination_ISM")
-            .add_attribute("destination_ISM", destination_ism),
+        new_event("register_destination_ISM").add_attribute("destination_ISM", destination_ism),
     ))
 }
 
@@ -178,15 +184,33 @@ fn post_dispatch(
 
     let decoded_msg: Message = req.message.clone().into();
 
-    ensure_eq!(
-        latest_dispatch_id,
-        decoded_msg.id(),
+    // only the last message of a batch dispatch is the latest one
+    ensure!(
+        latest_dispatch_id == decoded_msg.id()
+            || in_latest_dispatch_batch(&deps.querier, &mailbox, &decoded_msg.id()),
         ContractError::LastDispatchIDMismatch {
             got: decoded_msg.id(),
             expected: latest_dispatch_id
         }
     );
 
+    match record_posted_nonce(deps.storage, &LATEST_POSTED_NONCE, decoded_msg.nonce)? {
+        PostedNonce::New => {}
+        PostedNonce::Repeated => {
+            return Ok(Response::new()
+                .add_messages(skip_repeated_post(info))
+                .add_event(
+                    new_event("post_dispatch_skipped")
+                        .add_attribute("message_id", decoded_msg.id().to_hex()),
+                ))
+        }
+        PostedNonce::Replayed => {
+            return Err(ContractError::AlreadyPosted {
+                nonce: decoded_msg.nonce,
+            })
+        }
+    }
+
     //send message to axelar gateway
     let destination_chain = DESTINATION_CHAIN.load(deps.storage)?;
     let destination_ism = DESTINATION_ISM.load(deps.storage)?;
@@ -332,47 +356,81 @@ fn min_ntrn_ibc_fee(fee: IbcFee) -> IbcFee {
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let res = hpl_migration::migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION, &[])?;
+
+    let mailbox = MAILBOX.load(deps.storage)?;
+    seed_latest_posted_nonce(deps.storage, &deps.querier, mailbox, &LATEST_POSTED_NONCE)?;
+
+    Ok(res)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     use cosmwasm_std::{
-        from_binary,
-        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-        HexBinary, OwnedDeps, WasmQuery,
+        coin, from_json,
+        testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        to_json_binary, BankMsg, ContractResult, HexBinary, OwnedDeps, QuerierWrapper,
+        SystemResult, WasmQuery,
     };
-
     use hpl_interface::{
-        build_test_executor, build_test_querier, core::mailbox, hook::QuoteDispatchMsg,
+        core::mailbox::{self, NonceResponse},
+        hook::QuoteDispatchMsg,
     };
-    use hpl_ownable::get_owner;
     use ibcx_test_utils::hex;
+    use neutron_sdk::query::min_ibc_fee::MinIbcFeeResponse;
     use rstest::{fixture, rstest};
 
-    use crate::{execute, instantiate};
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier<NeutronQuery>, NeutronQuery>;
+
+    const TEST_MESSAGE: &str = "dc7b240deb74cca40636435ade8514b7ac35176e085f810e92dbc8bdb54a3d554ef32b9f724df19861d7e9b89a8ed11a4ecb35512f58b18b6607689cb9ba36dcf0f4af3cc1c7128c6cf0b47ea1f1aa07a4fe64502edd9a2b2e2dddf770776040efa24f19";
 
-    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+    const TEST_MESSAGE_ID: &str =
+        "a6d8af738f99da8a0a8a3611e6c777bc9ebf42b1f685a5ff6b1ff1f2b7b70f45";
 
-    build_test_executor!(self::execute);
-    build_test_querier!(self::query);
+    // instantiate and migrate don't use the neutron querier
+    fn deps_mut(deps: &mut TestDeps) -> DepsMut<'_> {
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&deps.querier),
+        }
+    }
 
     #[fixture]
-    fn deps(
-        #[default(Addr::unchecked("deployer"))] sender: Addr,
-        #[default(Addr::unchecked("owner"))] owner: Addr,
-        #[default(Addr::unchecked("mailbox"))] mailbox: Addr,
-    ) -> TestDeps {
-        let mut deps = mock_dependencies();
+    fn deps() -> TestDeps {
+        let querier = MockQuerier::<NeutronQuery>::new(&[]).with_custom_handler(|query| {
+            let res = match query {
+                NeutronQuery::MinIbcFee {} => MinIbcFeeResponse {
+                    min_fee: IbcFee {
+                        recv_fee: vec![],
+                        ack_fee: vec![coin(1000, FEE_DENOM)],
+                        timeout_fee: vec![coin(1000, FEE_DENOM)],
+                    },
+                },
+                _ => unreachable!("noo"),
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        });
+
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: Default::default(),
+        };
 
         instantiate(
-            deps.as_mut(),
+            deps_mut(&mut deps),
             mock_env(),
-            mock_info(sender.as_str(), &[]),
+            mock_info("deployer", &[]),
             InstantiateMsg {
-                owner: owner.to_string(),
-                mailbox: mailbox.to_string(),
+                owner: "owner".to_string(),
+                mailbox: "mailbox".to_string(),
                 destination_chain: "test-chain".to_string(),
-                destination_contract: "test_contract".to_string(),
                 gas_token: "untrn".to_string(),
                 axelar_gateway_channel: "channel-1".to_string(),
                 destination_ism: "4D147dCb984e6affEEC47e44293DA442580A3Ec0".to_string(),
@@ -383,29 +441,122 @@ mod test {
         deps
     }
 
-    // #[rstest]
-    // fn test_post_dispatch(){
-    //     let message = Message{
-    //         version: 1,
-    //         sender: HexBinary::from_hex("6E657574726F6E317877747A397733706664336E68373765326C76656C7A7A3064773768783372786E6D7A746573").unwrap(),
-    //         nonce: 2,
-    //         origin_domain: 10,
-    //         recipient: HexBinary::from_hex("6E657574726F6E317877747A397733706664336E68373765326C76656C7A7A3064773768783372786E6D7A746573").unwrap(),
-    //         body: HexBinary::from_hex("686920776F726C64").unwrap(),
-    //         dest_domain: 20,
-    //     };
-
-    //     let pd_message = PostDispatchMsg{
-    //         metadata: HexBinary::default(),
-    //         message: message.into(),
-    //     };
-    //     dispatch_resp = post_dispatch(deps.as_mut(), env, info, req)
-    // }
+    fn mock_mailbox(deps: &mut TestDeps, nonce: u32) {
+        deps.querier.update_wasm(move |query| {
+            let msg = match query {
+                WasmQuery::Smart { msg, .. } => from_json(msg).unwrap(),
+                _ => unreachable!("noo"),
+            };
+
+            let res = match msg {
+                mailbox::QueryMsg::Mailbox(MailboxQueryMsg::LatestDispatchId {}) => {
+                    to_json_binary(&LatestDispatchedIdResponse {
+                        message_id: hex(TEST_MESSAGE_ID),
+                    })
+                }
+                mailbox::QueryMsg::Mailbox(MailboxQueryMsg::Nonce {}) => {
+                    to_json_binary(&NonceResponse { nonce })
+                }
+                _ => unreachable!("unwrap noo"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+    }
+
+    #[rstest]
+    fn test_post_dispatch_replay(mut deps: TestDeps) {
+        mock_mailbox(&mut deps, 0);
+
+        let msg = ExecuteMsg::PostDispatch(PostDispatchMsg {
+            metadata: AxelarMetadata { gas_amount: 200 }.into(),
+            message: hex(TEST_MESSAGE),
+        });
+        let nonce = Message::from(hex(TEST_MESSAGE)).nonce;
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mailbox", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            LATEST_POSTED_NONCE.load(deps.as_ref().storage).unwrap(),
+            nonce
+        );
+
+        // the latest message posted again is skipped, not sent twice
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("someone", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            res.events,
+            vec![new_event("post_dispatch_skipped")
+                .add_attribute("message_id", TEST_MESSAGE_ID.to_string())]
+        );
+
+        // along with the fee attached to it, which goes back to the caller
+        let funds = vec![coin(300, "untrn")];
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mailbox", &funds),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages.into_iter().map(|v| v.msg).collect::<Vec<_>>(),
+            vec![BankMsg::Send {
+                to_address: "mailbox".to_string(),
+                amount: funds,
+            }
+            .into()]
+        );
+
+        // older messages are rejected
+        LATEST_POSTED_NONCE
+            .save(deps.as_mut().storage, &(nonce + 1))
+            .unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("someone", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyPosted { nonce });
+    }
+
+    #[rstest]
+    #[case(None, 5, Some(4))]
+    #[case(None, 0, None)]
+    #[case(Some(7), 5, Some(7))]
+    fn test_migrate(
+        mut deps: TestDeps,
+        #[case] latest_nonce: Option<u32>,
+        #[case] mailbox_nonce: u32,
+        #[case] expected: Option<u32>,
+    ) {
+        mock_mailbox(&mut deps, mailbox_nonce);
+
+        if let Some(nonce) = latest_nonce {
+            LATEST_POSTED_NONCE
+                .save(deps.as_mut().storage, &nonce)
+                .unwrap();
+        }
+
+        migrate(deps_mut(&mut deps), mock_env(), MigrateMsg {}).unwrap();
+
+        assert_eq!(
+            LATEST_POSTED_NONCE.may_load(deps.as_ref().storage).unwrap(),
+            expected
+        );
+    }
 
     #[rstest]
     fn test_quote_dispatch() {
         let metadata: HexBinary = AxelarMetadata { gas_amount: 200 }.into();
-        print!("{}", metadata.to_hex());
         let quote_dispatch_msg = QuoteDispatchMsg {
             metadata,
             message: HexBinary::from_hex("68656C6C6F").unwrap(),
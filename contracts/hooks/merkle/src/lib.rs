@@ -1,14 +1,16 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo, QueryResponse, Response,
-    StdError,
+    ensure, Addr, Deps, DepsMut, Env, Event, MessageInfo, QueryResponse, Response, StdError,
 };
 use cw_storage_plus::Item;
 use hpl_interface::{
-    core::mailbox::{LatestDispatchedIdResponse, MailboxQueryMsg},
+    core::mailbox::{
+        in_latest_dispatch_batch, record_posted_nonce, seed_latest_posted_nonce,
+        skip_repeated_post, LatestDispatchedIdResponse, MailboxQueryMsg, PostedNonce,
+    },
     hook::{
-        merkle::{self, ExecuteMsg, InstantiateMsg, MerkleHookQueryMsg, QueryMsg},
+        merkle::{self, ExecuteMsg, InstantiateMsg, MerkleHookQueryMsg, MigrateMsg, QueryMsg},
         HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchResponse,
     },
     to_binary,
@@ -28,6 +30,9 @@ pub enum ContractError {
 
     #[error("hook paused")]
     Paused {},
+
+    #[error("message already inserted. nonce: {nonce}")]
+    AlreadyInserted { nonce: u32 },
 }
 
 // version info for migration info
@@ -40,6 +45,9 @@ pub const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
 pub const MESSAGE_TREE_KEY: &str = "message_tree";
 pub const MESSAGE_TREE: Item<MerkleTree> = Item::new(MESSAGE_TREE_KEY);
 
+pub const LATEST_INSERTED_NONCE_KEY: &str = "latest_inserted_nonce";
+pub const LATEST_INSERTED_NONCE: Item<u32> = Item::new(LATEST_INSERTED_NONCE_KEY);
+
 fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_hook_merkle::{}", name))
 }
@@ -91,12 +99,30 @@ pub fn execute(
 
             let decoded_msg: Message = message.into();
 
-            ensure_eq!(
-                latest_dispatch_id,
-                decoded_msg.id(),
+            // only the last message of a batch dispatch is the latest one
+            ensure!(
+                latest_dispatch_id == decoded_msg.id()
+                    || in_latest_dispatch_batch(&deps.querier, &mailbox, &decoded_msg.id()),
                 ContractError::Unauthorized {}
             );
 
+            match record_posted_nonce(deps.storage, &LATEST_INSERTED_NONCE, decoded_msg.nonce)? {
+                PostedNonce::New => {}
+                PostedNonce::Repeated => {
+                    return Ok(Response::new()
+                        .add_messages(skip_repeated_post(info))
+                        .add_event(
+                            new_event("post_dispatch_skipped")
+                                .add_attribute("message_id", decoded_msg.id().to_hex()),
+                        ))
+                }
+                PostedNonce::Replayed => {
+                    return Err(ContractError::AlreadyInserted {
+                        nonce: decoded_msg.nonce,
+                    })
+                }
+            }
+
             let mut tree = MESSAGE_TREE.load(deps.storage)?;
             let index = tree.count;
             tree.insert(decoded_msg.id())?;
@@ -191,8 +217,13 @@ fn get_tree_checkpoint(deps: Deps) -> Result<merkle::CheckPointResponse, Contrac
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    Ok(Response::new())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let res = hpl_migration::migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION, &[])?;
+
+    let mailbox = MAILBOX.load(deps.storage)?;
+    seed_latest_posted_nonce(deps.storage, &deps.querier, mailbox, &LATEST_INSERTED_NONCE)?;
+
+    Ok(res)
 }
 
 #[cfg(test)]
@@ -257,16 +288,11 @@ mod test {
     const TEST_MESSAGE: &str = "dc7b240deb74cca40636435ade8514b7ac35176e085f810e92dbc8bdb54a3d554ef32b9f724df19861d7e9b89a8ed11a4ecb35512f58b18b6607689cb9ba36dcf0f4af3cc1c7128c6cf0b47ea1f1aa07a4fe64502edd9a2b2e2dddf770776040efa24f19";
     const TEST_MESSAGE_FAIL: &str = "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef";
 
-    #[rstest]
-    #[case("mailbox", None)]
-    #[should_panic(expected = "unauthorized")]
-    #[case("mailbox", Some(hex(TEST_MESSAGE_FAIL)))]
-    fn test_post_dispatch(
-        mut deps: TestDeps,
-        #[case] sender: &str,
-        #[case] message: Option<HexBinary>,
-    ) {
-        deps.querier.update_wasm(|query| {
+    const TEST_MESSAGE_ID: &str =
+        "a6d8af738f99da8a0a8a3611e6c777bc9ebf42b1f685a5ff6b1ff1f2b7b70f45";
+
+    fn mock_mailbox(deps: &mut TestDeps, latest: bool, in_batch: bool) {
+        deps.querier.update_wasm(move |query| {
             use cosmwasm_std::{to_binary, ContractResult, SystemResult};
 
             let (_contract_addr, msg) = match query {
@@ -278,21 +304,50 @@ mod test {
 
             match msg {
                 mailbox::QueryMsg::Mailbox(MailboxQueryMsg::LatestDispatchId {}) => {
+                    // a message dispatched later in the same batch is the latest one otherwise
                     let res = LatestDispatchedIdResponse {
-                        message_id: hex(
-                            "a6d8af738f99da8a0a8a3611e6c777bc9ebf42b1f685a5ff6b1ff1f2b7b70f45",
-                        ),
+                        message_id: if latest {
+                            hex(TEST_MESSAGE_ID)
+                        } else {
+                            hex(&"00".repeat(32))
+                        },
+                    };
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+                }
+                mailbox::QueryMsg::Mailbox(MailboxQueryMsg::LatestDispatchBatch {}) => {
+                    let res = mailbox::LatestDispatchBatchResponse {
+                        message_ids: if in_batch {
+                            vec![hex(TEST_MESSAGE_ID), hex(&"00".repeat(32))]
+                        } else {
+                            vec![hex(&"00".repeat(32))]
+                        },
                     };
                     SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
                 }
                 _ => unreachable!("unwrap noo"),
             }
         });
+    }
+
+    #[rstest]
+    #[case(None, true, false)]
+    #[case(None, false, true)]
+    #[should_panic(expected = "unauthorized")]
+    #[case(None, false, false)]
+    #[should_panic(expected = "unauthorized")]
+    #[case(Some(hex(TEST_MESSAGE_FAIL)), true, true)]
+    fn test_post_dispatch(
+        mut deps: TestDeps,
+        #[case] message: Option<HexBinary>,
+        #[case] latest: bool,
+        #[case] in_batch: bool,
+    ) {
+        mock_mailbox(&mut deps, latest, in_batch);
 
         let res = execute(
             deps.as_mut(),
             mock_env(),
-            mock_info(sender, &[]),
+            mock_info("mailbox", &[]),
             ExecuteMsg::PostDispatch(PostDispatchMsg {
                 metadata: HexBinary::default(),
                 message: message.unwrap_or(hex(TEST_MESSAGE)),
@@ -318,6 +373,84 @@ mod test {
         assert_eq!(tree.count, 1);
     }
 
+    #[rstest]
+    fn test_post_dispatch_replay(mut deps: TestDeps) {
+        mock_mailbox(&mut deps, true, true);
+
+        let msg = ExecuteMsg::PostDispatch(PostDispatchMsg {
+            metadata: HexBinary::default(),
+            message: hex(TEST_MESSAGE),
+        });
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mailbox", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+
+        // the latest message posted again is skipped, not inserted twice
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("someone", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.events,
+            vec![new_event("post_dispatch_skipped")
+                .add_attribute("message_id", TEST_MESSAGE_ID.to_string())]
+        );
+        assert_eq!(MESSAGE_TREE.load(deps.as_ref().storage).unwrap().count, 1);
+
+        // older messages are rejected
+        let nonce = Message::from(hex(TEST_MESSAGE)).nonce;
+        LATEST_INSERTED_NONCE
+            .save(deps.as_mut().storage, &(nonce + 1))
+            .unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("someone", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyInserted { nonce });
+        assert_eq!(MESSAGE_TREE.load(deps.as_ref().storage).unwrap().count, 1);
+    }
+
+    #[rstest]
+    #[case(None, 5, Some(4))]
+    #[case(None, 0, None)]
+    #[case(Some(7), 5, Some(7))]
+    fn test_migrate(
+        mut deps: TestDeps,
+        #[case] latest_nonce: Option<u32>,
+        #[case] mailbox_nonce: u32,
+        #[case] expected: Option<u32>,
+    ) {
+        use cosmwasm_std::{to_json_binary, ContractResult, SystemResult};
+
+        deps.querier.update_wasm(move |_| {
+            let res = mailbox::NonceResponse {
+                nonce: mailbox_nonce,
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        });
+
+        if let Some(nonce) = latest_nonce {
+            LATEST_INSERTED_NONCE
+                .save(deps.as_mut().storage, &nonce)
+                .unwrap();
+        }
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        assert_eq!(
+            LATEST_INSERTED_NONCE
+                .may_load(deps.as_ref().storage)
+                .unwrap(),
+            expected
+        );
+    }
+
     #[rstest]
     fn test_queries(deps: TestDeps) {
         let res: MailboxResponse =
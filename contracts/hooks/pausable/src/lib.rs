@@ -5,7 +5,7 @@ use cosmwasm_std::{
 };
 use hpl_interface::{
     hook::{
-        pausable::{ExecuteMsg, InstantiateMsg, QueryMsg},
+        pausable::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
         HookQueryMsg, MailboxResponse, QuoteDispatchResponse,
     },
     to_binary,
@@ -99,6 +99,16 @@ fn quote_dispatch() -> Result<QuoteDispatchResponse, ContractError> {
     Ok(QuoteDispatchResponse { gas_amount: None })
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
@@ -11,7 +11,7 @@ use hpl_interface::{
         self,
         routing_custom::{
             ClearCustomHookMsg, CustomHookResponse, CustomHooksResponse, CustomRoutingHookQueryMsg,
-            ExecuteMsg, InstantiateMsg, QueryMsg, RegisterCustomHookMsg,
+            ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RegisterCustomHookMsg,
         },
         HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
     },
@@ -292,6 +292,16 @@ fn quote_dispatch(
     Ok(resp)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
@@ -9,7 +9,7 @@ use cw_storage_plus::Item;
 use hpl_interface::{
     hook::{
         self,
-        routing_fallback::{ExecuteMsg, InstantiateMsg, QueryMsg},
+        routing_fallback::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
         HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
     },
     to_binary,
@@ -154,6 +154,16 @@ pub fn quote_dispatch(
     Ok(resp)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
@@ -8,7 +8,7 @@ use cosmwasm_std::{
 use hpl_interface::{
     hook::{
         self,
-        routing::{ExecuteMsg, InstantiateMsg, QueryMsg},
+        routing::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
         HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
     },
     to_binary,
@@ -135,6 +135,16 @@ fn post_dispatch(
     ))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
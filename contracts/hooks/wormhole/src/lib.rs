@@ -3,14 +3,20 @@ use std::{env, ops::Deref};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, wasm_execute, Addr, Binary, Deps, DepsMut, Env, Event, MessageInfo, QueryResponse,
+    ensure, wasm_execute, Addr, Binary, Deps, DepsMut, Env, Event, MessageInfo, QueryResponse,
     Response, StdError, StdResult,
 };
 use cw_storage_plus::Item;
 use hpl_interface::{
-    core::mailbox::{LatestDispatchedIdResponse, MailboxQueryMsg},
+    core::mailbox::{
+        in_latest_dispatch_batch, record_posted_nonce, seed_latest_posted_nonce,
+        skip_repeated_post, LatestDispatchedIdResponse, MailboxQueryMsg, PostedNonce,
+    },
     hook::{
-        wormhole::{ExecuteMsg, InstantiateMsg, QueryMsg, WormholeInfoResponse, WormholeQueryMsg},
+        wormhole::{
+            ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, WormholeInfoResponse,
+            WormholeQueryMsg,
+        },
         HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchResponse,
     },
     to_binary,
@@ -29,6 +35,9 @@ const WORMHOLE_CORE: Item<Addr> = Item::new(WORMHOLE_CORE_KEY);
 pub const MAILBOX_KEY: &str = "mailbox";
 pub const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
 
+pub const LATEST_POSTED_NONCE_KEY: &str = "latest_posted_nonce";
+pub const LATEST_POSTED_NONCE: Item<u32> = Item::new(LATEST_POSTED_NONCE_KEY);
+
 fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_hook_wormhole::{}", name))
 }
@@ -46,6 +55,9 @@ pub enum ContractError {
 
     #[error("hook paused")]
     Paused {},
+
+    #[error("message already posted. nonce: {nonce}")]
+    AlreadyPosted { nonce: u32 },
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -90,7 +102,7 @@ pub fn execute(
 
 fn post_dispatch(
     deps: DepsMut,
-    _info: MessageInfo,
+    info: MessageInfo,
     req: PostDispatchMsg,
 ) -> Result<Response, ContractError> {
     // Ensure message_id matches latest dispatch from mailbox
@@ -106,12 +118,30 @@ fn post_dispatch(
     let decoded_msg: Message = req.message.clone().into();
     let message_id = decoded_msg.id();
 
-    ensure_eq!(
-        latest_dispatch_id,
-        message_id,
+    // only the last message of a batch dispatch is the latest one
+    ensure!(
+        latest_dispatch_id == message_id
+            || in_latest_dispatch_batch(&deps.querier, &mailbox, &message_id),
         ContractError::Unauthorized {}
     );
 
+    match record_posted_nonce(deps.storage, &LATEST_POSTED_NONCE, decoded_msg.nonce)? {
+        PostedNonce::New => {}
+        PostedNonce::Repeated => {
+            return Ok(Response::new()
+                .add_messages(skip_repeated_post(info))
+                .add_event(
+                    new_event("post_dispatch_skipped")
+                        .add_attribute("message_id", decoded_msg.id().to_hex()),
+                ))
+        }
+        PostedNonce::Replayed => {
+            return Err(ContractError::AlreadyPosted {
+                nonce: decoded_msg.nonce,
+            })
+        }
+    }
+
     // send ID to wormhole core-bridging-contract
     let wormhole_core = WORMHOLE_CORE.load(deps.storage)?;
     let wormhole_message: WormholeExecuteMsg = WormholeExecuteMsg::PostMessage {
@@ -154,3 +184,174 @@ fn quote_dispatch() -> Result<QuoteDispatchResponse, ContractError> {
     // We do not take fees for wormhole hook usage, and maintain IBC relayer ourselves
     Ok(QuoteDispatchResponse { gas_amount: None })
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let res = hpl_migration::migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION, &[])?;
+
+    let mailbox = MAILBOX.load(deps.storage)?;
+    seed_latest_posted_nonce(deps.storage, &deps.querier, mailbox, &LATEST_POSTED_NONCE)?;
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use cosmwasm_std::{
+        coin, from_json,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        to_json_binary, BankMsg, ContractResult, HexBinary, OwnedDeps, SystemResult, WasmQuery,
+    };
+    use hpl_interface::core::mailbox::{self, NonceResponse};
+    use ibcx_test_utils::hex;
+    use rstest::{fixture, rstest};
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    const TEST_MESSAGE: &str = "dc7b240deb74cca40636435ade8514b7ac35176e085f810e92dbc8bdb54a3d554ef32b9f724df19861d7e9b89a8ed11a4ecb35512f58b18b6607689cb9ba36dcf0f4af3cc1c7128c6cf0b47ea1f1aa07a4fe64502edd9a2b2e2dddf770776040efa24f19";
+
+    const TEST_MESSAGE_ID: &str =
+        "a6d8af738f99da8a0a8a3611e6c777bc9ebf42b1f685a5ff6b1ff1f2b7b70f45";
+
+    #[fixture]
+    fn deps() -> TestDeps {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                wormhole_core: "wormhole_core".to_string(),
+                mailbox: "mailbox".to_string(),
+            },
+        )
+        .unwrap();
+
+        deps
+    }
+
+    fn mock_mailbox(deps: &mut TestDeps, nonce: u32) {
+        deps.querier.update_wasm(move |query| {
+            let msg = match query {
+                WasmQuery::Smart { msg, .. } => from_json(msg).unwrap(),
+                _ => unreachable!("noo"),
+            };
+
+            let res = match msg {
+                mailbox::QueryMsg::Mailbox(MailboxQueryMsg::LatestDispatchId {}) => {
+                    to_json_binary(&LatestDispatchedIdResponse {
+                        message_id: hex(TEST_MESSAGE_ID),
+                    })
+                }
+                mailbox::QueryMsg::Mailbox(MailboxQueryMsg::Nonce {}) => {
+                    to_json_binary(&NonceResponse { nonce })
+                }
+                _ => unreachable!("unwrap noo"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+    }
+
+    #[rstest]
+    fn test_post_dispatch_replay(mut deps: TestDeps) {
+        mock_mailbox(&mut deps, 0);
+
+        let msg = ExecuteMsg::PostDispatch(PostDispatchMsg {
+            metadata: HexBinary::default(),
+            message: hex(TEST_MESSAGE),
+        });
+        let nonce = Message::from(hex(TEST_MESSAGE)).nonce;
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mailbox", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            wasm_execute(
+                "wormhole_core",
+                &WormholeExecuteMsg::PostMessage {
+                    nonce,
+                    message: hex(TEST_MESSAGE_ID).into(),
+                },
+                vec![],
+            )
+            .unwrap()
+            .into()
+        );
+
+        // the latest message posted again is skipped, not sent twice
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("someone", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            res.events,
+            vec![new_event("post_dispatch_skipped")
+                .add_attribute("message_id", TEST_MESSAGE_ID.to_string())]
+        );
+
+        // along with the fee attached to it, which goes back to the caller
+        let funds = vec![coin(300, "untrn")];
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mailbox", &funds),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages.into_iter().map(|v| v.msg).collect::<Vec<_>>(),
+            vec![BankMsg::Send {
+                to_address: "mailbox".to_string(),
+                amount: funds,
+            }
+            .into()]
+        );
+
+        // older messages are rejected
+        LATEST_POSTED_NONCE
+            .save(deps.as_mut().storage, &(nonce + 1))
+            .unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("someone", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyPosted { nonce });
+    }
+
+    #[rstest]
+    #[case(None, 5, Some(4))]
+    #[case(None, 0, None)]
+    #[case(Some(7), 5, Some(7))]
+    fn test_migrate(
+        mut deps: TestDeps,
+        #[case] latest_nonce: Option<u32>,
+        #[case] mailbox_nonce: u32,
+        #[case] expected: Option<u32>,
+    ) {
+        mock_mailbox(&mut deps, mailbox_nonce);
+
+        if let Some(nonce) = latest_nonce {
+            LATEST_POSTED_NONCE
+                .save(deps.as_mut().storage, &nonce)
+                .unwrap();
+        }
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        assert_eq!(
+            LATEST_POSTED_NONCE.may_load(deps.as_ref().storage).unwrap(),
+            expected
+        );
+    }
+}
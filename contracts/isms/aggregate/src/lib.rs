@@ -4,14 +4,16 @@ pub use crate::error::ContractError;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, Addr, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo, QueryResponse,
-    Response, StdResult,
+    ensure_eq, Addr, Deps, DepsMut, Env, Event, HexBinary, MessageInfo, QueryResponse, Response,
+    StdResult,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Item;
 use hpl_interface::{
     ism::{
-        aggregate::{AggregateIsmQueryMsg, ExecuteMsg, InstantiateMsg, IsmsResponse, QueryMsg},
+        aggregate::{
+            AggregateIsmQueryMsg, ExecuteMsg, InstantiateMsg, IsmsResponse, MigrateMsg, QueryMsg,
+        },
         IsmQueryMsg, IsmType, ModuleTypeResponse, VerifyInfoResponse, VerifyResponse,
     },
     to_binary,
@@ -131,7 +133,10 @@ fn verify(
     let mut threshold = THRESHOLD.load(deps.storage)?;
 
     for (ism, meta) in AggregateMetadata::from_hex(metadata, isms) {
-        let verified = hpl_interface::ism::verify(&deps.querier, ism, meta, message.clone())?;
+        // a sub-ism rejecting its metadata with an error doesn't verify it,
+        // but it mustn't fail the check while the others can still meet the threshold
+        let verified =
+            hpl_interface::ism::verify(&deps.querier, ism, meta, message.clone()).unwrap_or(false);
 
         if verified {
             threshold -= 1;
@@ -159,6 +164,84 @@ fn verify_info(deps: Deps, _message: HexBinary) -> Result<VerifyInfoResponse, Co
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{
+        from_json,
+        testing::{mock_dependencies, mock_env, mock_info},
+        to_json_binary, Addr, ContractResult, SystemResult, WasmQuery,
+    };
+    use hpl_interface::ism::ExpectedIsmQueryMsg;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_failing_ism() {
+        // the first ism errors, the others verify or reject as given
+        for (results, verified) in [
+            ([true, true], true),
+            ([true, false], false),
+            ([false, false], false),
+        ] {
+            let mut deps = mock_dependencies();
+
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("deployer", &[]),
+                InstantiateMsg {
+                    owner: "owner".to_string(),
+                    isms: vec!["ism1".to_string(), "ism2".to_string(), "ism3".to_string()],
+                    threshold: 2,
+                },
+            )
+            .unwrap();
+
+            deps.querier.update_wasm(move |query| {
+                let (contract_addr, msg) = match query {
+                    WasmQuery::Smart { contract_addr, msg } => (contract_addr, msg),
+                    _ => unreachable!("wrong query type"),
+                };
+                let req: ExpectedIsmQueryMsg = from_json(msg).unwrap();
+                assert!(matches!(
+                    req,
+                    ExpectedIsmQueryMsg::Ism(IsmQueryMsg::Verify { .. })
+                ));
+
+                let verified = match contract_addr.as_str() {
+                    "ism1" => {
+                        return SystemResult::Ok(ContractResult::Err(
+                            "duplicate signer".to_string(),
+                        ))
+                    }
+                    "ism2" => results[0],
+                    "ism3" => results[1],
+                    _ => unreachable!("unknown ism"),
+                };
+
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&VerifyResponse { verified }).unwrap(),
+                ))
+            });
+
+            let metadata = AggregateMetadata::new(
+                ["ism1", "ism2", "ism3"]
+                    .into_iter()
+                    .map(|v| (Addr::unchecked(v), HexBinary::from(v.as_bytes())))
+                    .collect(),
+            );
+
+            let res = verify(deps.as_ref(), metadata.into(), HexBinary::default()).unwrap();
+            assert_eq!(res.verified, verified);
+        }
+    }
 }
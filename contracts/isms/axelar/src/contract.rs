@@ -2,10 +2,10 @@ use crate::helpers::new_event;
 use crate::state::{Config, CONFIG, VERIFIED_IDS};
 use crate::{ContractError, CONTRACT_NAME, CONTRACT_VERSION};
 use cosmwasm_std::{
-    ensure_eq, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo, QueryResponse, Response,
+    ensure_eq, Deps, DepsMut, Env, HexBinary, MessageInfo, QueryResponse, Response,
 };
 use cw2::set_contract_version;
-use hpl_interface::ism::axelar::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use hpl_interface::ism::axelar::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use hpl_interface::ism::IsmQueryMsg::{ModuleType, Verify, VerifyInfo};
 use hpl_interface::ism::{IsmType, ModuleTypeResponse, VerifyInfoResponse, VerifyResponse};
 use hpl_interface::to_binary;
@@ -160,8 +160,13 @@ fn verify_info(deps: Deps, _message: HexBinary) -> Result<VerifyInfoResponse, Co
 }
 
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
 }
 
 #[cfg(test)]
@@ -1,13 +1,14 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, QueryResponse, Response};
+use cosmwasm_std::{ensure, Deps, DepsMut, Env, MessageInfo, QueryResponse, Response};
 use cw2::set_contract_version;
 use hpl_interface::{
     ism::{
         multisig::{
-            EnrolledValidatorsResponse, ExecuteMsg, InstantiateMsg, MultisigIsmQueryMsg, QueryMsg,
+            EnrolledValidatorsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg,
+            MultisigIsmQueryMsg, QueryMsg, RequireOrderedSignaturesResponse,
         },
-        IsmQueryMsg,
+        IsmQueryMsg, IsmType,
     },
     to_binary,
 };
@@ -15,7 +16,7 @@ use hpl_interface::{
 use crate::{
     error::ContractError,
     execute,
-    state::{THRESHOLD, VALIDATORS},
+    state::{MODULE_TYPE, REQUIRE_ORDERED_SIGNATURES, THRESHOLD, VALIDATORS},
     CONTRACT_NAME, CONTRACT_VERSION,
 };
 
@@ -32,6 +33,16 @@ pub fn instantiate(
 
     hpl_ownable::initialize(deps.storage, &owner)?;
 
+    let module_type = msg.module_type.unwrap_or(IsmType::MessageIdMultisig);
+    ensure!(
+        matches!(
+            module_type,
+            IsmType::MessageIdMultisig | IsmType::MerkleRootMultisig
+        ),
+        ContractError::InvalidModuleType
+    );
+    MODULE_TYPE.save(deps.storage, &module_type)?;
+
     Ok(Response::new().add_attribute("method", "instantiate"))
 }
 
@@ -55,6 +66,25 @@ pub fn execute(
         } => execute::unenroll_validator(deps, info, domain, vald),
         SetThreshold { set: threshold } => execute::set_threshold(deps, info, threshold),
         SetThresholds { set: thresholds } => execute::set_thresholds(deps, info, thresholds),
+        SetValidatorsAndThreshold {
+            domain,
+            validators,
+            threshold,
+        } => execute::set_validators_and_threshold(deps, info, domain, validators, threshold),
+        EnrollWeightedValidator { set: msg } => execute::enroll_weighted_validator(deps, info, msg),
+        UpdateValidatorWeight { set: msg } => execute::update_validator_weight(deps, info, msg),
+        SetThresholdWeight {
+            domain,
+            threshold_weight,
+        } => execute::set_threshold_weight(deps, info, domain, threshold_weight),
+        ScheduleValidatorSetRotation { domain, rotation } => {
+            execute::schedule_rotation(deps, env, info, domain, rotation)
+        }
+        CancelValidatorSetRotation { domain } => execute::cancel_rotation(deps, info, domain),
+        FinalizeValidatorSetRotation { domain } => execute::finalize_rotation(deps, env, domain),
+        SetRequireOrderedSignatures { enabled } => {
+            execute::set_require_ordered_signatures(deps, info, enabled)
+        }
     }
 }
 
@@ -67,14 +97,14 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
     match msg {
         QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
         QueryMsg::Ism(msg) => match msg {
-            ModuleType {} => to_binary(query::get_module_type()),
+            ModuleType {} => to_binary(query::get_module_type(deps)),
             Verify {
                 metadata: raw_metadata,
                 message: raw_message,
-            } => to_binary(query::verify_message(deps, raw_metadata, raw_message)),
+            } => to_binary(query::verify_message(deps, env, raw_metadata, raw_message)),
             VerifyInfo {
                 message: raw_message,
-            } => to_binary(query::get_verify_info(deps, raw_message)),
+            } => to_binary(query::get_verify_info(deps, env, raw_message)),
         },
         QueryMsg::MultisigIsm(msg) => match msg {
             MultisigIsmQueryMsg::EnrolledValidators { domain } => to_binary({
@@ -86,6 +116,34 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
                     threshold,
                 })
             }),
+            MultisigIsmQueryMsg::WeightedValidators { domain } => {
+                to_binary(query::get_weighted_validators(deps, domain))
+            }
+            MultisigIsmQueryMsg::RequireOrderedSignatures {} => to_binary({
+                let enabled = REQUIRE_ORDERED_SIGNATURES
+                    .may_load(deps.storage)?
+                    .unwrap_or_default();
+
+                Ok::<_, ContractError>(RequireOrderedSignaturesResponse { enabled })
+            }),
+            MultisigIsmQueryMsg::PendingRotation { domain } => {
+                to_binary(query::get_pending_rotation(deps, env, domain))
+            }
+            MultisigIsmQueryMsg::ListValidatorSets {
+                offset,
+                limit,
+                order,
+            } => to_binary(query::list_validator_sets(deps, offset, limit, order)),
         },
     }
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
@@ -1,4 +1,5 @@
 use cosmwasm_std::{RecoverPubkeyError, StdError, VerificationError};
+use hpl_interface::types::SignatureError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,12 +13,21 @@ pub enum ContractError {
     #[error("{0}")]
     RecoverPubkeyError(#[from] RecoverPubkeyError),
 
+    #[error("{0}")]
+    Signature(#[from] SignatureError),
+
     #[error("unauthorized")]
     Unauthorized,
 
     #[error("wrong length")]
     WrongLength,
 
+    #[error("message index {message_index} is beyond signed checkpoint {checkpoint_index}")]
+    InvalidCheckpointIndex {
+        message_index: u32,
+        checkpoint_index: u32,
+    },
+
     #[error("invalid pubkey")]
     InvalidPubKey,
 
@@ -26,4 +36,52 @@ pub enum ContractError {
 
     #[error("validator not exists")]
     ValidatorNotExist,
+
+    #[error("invalid module type")]
+    InvalidModuleType,
+
+    #[error("weight must be greater than zero")]
+    InvalidWeight,
+
+    #[error("invalid threshold: {threshold}. must be between 1 and {validators}")]
+    InvalidThreshold { threshold: u8, validators: usize },
+
+    #[error("total validator weight overflows")]
+    WeightOverflow,
+
+    #[error("threshold weight {threshold_weight} exceeds total weight {total_weight}")]
+    UnreachableThresholdWeight {
+        threshold_weight: u64,
+        total_weight: u64,
+    },
+
+    #[error("rotation not found. domain: {domain}")]
+    RotationNotFound { domain: u32 },
+
+    #[error("rotation already activated. domain: {domain}")]
+    RotationInProgress { domain: u32 },
+
+    #[error("rotations don't carry weights. domain {domain} has a threshold weight")]
+    RotationWeighted { domain: u32 },
+
+    #[error("validator set is locked by a pending rotation. domain: {domain}")]
+    RotationPending { domain: u32 },
+
+    #[error("rotation overlap has not ended yet. domain: {domain}")]
+    RotationNotReady { domain: u32 },
+
+    #[error("rotation activation must be in the future")]
+    InvalidActivation,
+
+    #[error("threshold not set. domain: {domain}")]
+    ThresholdNotSet { domain: u32 },
+
+    #[error("unknown signer: {signer}")]
+    UnknownSigner { signer: String },
+
+    #[error("duplicate signer: {signer}")]
+    DuplicateSigner { signer: String },
+
+    #[error("signatures not in ascending signer order")]
+    UnorderedSignatures,
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Event};
+use cosmwasm_std::{Addr, Event, HexBinary};
 
 pub fn emit_init_transfer_ownership(next_owner: String) -> Event {
     Event::new("ism_multisig_init_transfer_ownership").add_attribute("next_owner", next_owner)
@@ -24,8 +24,58 @@ pub fn emit_unenroll_validator(domain: u32, validator: String) -> Event {
         .add_attribute("validator", validator)
 }
 
+pub fn emit_set_validators(domain: u32, validators: &[HexBinary]) -> Event {
+    Event::new("ism_multisig_set_validators")
+        .add_attribute("domain", domain.to_string())
+        .add_attribute(
+            "validators",
+            validators
+                .iter()
+                .map(|v| v.to_hex())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+}
+
+pub fn emit_remove_domain(domain: u32) -> Event {
+    Event::new("ism_multisig_remove_domain").add_attribute("domain", domain.to_string())
+}
+
 pub fn emit_set_threshold(domain: u32, threshold: u8) -> Event {
     Event::new("ism_multisig_set_threshold")
         .add_attribute("domain", domain.to_string())
         .add_attribute("threshold", threshold.to_string())
 }
+
+pub fn emit_set_validator_weight(domain: u32, validator: String, weight: u64) -> Event {
+    Event::new("ism_multisig_set_validator_weight")
+        .add_attribute("domain", domain.to_string())
+        .add_attribute("validator", validator)
+        .add_attribute("weight", weight.to_string())
+}
+
+pub fn emit_set_threshold_weight(domain: u32, threshold_weight: u64) -> Event {
+    Event::new("ism_multisig_set_threshold_weight")
+        .add_attribute("domain", domain.to_string())
+        .add_attribute("threshold_weight", threshold_weight.to_string())
+}
+
+pub fn emit_set_require_ordered_signatures(enabled: bool) -> Event {
+    Event::new("ism_multisig_set_require_ordered_signatures")
+        .add_attribute("enabled", enabled.to_string())
+}
+
+pub fn emit_schedule_rotation(domain: u32, activation: String, overlap: u64) -> Event {
+    Event::new("ism_multisig_schedule_rotation")
+        .add_attribute("domain", domain.to_string())
+        .add_attribute("activation", activation)
+        .add_attribute("overlap", overlap.to_string())
+}
+
+pub fn emit_cancel_rotation(domain: u32) -> Event {
+    Event::new("ism_multisig_cancel_rotation").add_attribute("domain", domain.to_string())
+}
+
+pub fn emit_finalize_rotation(domain: u32) -> Event {
+    Event::new("ism_multisig_finalize_rotation").add_attribute("domain", domain.to_string())
+}
@@ -1,13 +1,92 @@
-use cosmwasm_std::{ensure_eq, DepsMut, Event, HexBinary, MessageInfo, Response, StdResult};
-use hpl_interface::ism::multisig::{ThresholdSet, ValidatorSet as MsgValidatorSet};
+use cosmwasm_std::{
+    ensure, ensure_eq, DepsMut, Env, Event, HexBinary, MessageInfo, Order, Response, StdResult,
+    Storage,
+};
+use hpl_interface::ism::multisig::{
+    RotationActivation, ThresholdSet, ValidatorSet as MsgValidatorSet, ValidatorSetRotation,
+    WeightedValidatorSet,
+};
 use hpl_ownable::get_owner;
 
 use crate::{
-    event::{emit_enroll_validator, emit_set_threshold, emit_unenroll_validator},
-    state::{THRESHOLD, VALIDATORS},
+    event::{
+        emit_cancel_rotation, emit_enroll_validator, emit_finalize_rotation, emit_remove_domain,
+        emit_schedule_rotation, emit_set_require_ordered_signatures, emit_set_threshold,
+        emit_set_threshold_weight, emit_set_validator_weight, emit_set_validators,
+        emit_unenroll_validator,
+    },
+    query::{rotation_phase, RotationPhase},
+    state::{
+        DEFAULT_WEIGHT, REQUIRE_ORDERED_SIGNATURES, ROTATIONS, THRESHOLD, THRESHOLD_WEIGHT,
+        VALIDATORS, WEIGHTS,
+    },
     ContractError,
 };
 
+/// Checks that the domain's configured threshold can still be met by its validators,
+/// i.e. `0 < threshold <= validators` and the threshold weight doesn't exceed the total weight.
+///
+/// This holds after every change to a domain, so a threshold can only be set once enough
+/// validators are enrolled. `SetValidatorsAndThreshold` sets up a domain at once.
+fn ensure_threshold_reachable(storage: &dyn Storage, domain: u32) -> Result<(), ContractError> {
+    ensure_set_reachable(
+        storage,
+        domain,
+        &VALIDATORS.may_load(storage, domain)?.unwrap_or_default(),
+        THRESHOLD.may_load(storage, domain)?,
+    )
+}
+
+fn ensure_set_reachable(
+    storage: &dyn Storage,
+    domain: u32,
+    validators: &[HexBinary],
+    threshold: Option<u8>,
+) -> Result<(), ContractError> {
+    if let Some(threshold) = threshold {
+        ensure!(
+            threshold > 0 && threshold as usize <= validators.len(),
+            ContractError::InvalidThreshold {
+                threshold,
+                validators: validators.len()
+            }
+        );
+    }
+
+    if let Some(threshold_weight) = THRESHOLD_WEIGHT.may_load(storage, domain)? {
+        let total_weight = validators.iter().try_fold(0u64, |total, v| {
+            let weight = WEIGHTS
+                .may_load(storage, (domain, v.to_vec()))?
+                .unwrap_or(DEFAULT_WEIGHT);
+
+            total
+                .checked_add(weight)
+                .ok_or(ContractError::WeightOverflow)
+        })?;
+
+        ensure!(
+            threshold_weight <= total_weight,
+            ContractError::UnreachableThresholdWeight {
+                threshold_weight,
+                total_weight
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Finalizing a rotation replaces the whole set, so the set can't be changed
+/// while one is pending. It has to be finalized or cancelled first.
+fn ensure_no_rotation(storage: &dyn Storage, domain: u32) -> Result<(), ContractError> {
+    ensure!(
+        !ROTATIONS.has(storage, domain),
+        ContractError::RotationPending { domain }
+    );
+
+    Ok(())
+}
+
 pub fn set_threshold(
     deps: DepsMut,
     info: MessageInfo,
@@ -18,7 +97,9 @@ pub fn set_threshold(
         info.sender,
         ContractError::Unauthorized
     );
+    ensure_no_rotation(deps.storage, threshold.domain)?;
     THRESHOLD.save(deps.storage, threshold.domain, &threshold.threshold)?;
+    ensure_threshold_reachable(deps.storage, threshold.domain)?;
 
     Ok(Response::new().add_event(emit_set_threshold(threshold.domain, threshold.threshold)))
 }
@@ -37,10 +118,13 @@ pub fn set_thresholds(
     let events: Vec<Event> = thresholds
         .into_iter()
         .map(|v| {
+            ensure_no_rotation(deps.storage, v.domain)?;
             THRESHOLD.save(deps.storage, v.domain, &v.threshold)?;
+            ensure_threshold_reachable(deps.storage, v.domain)?;
+
             Ok(emit_set_threshold(v.domain, v.threshold))
         })
-        .collect::<StdResult<_>>()?;
+        .collect::<Result<_, ContractError>>()?;
 
     Ok(Response::new().add_events(events))
 }
@@ -56,6 +140,8 @@ pub fn enroll_validator(
         ContractError::Unauthorized {}
     );
 
+    ensure_no_rotation(deps.storage, msg.domain)?;
+
     let validator_state = VALIDATORS.may_load(deps.storage, msg.domain)?;
 
     if let Some(mut validators) = validator_state {
@@ -70,6 +156,7 @@ pub fn enroll_validator(
     } else {
         VALIDATORS.save(deps.storage, msg.domain, &vec![msg.validator.clone()])?;
     }
+    ensure_threshold_reachable(deps.storage, msg.domain)?;
 
     Ok(Response::new().add_event(emit_enroll_validator(msg.domain, msg.validator.to_hex())))
 }
@@ -88,6 +175,8 @@ pub fn enroll_validators(
     let mut events: Vec<Event> = Vec::new();
 
     for msg in validators.into_iter() {
+        ensure_no_rotation(deps.storage, msg.domain)?;
+
         let validators_state = VALIDATORS.may_load(deps.storage, msg.domain)?;
 
         if let Some(mut validators) = validators_state {
@@ -104,11 +193,293 @@ pub fn enroll_validators(
             VALIDATORS.save(deps.storage, msg.domain, &vec![msg.validator.clone()])?;
             events.push(emit_enroll_validator(msg.domain, msg.validator.to_hex()));
         }
+        ensure_threshold_reachable(deps.storage, msg.domain)?;
+    }
+
+    Ok(Response::new().add_events(events))
+}
+
+pub fn enroll_weighted_validator(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    msg: WeightedValidatorSet,
+) -> Result<Response, ContractError> {
+    ensure!(msg.weight > 0, ContractError::InvalidWeight);
+
+    // the weight is stored first so that enrolling checks the threshold weight against it.
+    // a failed enroll reverts it along with the rest of the transaction.
+    WEIGHTS.save(
+        deps.storage,
+        (msg.domain, msg.validator.to_vec()),
+        &msg.weight,
+    )?;
+
+    let res = enroll_validator(
+        deps.branch(),
+        info,
+        MsgValidatorSet {
+            domain: msg.domain,
+            validator: msg.validator.clone(),
+        },
+    )?;
+
+    Ok(res.add_event(emit_set_validator_weight(
+        msg.domain,
+        msg.validator.to_hex(),
+        msg.weight,
+    )))
+}
+
+pub fn update_validator_weight(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: WeightedValidatorSet,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        ContractError::Unauthorized {}
+    );
+    ensure!(msg.weight > 0, ContractError::InvalidWeight);
+    ensure_no_rotation(deps.storage, msg.domain)?;
+
+    let validators = VALIDATORS
+        .may_load(deps.storage, msg.domain)?
+        .unwrap_or_default();
+    ensure!(
+        validators.contains(&msg.validator),
+        ContractError::ValidatorNotExist {}
+    );
+
+    WEIGHTS.save(
+        deps.storage,
+        (msg.domain, msg.validator.to_vec()),
+        &msg.weight,
+    )?;
+    ensure_threshold_reachable(deps.storage, msg.domain)?;
+
+    Ok(Response::new().add_event(emit_set_validator_weight(
+        msg.domain,
+        msg.validator.to_hex(),
+        msg.weight,
+    )))
+}
+
+pub fn set_threshold_weight(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    threshold_weight: u64,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        ContractError::Unauthorized {}
+    );
+    ensure!(threshold_weight > 0, ContractError::InvalidWeight);
+    ensure_no_rotation(deps.storage, domain)?;
+
+    THRESHOLD_WEIGHT.save(deps.storage, domain, &threshold_weight)?;
+    ensure_threshold_reachable(deps.storage, domain)?;
+
+    Ok(Response::new().add_event(emit_set_threshold_weight(domain, threshold_weight)))
+}
+
+/// Replaces the validator set and threshold of `domain` at once.
+/// An empty set with a zero threshold removes the domain.
+pub fn set_validators_and_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    validators: Vec<HexBinary>,
+    threshold: u8,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        ContractError::Unauthorized {}
+    );
+
+    ensure_no_rotation(deps.storage, domain)?;
+
+    if validators.is_empty() && threshold == 0 {
+        remove_domain(deps.storage, domain)?;
+
+        return Ok(Response::new().add_event(emit_remove_domain(domain)));
     }
 
+    let events = replace_validator_set(deps.storage, domain, validators, threshold)?;
+
     Ok(Response::new().add_events(events))
 }
 
+/// Drops the validators, thresholds and weights of `domain`.
+fn remove_domain(storage: &mut dyn Storage, domain: u32) -> Result<(), ContractError> {
+    let weighted = WEIGHTS
+        .prefix(domain)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for validator in weighted {
+        WEIGHTS.remove(storage, (domain, validator));
+    }
+
+    VALIDATORS.remove(storage, domain);
+    THRESHOLD.remove(storage, domain);
+    THRESHOLD_WEIGHT.remove(storage, domain);
+
+    Ok(())
+}
+
+/// Sorts `validators` and rejects duplicates.
+fn sorted_validators(mut validators: Vec<HexBinary>) -> Result<Vec<HexBinary>, ContractError> {
+    validators.sort();
+    ensure!(
+        validators.windows(2).all(|v| v[0] != v[1]),
+        ContractError::ValidatorDuplicate
+    );
+
+    Ok(validators)
+}
+
+fn replace_validator_set(
+    storage: &mut dyn Storage,
+    domain: u32,
+    validators: Vec<HexBinary>,
+    threshold: u8,
+) -> Result<Vec<Event>, ContractError> {
+    let validators = sorted_validators(validators)?;
+
+    // weights of validators that left the set
+    let stale = WEIGHTS
+        .prefix(domain)
+        .keys(storage, None, None, Order::Ascending)
+        .filter(|v| match v {
+            Ok(v) => !validators.iter().any(|x| x.as_slice() == v.as_slice()),
+            Err(_) => true,
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    for validator in stale {
+        WEIGHTS.remove(storage, (domain, validator));
+    }
+
+    VALIDATORS.save(storage, domain, &validators)?;
+    THRESHOLD.save(storage, domain, &threshold)?;
+    ensure_threshold_reachable(storage, domain)?;
+
+    Ok(vec![
+        emit_set_validators(domain, &validators),
+        emit_set_threshold(domain, threshold),
+    ])
+}
+
+pub fn schedule_rotation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    domain: u32,
+    mut rotation: ValidatorSetRotation,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        ContractError::Unauthorized {}
+    );
+
+    // an activated rotation has to be finalized before the next one
+    if let Some(pending) = ROTATIONS.may_load(deps.storage, domain)? {
+        ensure!(
+            rotation_phase(&pending, &env.block) == RotationPhase::Scheduled,
+            ContractError::RotationInProgress { domain }
+        );
+    }
+    ensure!(
+        rotation_phase(&rotation, &env.block) == RotationPhase::Scheduled,
+        ContractError::InvalidActivation
+    );
+    // the rotated set would be verified by count while the enrolled one is weighted
+    ensure!(
+        !THRESHOLD_WEIGHT.has(deps.storage, domain),
+        ContractError::RotationWeighted { domain }
+    );
+
+    rotation.validators = sorted_validators(rotation.validators)?;
+    ensure_set_reachable(
+        deps.storage,
+        domain,
+        &rotation.validators,
+        Some(rotation.threshold),
+    )?;
+
+    ROTATIONS.save(deps.storage, domain, &rotation)?;
+
+    let activation = match rotation.activation {
+        RotationActivation::Height(height) => format!("height:{height}"),
+        RotationActivation::Time(time) => format!("time:{}", time.seconds()),
+    };
+
+    Ok(Response::new().add_event(emit_schedule_rotation(domain, activation, rotation.overlap)))
+}
+
+/// Drops the pending rotation of `domain`, even an activated one.
+/// Verification falls back to the enrolled set.
+pub fn cancel_rotation(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        ContractError::Unauthorized {}
+    );
+    ensure!(
+        ROTATIONS.has(deps.storage, domain),
+        ContractError::RotationNotFound { domain }
+    );
+
+    ROTATIONS.remove(deps.storage, domain);
+
+    Ok(Response::new().add_event(emit_cancel_rotation(domain)))
+}
+
+pub fn finalize_rotation(deps: DepsMut, env: Env, domain: u32) -> Result<Response, ContractError> {
+    let rotation = ROTATIONS
+        .may_load(deps.storage, domain)?
+        .ok_or(ContractError::RotationNotFound { domain })?;
+    ensure!(
+        rotation_phase(&rotation, &env.block) == RotationPhase::Active,
+        ContractError::RotationNotReady { domain }
+    );
+
+    ROTATIONS.remove(deps.storage, domain);
+    let events = replace_validator_set(
+        deps.storage,
+        domain,
+        rotation.validators,
+        rotation.threshold,
+    )?;
+
+    Ok(Response::new()
+        .add_events(events)
+        .add_event(emit_finalize_rotation(domain)))
+}
+
+pub fn set_require_ordered_signatures(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        ContractError::Unauthorized {}
+    );
+
+    REQUIRE_ORDERED_SIGNATURES.save(deps.storage, &enabled)?;
+
+    Ok(Response::new().add_event(emit_set_require_ordered_signatures(enabled)))
+}
+
 pub fn unenroll_validator(
     deps: DepsMut,
     info: MessageInfo,
@@ -121,6 +492,8 @@ pub fn unenroll_validator(
         ContractError::Unauthorized {}
     );
 
+    ensure_no_rotation(deps.storage, domain)?;
+
     let validators = VALIDATORS
         .load(deps.storage, domain)
         .map_err(|_| ContractError::ValidatorNotExist {})?;
@@ -135,6 +508,8 @@ pub fn unenroll_validator(
     validator_list.sort();
 
     VALIDATORS.save(deps.storage, domain, &validator_list)?;
+    WEIGHTS.remove(deps.storage, (domain, validator.to_vec()));
+    ensure_threshold_reachable(deps.storage, domain)?;
 
     Ok(Response::new().add_event(emit_unenroll_validator(domain, validator.to_hex())))
 }
@@ -142,17 +517,20 @@ pub fn unenroll_validator(
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
-        testing::{mock_dependencies, mock_info},
-        Addr, HexBinary, Storage,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr, Deps, HexBinary, Storage,
     };
     use hpl_interface::{
         build_test_executor, build_test_querier,
-        ism::multisig::{ExecuteMsg, ValidatorSet},
+        ism::multisig::{
+            ExecuteMsg, RotationActivation, ValidatorSet, ValidatorSetRotation,
+            WeightedValidatorSet,
+        },
     };
     use ibcx_test_utils::{addr, hex};
     use rstest::rstest;
 
-    use crate::state::VALIDATORS;
+    use crate::state::{ROTATIONS, THRESHOLD, VALIDATORS, WEIGHTS};
 
     build_test_executor!(crate::contract::execute);
     build_test_querier!(crate::contract::query);
@@ -165,6 +543,12 @@ mod test {
         hpl_ownable::initialize(storage, &owner).unwrap();
     }
 
+    fn mock_validators(storage: &mut dyn Storage, domain: u32, n: u8) {
+        let validators = (0..n).map(|i| vec![i; 20].into()).collect();
+
+        VALIDATORS.save(storage, domain, &validators).unwrap();
+    }
+
     #[test]
     fn test_set_threshold() {
         let mut deps = mock_dependencies();
@@ -176,6 +560,50 @@ mod test {
             threshold: 8u8,
         };
 
+        // the threshold can't be zero
+        let info = mock_info(owner.as_str(), &[]);
+        let err = set_threshold(
+            deps.as_mut(),
+            info,
+            ThresholdSet {
+                domain: 1,
+                threshold: 0,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidThreshold {
+                threshold: 0,
+                validators: 0
+            }
+        ));
+
+        // the threshold can't be set before validators are enrolled
+        let info = mock_info(owner.as_str(), &[]);
+        let err = set_threshold(deps.as_mut(), info, threshold.clone()).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidThreshold {
+                threshold: 8,
+                validators: 0
+            }
+        ));
+
+        // nor exceed the validator set once there is one
+        mock_validators(deps.as_mut().storage, 1, 2);
+        let info = mock_info(owner.as_str(), &[]);
+        let err = set_threshold(deps.as_mut(), info, threshold.clone()).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidThreshold {
+                threshold: 8,
+                validators: 2
+            }
+        ));
+
+        mock_validators(deps.as_mut().storage, 1, 8);
+
         // set_threshold failure test
         let info = mock_info(ADDR2_VAULE, &[]);
         let fail_result = set_threshold(deps.as_mut(), info, threshold.clone()).unwrap_err();
@@ -217,6 +645,10 @@ mod test {
             },
         ];
 
+        for threshold in thresholds.iter() {
+            mock_validators(deps.as_mut().storage, threshold.domain, threshold.threshold);
+        }
+
         // set_threshold failure test
         let info = mock_info(ADDR2_VAULE, &[]);
         let fail_result = set_thresholds(deps.as_mut(), info, thresholds.clone()).unwrap_err();
@@ -304,4 +736,500 @@ mod test {
             .unwrap()
             .is_empty());
     }
+
+    #[test]
+    fn test_weights() {
+        let mut deps = mock_dependencies();
+
+        hpl_ownable::initialize(deps.as_mut().storage, &addr("owner")).unwrap();
+
+        let weight_of = |deps: Deps, validator: &HexBinary| {
+            WEIGHTS
+                .may_load(deps.storage, (1, validator.to_vec()))
+                .unwrap()
+        };
+
+        let err = enroll_weighted_validator(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            WeightedValidatorSet {
+                domain: 1,
+                validator: hex("deadbeef"),
+                weight: 0,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidWeight));
+
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::EnrollWeightedValidator {
+                set: WeightedValidatorSet {
+                    domain: 1,
+                    validator: hex("deadbeef"),
+                    weight: 3,
+                },
+            },
+            vec![],
+        );
+        assert_eq!(
+            VALIDATORS.load(deps.as_ref().storage, 1).unwrap(),
+            vec![hex("deadbeef")]
+        );
+        assert_eq!(weight_of(deps.as_ref(), &hex("deadbeef")), Some(3));
+
+        // only enrolled validators can be reweighted
+        let err = update_validator_weight(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            WeightedValidatorSet {
+                domain: 1,
+                validator: hex("debeefed"),
+                weight: 2,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidatorNotExist));
+
+        let err = update_validator_weight(
+            deps.as_mut(),
+            mock_info("someone", &[]),
+            WeightedValidatorSet {
+                domain: 1,
+                validator: hex("deadbeef"),
+                weight: 2,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::UpdateValidatorWeight {
+                set: WeightedValidatorSet {
+                    domain: 1,
+                    validator: hex("deadbeef"),
+                    weight: 2,
+                },
+            },
+            vec![],
+        );
+        assert_eq!(weight_of(deps.as_ref(), &hex("deadbeef")), Some(2));
+
+        // threshold weight can't exceed the total weight of the set
+        let err = set_threshold_weight(deps.as_mut(), mock_info("owner", &[]), 1, 5).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::UnreachableThresholdWeight {
+                threshold_weight: 5,
+                total_weight: 2
+            }
+        ));
+
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::EnrollWeightedValidator {
+                set: WeightedValidatorSet {
+                    domain: 1,
+                    validator: hex("debeefed"),
+                    weight: 5,
+                },
+            },
+            vec![],
+        );
+
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::SetThresholdWeight {
+                domain: 1,
+                threshold_weight: 5,
+            },
+            vec![],
+        );
+        assert_eq!(THRESHOLD_WEIGHT.load(deps.as_ref().storage, 1).unwrap(), 5);
+
+        // unenrolling drops the weight as well
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::UnenrollValidator {
+                domain: 1,
+                validator: hex("deadbeef"),
+            },
+            vec![],
+        );
+        assert_eq!(weight_of(deps.as_ref(), &hex("deadbeef")), None);
+    }
+
+    #[test]
+    fn test_set_require_ordered_signatures() {
+        let mut deps = mock_dependencies();
+
+        hpl_ownable::initialize(deps.as_mut().storage, &addr("owner")).unwrap();
+
+        let err = set_require_ordered_signatures(deps.as_mut(), mock_info("someone", &[]), true)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+
+        let res =
+            set_require_ordered_signatures(deps.as_mut(), mock_info("owner", &[]), true).unwrap();
+        assert_eq!(res.events, vec![emit_set_require_ordered_signatures(true)]);
+        assert!(REQUIRE_ORDERED_SIGNATURES
+            .load(deps.as_ref().storage)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_threshold_consistency() {
+        let mut deps = mock_dependencies();
+
+        hpl_ownable::initialize(deps.as_mut().storage, &addr("owner")).unwrap();
+
+        let validators = vec![hex("deadbeef"), hex("debeefed")];
+
+        for threshold in [0, 3] {
+            let err = set_validators_and_threshold(
+                deps.as_mut(),
+                mock_info("owner", &[]),
+                1,
+                validators.clone(),
+                threshold,
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::InvalidThreshold { .. }));
+        }
+
+        let err = set_validators_and_threshold(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            1,
+            vec![hex("deadbeef"), hex("deadbeef")],
+            1,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidatorDuplicate));
+
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::SetValidatorsAndThreshold {
+                domain: 1,
+                validators: vec![hex("debeefed"), hex("deadbeef")],
+                threshold: 2,
+            },
+            vec![],
+        );
+        assert_eq!(
+            VALIDATORS.load(deps.as_ref().storage, 1).unwrap(),
+            validators
+        );
+        assert_eq!(THRESHOLD.load(deps.as_ref().storage, 1).unwrap(), 2);
+
+        // unenrolling would leave the threshold unreachable
+        let err = unenroll_validator(deps.as_mut(), mock_info("owner", &[]), 1, hex("deadbeef"))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidThreshold {
+                threshold: 2,
+                validators: 1
+            }
+        ));
+
+        // nor can the last validator be removed while a threshold is set
+        set_validators_and_threshold(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            4,
+            vec![hex("deadbeef")],
+            1,
+        )
+        .unwrap();
+        let err = unenroll_validator(deps.as_mut(), mock_info("owner", &[]), 4, hex("deadbeef"))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidThreshold {
+                threshold: 1,
+                validators: 0
+            }
+        ));
+
+        // enrolling leaves a threshold stored before the set in place, so it's checked too
+        THRESHOLD.save(deps.as_mut().storage, 5, &8).unwrap();
+        let err = enroll_validator(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            ValidatorSet {
+                domain: 5,
+                validator: hex("deadbeef"),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidThreshold {
+                threshold: 8,
+                validators: 1
+            }
+        ));
+
+        // the threshold weight has to be reachable as well
+        set_validators_and_threshold(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            2,
+            validators.clone(),
+            1,
+        )
+        .unwrap();
+        WEIGHTS
+            .save(deps.as_mut().storage, (2, hex("deadbeef").to_vec()), &3)
+            .unwrap();
+        let err = set_threshold_weight(deps.as_mut(), mock_info("owner", &[]), 2, 5).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::UnreachableThresholdWeight {
+                threshold_weight: 5,
+                total_weight: 4
+            }
+        ));
+
+        // replacing the set drops the weights of validators that left
+        set_validators_and_threshold(deps.as_mut(), mock_info("owner", &[]), 3, validators, 1)
+            .unwrap();
+        WEIGHTS
+            .save(deps.as_mut().storage, (3, hex("deadbeef").to_vec()), &3)
+            .unwrap();
+        set_validators_and_threshold(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            3,
+            vec![hex("debeefed")],
+            1,
+        )
+        .unwrap();
+        assert!(!WEIGHTS.has(deps.as_ref().storage, (3, hex("deadbeef").to_vec())));
+
+        // an empty set with a zero threshold removes the domain altogether
+        WEIGHTS
+            .save(deps.as_mut().storage, (3, hex("debeefed").to_vec()), &2)
+            .unwrap();
+        THRESHOLD_WEIGHT.save(deps.as_mut().storage, 3, &1).unwrap();
+        let res =
+            set_validators_and_threshold(deps.as_mut(), mock_info("owner", &[]), 3, vec![], 0)
+                .unwrap();
+        assert_eq!(res.events, vec![emit_remove_domain(3)]);
+        assert!(!VALIDATORS.has(deps.as_ref().storage, 3));
+        assert!(!THRESHOLD.has(deps.as_ref().storage, 3));
+        assert!(!THRESHOLD_WEIGHT.has(deps.as_ref().storage, 3));
+        assert!(!WEIGHTS.has(deps.as_ref().storage, (3, hex("debeefed").to_vec())));
+
+        // an empty set still needs the threshold removed along with it
+        let err =
+            set_validators_and_threshold(deps.as_mut(), mock_info("owner", &[]), 2, vec![], 1)
+                .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidThreshold {
+                threshold: 1,
+                validators: 0
+            }
+        ));
+
+        // a removed domain can be set up again
+        set_validators_and_threshold(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            3,
+            vec![hex("deadbeef")],
+            1,
+        )
+        .unwrap();
+        assert_eq!(THRESHOLD.load(deps.as_ref().storage, 3).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_weight_overflow() {
+        let mut deps = mock_dependencies();
+
+        let validators = vec![hex("deadbeef"), hex("debeefed")];
+        for validator in validators.iter() {
+            WEIGHTS
+                .save(deps.as_mut().storage, (1, validator.to_vec()), &u64::MAX)
+                .unwrap();
+        }
+        THRESHOLD_WEIGHT.save(deps.as_mut().storage, 1, &1).unwrap();
+
+        let err = ensure_set_reachable(deps.as_ref().storage, 1, &validators, None).unwrap_err();
+        assert!(matches!(err, ContractError::WeightOverflow));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        hpl_ownable::initialize(deps.as_mut().storage, &addr("owner")).unwrap();
+        mock_validators(deps.as_mut().storage, 1, 2);
+        THRESHOLD.save(deps.as_mut().storage, 1, &2).unwrap();
+
+        let rotation = |activation: u64, threshold: u8| ValidatorSetRotation {
+            validators: vec![hex("debeefed"), hex("deadbeef")],
+            threshold,
+            activation: RotationActivation::Height(activation),
+            overlap: 10,
+        };
+        let height = env.block.height;
+
+        let err = schedule_rotation(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            1,
+            rotation(height + 5, 2),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+
+        let err = schedule_rotation(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            1,
+            rotation(height, 2),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidActivation));
+
+        let err = schedule_rotation(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            1,
+            rotation(height + 5, 3),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidThreshold { .. }));
+
+        // weighted domains can't be rotated
+        THRESHOLD_WEIGHT.save(deps.as_mut().storage, 2, &1).unwrap();
+        let err = schedule_rotation(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            2,
+            rotation(height + 5, 1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RotationWeighted { domain: 2 }));
+
+        // a scheduled rotation can be cancelled
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::ScheduleValidatorSetRotation {
+                domain: 1,
+                rotation: rotation(height + 5, 2),
+            },
+            vec![],
+        );
+        cancel_rotation(deps.as_mut(), mock_info("owner", &[]), 1).unwrap();
+        assert!(!ROTATIONS.has(deps.as_ref().storage, 1));
+
+        let err = cancel_rotation(deps.as_mut(), mock_info("owner", &[]), 1).unwrap_err();
+        assert!(matches!(err, ContractError::RotationNotFound { domain: 1 }));
+
+        schedule_rotation(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            1,
+            rotation(height + 5, 1),
+        )
+        .unwrap();
+        assert_eq!(
+            ROTATIONS.load(deps.as_ref().storage, 1).unwrap().validators,
+            vec![hex("deadbeef"), hex("debeefed")]
+        );
+
+        // the enrolled set is frozen while a rotation is pending
+        let err = set_validators_and_threshold(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            1,
+            vec![hex("deadbeef")],
+            1,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RotationPending { domain: 1 }));
+
+        let err = unenroll_validator(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            1,
+            vec![0; 20].into(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RotationPending { domain: 1 }));
+
+        let err = set_threshold_weight(deps.as_mut(), mock_info("owner", &[]), 1, 1).unwrap_err();
+        assert!(matches!(err, ContractError::RotationPending { domain: 1 }));
+
+        // once activated, it can't be replaced, and finalizes after the overlap
+        env.block.height = height + 5;
+        let err = schedule_rotation(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            1,
+            rotation(height + 50, 1),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::RotationInProgress { domain: 1 }
+        ));
+
+        let err = finalize_rotation(deps.as_mut(), env.clone(), 1).unwrap_err();
+        assert!(matches!(err, ContractError::RotationNotReady { domain: 1 }));
+
+        env.block.height = height + 15;
+        finalize_rotation(deps.as_mut(), env.clone(), 1).unwrap();
+        assert!(!ROTATIONS.has(deps.as_ref().storage, 1));
+        assert_eq!(
+            VALIDATORS.load(deps.as_ref().storage, 1).unwrap(),
+            vec![hex("deadbeef"), hex("debeefed")]
+        );
+        assert_eq!(THRESHOLD.load(deps.as_ref().storage, 1).unwrap(), 1);
+
+        // the owner can still back out of an activated rotation
+        schedule_rotation(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            1,
+            rotation(height + 20, 2),
+        )
+        .unwrap();
+
+        env.block.height = height + 20;
+        cancel_rotation(deps.as_mut(), mock_info("owner", &[]), 1).unwrap();
+        assert!(!ROTATIONS.has(deps.as_ref().storage, 1));
+        assert_eq!(THRESHOLD.load(deps.as_ref().storage, 1).unwrap(), 1);
+
+        set_threshold(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            ThresholdSet {
+                domain: 1,
+                threshold: 2,
+            },
+        )
+        .unwrap();
+    }
 }
@@ -1,23 +1,77 @@
-use cosmwasm_std::{Deps, HexBinary};
+use cosmwasm_std::{ensure, BlockInfo, Deps, Env, HexBinary, StdResult};
 use hpl_interface::{
-    ism::{IsmType, ModuleTypeResponse, VerifyInfoResponse, VerifyResponse},
-    types::{eth_addr, eth_hash, Message, MessageIdMultisigIsmMetadata},
+    ism::{
+        multisig::{
+            DomainValidatorSet, ListValidatorSetsResponse, PendingRotationResponse,
+            RotationActivation, ValidatorSetRotation, WeightedValidator,
+            WeightedValidatorsResponse,
+        },
+        IsmType, ModuleTypeResponse, VerifyInfoResponse, VerifyResponse,
+    },
+    range_option,
+    types::{
+        eth_hash, recover_eth_addr, MerkleRootMultisigIsmMetadata, MerkleTree, Message,
+        MessageIdMultisigIsmMetadata,
+    },
+    Order,
 };
 
 use crate::{
     domain_hash, multisig_hash,
-    state::{THRESHOLD, VALIDATORS},
+    state::{
+        DEFAULT_WEIGHT, MODULE_TYPE, REQUIRE_ORDERED_SIGNATURES, ROTATIONS, THRESHOLD,
+        THRESHOLD_WEIGHT, VALIDATORS, WEIGHTS,
+    },
     ContractError,
 };
 
-pub fn get_module_type() -> Result<ModuleTypeResponse, ContractError> {
+// origin merkle tree + index + message id + 32 * 32 bytes of proof
+const MERKLE_ROOT_METADATA_MIN_LENGTH: usize = 1096;
+// origin merkle tree + merkle root + merkle index
+const MESSAGE_ID_METADATA_MIN_LENGTH: usize = 68;
+const SIGNATURE_LENGTH: usize = 65;
+
+/// Contracts instantiated before module types were configurable are message id multisigs.
+fn load_module_type(deps: Deps) -> Result<IsmType, ContractError> {
+    Ok(MODULE_TYPE
+        .may_load(deps.storage)?
+        .unwrap_or(IsmType::MessageIdMultisig))
+}
+
+pub fn get_module_type(deps: Deps) -> Result<ModuleTypeResponse, ContractError> {
     Ok(ModuleTypeResponse {
-        typ: IsmType::MessageIdMultisig,
+        typ: load_module_type(deps)?,
     })
 }
 
+#[derive(Debug, PartialEq)]
+pub enum RotationPhase {
+    /// Only the enrolled set is accepted
+    Scheduled,
+    /// Both the enrolled and the rotated set are accepted
+    Overlap,
+    /// Only the rotated set is accepted
+    Active,
+}
+
+pub fn rotation_phase(rotation: &ValidatorSetRotation, block: &BlockInfo) -> RotationPhase {
+    let (now, activation) = match rotation.activation {
+        RotationActivation::Height(height) => (block.height, height),
+        RotationActivation::Time(time) => (block.time.seconds(), time.seconds()),
+    };
+
+    if now < activation {
+        RotationPhase::Scheduled
+    } else if now < activation.saturating_add(rotation.overlap) {
+        RotationPhase::Overlap
+    } else {
+        RotationPhase::Active
+    }
+}
+
 pub fn verify_message(
     deps: Deps,
+    env: Env,
     raw_metadata: HexBinary,
     raw_message: HexBinary,
 ) -> Result<VerifyResponse, ContractError> {
@@ -26,51 +80,273 @@ pub fn verify_message(
         raw_metadata, raw_message
     ));
 
-    let metadata: MessageIdMultisigIsmMetadata = raw_metadata.into();
     let message: Message = raw_message.into();
 
-    let merkle_index = metadata.merkle_index();
+    let (multisig_hash, signatures) = match load_module_type(deps)? {
+        IsmType::MerkleRootMultisig => {
+            ensure!(
+                raw_metadata.len() >= MERKLE_ROOT_METADATA_MIN_LENGTH,
+                ContractError::WrongLength
+            );
+            let metadata: MerkleRootMultisigIsmMetadata = raw_metadata.into();
+            let signatures_len = metadata
+                .signatures_len()
+                .map_err(|_| ContractError::WrongLength)?;
+
+            // validators sign a checkpoint at or after the message being proven
+            ensure!(
+                metadata.message_index <= metadata.signed_checkpoint_index,
+                ContractError::InvalidCheckpointIndex {
+                    message_index: metadata.message_index,
+                    checkpoint_index: metadata.signed_checkpoint_index,
+                }
+            );
+
+            // the message has to be included in the tree the validators signed
+            let proof = metadata
+                .proof
+                .chunks_exact(32)
+                .map(HexBinary::from)
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| ContractError::WrongLength)?;
+            let merkle_root =
+                MerkleTree::branch_root(message.id(), proof, metadata.message_index as u128);
+
+            let multisig_hash = multisig_hash(
+                domain_hash(message.origin_domain, metadata.origin_mailbox.clone())?.to_vec(),
+                merkle_root.to_vec(),
+                metadata.signed_checkpoint_index,
+                metadata.message_id.to_vec(),
+            )?;
 
-    let multisig_hash = multisig_hash(
-        domain_hash(message.origin_domain, metadata.origin_merkle_tree)?.to_vec(),
-        metadata.merkle_root.to_vec(),
-        merkle_index,
-        message.id().to_vec(),
-    )?;
+            let signatures = (0..signatures_len)
+                .map(|i| metadata.signature_at(i))
+                .collect::<Vec<_>>();
+
+            (multisig_hash, signatures)
+        }
+        _ => {
+            ensure!(
+                raw_metadata.len() >= MESSAGE_ID_METADATA_MIN_LENGTH
+                    && raw_metadata[MESSAGE_ID_METADATA_MIN_LENGTH..]
+                        .chunks_exact(SIGNATURE_LENGTH)
+                        .remainder()
+                        .is_empty(),
+                ContractError::WrongLength
+            );
+            let metadata: MessageIdMultisigIsmMetadata = raw_metadata.into();
+
+            let multisig_hash = multisig_hash(
+                domain_hash(message.origin_domain, metadata.origin_merkle_tree.clone())?.to_vec(),
+                metadata.merkle_root.to_vec(),
+                metadata.merkle_index(),
+                message.id().to_vec(),
+            )?;
+
+            (multisig_hash, metadata.signatures)
+        }
+    };
 
     let hashed_message = eth_hash(multisig_hash)?;
 
-    // pizza :)
-    let validators = VALIDATORS.load(deps.storage, message.origin_domain)?;
-    let mut threshold = THRESHOLD.load(deps.storage, message.origin_domain)?;
+    let domain = message.origin_domain;
+    let verify_enrolled = || -> Result<bool, ContractError> {
+        // pizza :)
+        let validators = VALIDATORS.load(deps.storage, domain)?;
+        let threshold = THRESHOLD
+            .may_load(deps.storage, domain)?
+            .unwrap_or_default();
 
-    for signature in metadata.signatures {
-        let signature = signature.as_slice();
-        let pubkey = deps.api.secp256k1_recover_pubkey(
+        verify_signers(
+            deps,
+            domain,
+            &validators,
+            threshold,
             &hashed_message,
-            &signature[..64],
-            signature[64] - 27,
-        )?;
-
-        if validators.contains(&eth_addr(pubkey.into())?) {
-            threshold -= 1;
-            if threshold == 0 {
-                break;
+            &signatures,
+        )
+    };
+
+    let verified = match ROTATIONS.may_load(deps.storage, domain)? {
+        Some(rotation) => {
+            let verify_rotated = || {
+                verify_signers(
+                    deps,
+                    domain,
+                    &rotation.validators,
+                    rotation.threshold,
+                    &hashed_message,
+                    &signatures,
+                )
+            };
+
+            match rotation_phase(&rotation, &env.block) {
+                RotationPhase::Scheduled => verify_enrolled()?,
+                RotationPhase::Active => verify_rotated()?,
+                // metadata signed by the outgoing set is still good during the overlap
+                RotationPhase::Overlap => match verify_rotated() {
+                    Ok(true) => true,
+                    rotated => verify_enrolled().or(rotated)?,
+                },
+            }
+        }
+        None => verify_enrolled()?,
+    };
+
+    Ok(VerifyResponse { verified })
+}
+
+/// Checks the signatures reach the threshold of the given validator set.
+fn verify_signers(
+    deps: Deps,
+    domain: u32,
+    validators: &[HexBinary],
+    threshold: u8,
+    hashed_message: &[u8],
+    signatures: &[HexBinary],
+) -> Result<bool, ContractError> {
+    let threshold_weight = THRESHOLD_WEIGHT.may_load(deps.storage, domain)?;
+    let mut threshold = match threshold_weight {
+        Some(threshold_weight) => threshold_weight,
+        None => threshold as u64,
+    };
+    ensure!(threshold > 0, ContractError::ThresholdNotSet { domain });
+
+    let ordered = REQUIRE_ORDERED_SIGNATURES
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let mut signed: Vec<usize> = vec![];
+
+    for signature in signatures {
+        let signer = recover_eth_addr(deps.api, hashed_message, signature)?;
+
+        let position =
+            validators
+                .iter()
+                .position(|v| v == &signer)
+                .ok_or(ContractError::UnknownSigner {
+                    signer: signer.to_hex(),
+                })?;
+        ensure!(
+            !signed.contains(&position),
+            ContractError::DuplicateSigner {
+                signer: signer.to_hex()
             }
+        );
+        // validators are kept sorted, so this is ascending signer order
+        if ordered {
+            ensure!(
+                !matches!(signed.last(), Some(last) if *last >= position),
+                ContractError::UnorderedSignatures
+            );
+        }
+        signed.push(position);
+
+        // every signer counts as one until the domain is given a threshold weight
+        let weight = match threshold_weight {
+            Some(_) => load_weight(deps, domain, &signer)?,
+            None => DEFAULT_WEIGHT,
+        };
+
+        threshold = threshold.saturating_sub(weight);
+        if threshold == 0 {
+            break;
         }
     }
 
-    Ok(VerifyResponse {
-        verified: threshold == 0,
+    Ok(threshold == 0)
+}
+
+fn load_weight(deps: Deps, domain: u32, validator: &HexBinary) -> Result<u64, ContractError> {
+    Ok(WEIGHTS
+        .may_load(deps.storage, (domain, validator.to_vec()))?
+        .unwrap_or(DEFAULT_WEIGHT))
+}
+
+pub fn get_weighted_validators(
+    deps: Deps,
+    domain: u32,
+) -> Result<WeightedValidatorsResponse, ContractError> {
+    let validators = VALIDATORS
+        .may_load(deps.storage, domain)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|validator| {
+            let weight = load_weight(deps, domain, &validator)?;
+
+            Ok(WeightedValidator { validator, weight })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    Ok(WeightedValidatorsResponse {
+        validators,
+        threshold_weight: THRESHOLD_WEIGHT.may_load(deps.storage, domain)?,
+    })
+}
+
+pub fn list_validator_sets(
+    deps: Deps,
+    offset: Option<u32>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> Result<ListValidatorSetsResponse, ContractError> {
+    let ((min, max), limit, order) = range_option(offset, limit, order)?;
+
+    let sets = VALIDATORS
+        .range(deps.storage, min, max, order.into())
+        .take(limit)
+        .map(|item| {
+            let (domain, validators) = item?;
+            let threshold = THRESHOLD
+                .may_load(deps.storage, domain)?
+                .unwrap_or_default();
+
+            Ok(DomainValidatorSet {
+                domain,
+                validators,
+                threshold,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListValidatorSetsResponse { sets })
+}
+
+pub fn get_pending_rotation(
+    deps: Deps,
+    env: Env,
+    domain: u32,
+) -> Result<PendingRotationResponse, ContractError> {
+    let rotation = ROTATIONS.may_load(deps.storage, domain)?;
+    let activated = matches!(
+        &rotation,
+        Some(v) if rotation_phase(v, &env.block) != RotationPhase::Scheduled
+    );
+
+    Ok(PendingRotationResponse {
+        rotation,
+        activated,
     })
 }
 
 pub fn get_verify_info(
     deps: Deps,
+    env: Env,
     raw_message: HexBinary,
 ) -> Result<VerifyInfoResponse, ContractError> {
     let message: Message = raw_message.into();
 
+    // relayers should collect signatures from the rotated set once it's accepted
+    if let Some(rotation) = ROTATIONS.may_load(deps.storage, message.origin_domain)? {
+        if rotation_phase(&rotation, &env.block) != RotationPhase::Scheduled {
+            return Ok(VerifyInfoResponse {
+                threshold: rotation.threshold,
+                validators: rotation.validators,
+            });
+        }
+    }
+
     let threshold = THRESHOLD.load(deps.storage, message.origin_domain)?;
     let validators = VALIDATORS.load(deps.storage, message.origin_domain)?;
 
@@ -82,21 +358,38 @@ pub fn get_verify_info(
 
 #[cfg(test)]
 mod test {
-    use crate::state::{THRESHOLD, VALIDATORS};
-    use cosmwasm_std::{testing::mock_dependencies, HexBinary};
+    use crate::{
+        domain_hash, multisig_hash,
+        state::{
+            MODULE_TYPE, REQUIRE_ORDERED_SIGNATURES, ROTATIONS, THRESHOLD, THRESHOLD_WEIGHT,
+            VALIDATORS, WEIGHTS,
+        },
+        ContractError,
+    };
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env},
+        HexBinary,
+    };
     use hpl_interface::{
         ism::{IsmType, ModuleTypeResponse, VerifyResponse},
-        types::{eth_addr, Message},
+        types::{
+            eth_addr, eth_hash, MerkleRootMultisigIsmMetadata, MerkleTree, Message,
+            MessageIdMultisigIsmMetadata,
+        },
     };
     use ibcx_test_utils::hex;
     use k256::{ecdsa::SigningKey, elliptic_curve::rand_core::OsRng};
     use rstest::rstest;
 
-    use super::{get_module_type, get_verify_info, verify_message};
+    use super::{
+        get_module_type, get_pending_rotation, get_verify_info, get_weighted_validators,
+        list_validator_sets, verify_message, Order, RotationActivation, ValidatorSetRotation,
+    };
 
     #[test]
     fn test_get_module_type() {
-        let result = get_module_type().unwrap();
+        let mut deps = mock_dependencies();
+        let result = get_module_type(deps.as_ref()).unwrap();
 
         assert_eq!(
             result,
@@ -104,6 +397,102 @@ mod test {
                 typ: IsmType::MessageIdMultisig
             }
         );
+
+        MODULE_TYPE
+            .save(deps.as_mut().storage, &IsmType::MerkleRootMultisig)
+            .unwrap();
+        let result = get_module_type(deps.as_ref()).unwrap();
+
+        assert_eq!(
+            result,
+            ModuleTypeResponse {
+                typ: IsmType::MerkleRootMultisig
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::included(1, 1, true)]
+    #[case::wrong_index(1, 0, false)]
+    #[case::before_checkpoint(0, 0, true)]
+    fn test_verify_merkle_root(
+        #[case] message_leaf: usize,
+        #[case] message_index: u32,
+        #[case] verified: bool,
+    ) {
+        let raw_message = hex("0000000000000068220000000000000000000000000d1255b09d94659bb0888e0aa9fca60245ce402a0000682155208cd518cffaac1b5d8df216a9bd050c9a03f0d4f3ba88e5268ac4cd12ee2d68656c6c6f");
+        let message: Message = raw_message.clone().into();
+        let origin_merkle_tree = hex(&"ab".repeat(32));
+
+        // validators sign the checkpoint at the second leaf of the origin tree
+        let sibling = hex(&"cd".repeat(32));
+        let mut leaves = [sibling.clone(), sibling.clone()];
+        leaves[message_leaf] = message.id();
+
+        let mut tree = MerkleTree::default();
+        for leaf in leaves.iter() {
+            tree.insert(leaf.clone()).unwrap();
+        }
+
+        let mut proof = MerkleTree::zeroes().unwrap().map(|v| v.to_vec()).to_vec();
+        proof[0] = sibling.to_vec();
+
+        let checkpoint_hash = multisig_hash(
+            domain_hash(message.origin_domain, origin_merkle_tree.clone())
+                .unwrap()
+                .to_vec(),
+            tree.root().unwrap().to_vec(),
+            1,
+            leaves[1].to_vec(),
+        )
+        .unwrap();
+
+        let signing_key = SigningKey::random(&mut OsRng);
+        let (signature, recovery_id) = signing_key
+            .sign_prehash_recoverable(&eth_hash(checkpoint_hash).unwrap())
+            .unwrap();
+        let signature = [signature.to_vec(), vec![recovery_id.to_byte() + 27]].concat();
+
+        let metadata = MerkleRootMultisigIsmMetadata {
+            origin_mailbox: origin_merkle_tree,
+            message_index,
+            message_id: leaves[1].clone(),
+            proof: proof.concat().into(),
+            signed_checkpoint_index: 1,
+            signatures: signature.into(),
+        };
+
+        let mut deps = mock_dependencies();
+
+        let addr = eth_addr(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes()
+                .into(),
+        )
+        .unwrap();
+
+        MODULE_TYPE
+            .save(deps.as_mut().storage, &IsmType::MerkleRootMultisig)
+            .unwrap();
+        VALIDATORS
+            .save(deps.as_mut().storage, message.origin_domain, &vec![addr])
+            .unwrap();
+        THRESHOLD
+            .save(deps.as_mut().storage, message.origin_domain, &1u8)
+            .unwrap();
+
+        let res = verify_message(deps.as_ref(), mock_env(), metadata.into(), raw_message);
+        if verified {
+            assert_eq!(res.unwrap(), VerifyResponse { verified });
+        } else {
+            // a wrong proof recovers some other key
+            assert!(matches!(
+                res.unwrap_err(),
+                ContractError::UnknownSigner { .. }
+            ));
+        }
     }
 
     #[rstest]
@@ -140,7 +529,7 @@ mod test {
             .save(deps.as_mut().storage, message.origin_domain, &1u8)
             .unwrap();
 
-        let res = verify_message(deps.as_ref(), raw_metadata, raw_message).unwrap();
+        let res = verify_message(deps.as_ref(), mock_env(), raw_metadata, raw_message).unwrap();
         assert_eq!(res, VerifyResponse { verified: true });
     }
 
@@ -160,9 +549,325 @@ mod test {
             .unwrap();
         THRESHOLD.save(deps.as_mut().storage, 26658, &1u8).unwrap();
 
-        let info = get_verify_info(deps.as_ref(), raw_message).unwrap();
+        let info = get_verify_info(deps.as_ref(), mock_env(), raw_message).unwrap();
 
         assert_eq!(info.validators, vec![addr]);
         assert_eq!(info.threshold, 1);
     }
+
+    const RAW_MESSAGE: &str = "0000000000000068220000000000000000000000000d1255b09d94659bb0888e0aa9fca60245ce402a0000682155208cd518cffaac1b5d8df216a9bd050c9a03f0d4f3ba88e5268ac4cd12ee2d68656c6c6f";
+
+    /// Random validator keys, sorted by address like enrolled validators are.
+    fn gen_validators(n: usize) -> Vec<(SigningKey, HexBinary)> {
+        let mut validators = (0..n)
+            .map(|_| {
+                let key = SigningKey::random(&mut OsRng);
+                let addr = eth_addr(
+                    key.verifying_key()
+                        .to_encoded_point(false)
+                        .as_bytes()
+                        .into(),
+                )
+                .unwrap();
+
+                (key, addr)
+            })
+            .collect::<Vec<_>>();
+        validators.sort_by(|a, b| a.1.cmp(&b.1));
+
+        validators
+    }
+
+    fn message_id_metadata(message: &Message, keys: &[&SigningKey]) -> HexBinary {
+        let origin_merkle_tree = hex(&"ab".repeat(32));
+        let merkle_root = hex(&"cd".repeat(32));
+        let checkpoint_hash = multisig_hash(
+            domain_hash(message.origin_domain, origin_merkle_tree.clone())
+                .unwrap()
+                .to_vec(),
+            merkle_root.to_vec(),
+            0,
+            message.id().to_vec(),
+        )
+        .unwrap();
+        let digest = eth_hash(checkpoint_hash).unwrap();
+
+        MessageIdMultisigIsmMetadata {
+            origin_merkle_tree,
+            merkle_root,
+            merkle_index: hex("00000000"),
+            signatures: keys
+                .iter()
+                .map(|key| {
+                    let (signature, recovery_id) = key.sign_prehash_recoverable(&digest).unwrap();
+                    [signature.to_vec(), vec![recovery_id.to_byte() + 27]]
+                        .concat()
+                        .into()
+                })
+                .collect(),
+        }
+        .into()
+    }
+
+    #[rstest]
+    #[case::heavy_signer(vec![0], true)]
+    #[case::light_signers(vec![1, 2], false)]
+    #[case::all(vec![0, 1, 2], true)]
+    fn test_verify_weighted(#[case] signers: Vec<usize>, #[case] verified: bool) {
+        let message: Message = hex(RAW_MESSAGE).into();
+        let validators = gen_validators(3);
+        let addrs = validators
+            .iter()
+            .map(|(_, addr)| addr.clone())
+            .collect::<Vec<_>>();
+
+        let mut deps = mock_dependencies();
+
+        VALIDATORS
+            .save(deps.as_mut().storage, message.origin_domain, &addrs)
+            .unwrap();
+        for (addr, weight) in addrs.iter().zip([5u64, 2, 2]) {
+            WEIGHTS
+                .save(
+                    deps.as_mut().storage,
+                    (message.origin_domain, addr.to_vec()),
+                    &weight,
+                )
+                .unwrap();
+        }
+        THRESHOLD_WEIGHT
+            .save(deps.as_mut().storage, message.origin_domain, &5)
+            .unwrap();
+
+        let keys = signers
+            .into_iter()
+            .map(|i| &validators[i].0)
+            .collect::<Vec<_>>();
+        let metadata = message_id_metadata(&message, &keys);
+
+        let res = verify_message(deps.as_ref(), mock_env(), metadata, hex(RAW_MESSAGE)).unwrap();
+        assert_eq!(res, VerifyResponse { verified });
+
+        let res = get_weighted_validators(deps.as_ref(), message.origin_domain).unwrap();
+        assert_eq!(res.threshold_weight, Some(5));
+        assert_eq!(
+            res.validators
+                .into_iter()
+                .map(|v| v.weight)
+                .collect::<Vec<_>>(),
+            vec![5, 2, 2]
+        );
+    }
+
+    #[rstest]
+    #[case::duplicate(vec![0, 0], false)]
+    #[case::unordered(vec![1, 0], true)]
+    #[case::unknown(vec![3], false)]
+    fn test_verify_rejects(#[case] signers: Vec<usize>, #[case] ordered: bool) {
+        let message: Message = hex(RAW_MESSAGE).into();
+        let mut validators = gen_validators(3);
+        validators.extend(gen_validators(1));
+
+        let mut deps = mock_dependencies();
+
+        VALIDATORS
+            .save(
+                deps.as_mut().storage,
+                message.origin_domain,
+                &validators[..3]
+                    .iter()
+                    .map(|(_, addr)| addr.clone())
+                    .collect(),
+            )
+            .unwrap();
+        THRESHOLD
+            .save(deps.as_mut().storage, message.origin_domain, &2u8)
+            .unwrap();
+        REQUIRE_ORDERED_SIGNATURES
+            .save(deps.as_mut().storage, &ordered)
+            .unwrap();
+
+        let keys = signers
+            .iter()
+            .map(|i| &validators[*i].0)
+            .collect::<Vec<_>>();
+        let metadata = message_id_metadata(&message, &keys);
+
+        let err = verify_message(
+            deps.as_ref(),
+            mock_env(),
+            metadata.clone(),
+            hex(RAW_MESSAGE),
+        )
+        .unwrap_err();
+        match signers[..] {
+            [0, 0] => assert!(matches!(err, ContractError::DuplicateSigner { .. })),
+            [1, 0] => assert!(matches!(err, ContractError::UnorderedSignatures)),
+            _ => assert!(matches!(err, ContractError::UnknownSigner { .. })),
+        }
+
+        // signatures out of order are fine unless the owner asks for ordering
+        if ordered {
+            REQUIRE_ORDERED_SIGNATURES
+                .save(deps.as_mut().storage, &false)
+                .unwrap();
+
+            let res =
+                verify_message(deps.as_ref(), mock_env(), metadata, hex(RAW_MESSAGE)).unwrap();
+            assert_eq!(res, VerifyResponse { verified: true });
+        }
+    }
+
+    #[rstest]
+    #[case::empty(0)]
+    #[case::truncated_header(67)]
+    #[case::partial_signature(68 + 64)]
+    fn test_verify_message_id_wrong_length(#[case] len: usize) {
+        let message: Message = hex(RAW_MESSAGE).into();
+        let validators = gen_validators(1);
+
+        let mut deps = mock_dependencies();
+
+        VALIDATORS
+            .save(
+                deps.as_mut().storage,
+                message.origin_domain,
+                &vec![validators[0].1.clone()],
+            )
+            .unwrap();
+        THRESHOLD
+            .save(deps.as_mut().storage, message.origin_domain, &1u8)
+            .unwrap();
+
+        let metadata = message_id_metadata(&message, &[&validators[0].0]);
+        let metadata = HexBinary::from(&metadata[..len]);
+
+        let err =
+            verify_message(deps.as_ref(), mock_env(), metadata, hex(RAW_MESSAGE)).unwrap_err();
+        assert!(matches!(err, ContractError::WrongLength));
+    }
+
+    #[test]
+    fn test_verify_threshold_not_set() {
+        let message: Message = hex(RAW_MESSAGE).into();
+        let validators = gen_validators(1);
+
+        let mut deps = mock_dependencies();
+
+        VALIDATORS
+            .save(
+                deps.as_mut().storage,
+                message.origin_domain,
+                &vec![validators[0].1.clone()],
+            )
+            .unwrap();
+
+        let metadata = message_id_metadata(&message, &[&validators[0].0]);
+
+        for threshold in [None, Some(0u8)] {
+            if let Some(threshold) = threshold {
+                THRESHOLD
+                    .save(deps.as_mut().storage, message.origin_domain, &threshold)
+                    .unwrap();
+            }
+
+            let err = verify_message(
+                deps.as_ref(),
+                mock_env(),
+                metadata.clone(),
+                hex(RAW_MESSAGE),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::ThresholdNotSet { .. }));
+        }
+    }
+
+    #[test]
+    fn test_list_validator_sets() {
+        let mut deps = mock_dependencies();
+
+        for domain in 1..=3u32 {
+            VALIDATORS
+                .save(
+                    deps.as_mut().storage,
+                    domain,
+                    &vec![hex(&format!("{domain:02x}").repeat(20))],
+                )
+                .unwrap();
+            THRESHOLD.save(deps.as_mut().storage, domain, &1u8).unwrap();
+        }
+
+        let res = list_validator_sets(deps.as_ref(), Some(1), Some(1), None).unwrap();
+        assert_eq!(res.sets.len(), 1);
+        assert_eq!(res.sets[0].domain, 2);
+        assert_eq!(res.sets[0].validators, vec![hex(&"02".repeat(20))]);
+        assert_eq!(res.sets[0].threshold, 1);
+
+        let res = list_validator_sets(deps.as_ref(), None, None, Some(Order::Desc)).unwrap();
+        assert_eq!(
+            res.sets.iter().map(|v| v.domain).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+    }
+
+    #[rstest]
+    #[case::scheduled(12_355, true, false)]
+    #[case::overlap(12_340, true, true)]
+    #[case::active(12_330, false, true)]
+    fn test_verify_rotation(
+        #[case] activation: u64,
+        #[case] outgoing: bool,
+        #[case] incoming: bool,
+    ) {
+        let message: Message = hex(RAW_MESSAGE).into();
+        let (old, new) = (gen_validators(2), gen_validators(2));
+        let addrs = |set: &[(SigningKey, HexBinary)]| {
+            set.iter().map(|(_, addr)| addr.clone()).collect::<Vec<_>>()
+        };
+
+        let mut deps = mock_dependencies();
+        let env = mock_env(); // height 12_345
+
+        VALIDATORS
+            .save(deps.as_mut().storage, message.origin_domain, &addrs(&old))
+            .unwrap();
+        THRESHOLD
+            .save(deps.as_mut().storage, message.origin_domain, &2u8)
+            .unwrap();
+
+        ROTATIONS
+            .save(
+                deps.as_mut().storage,
+                message.origin_domain,
+                &ValidatorSetRotation {
+                    validators: addrs(&new),
+                    threshold: 2,
+                    activation: RotationActivation::Height(activation),
+                    overlap: 10,
+                },
+            )
+            .unwrap();
+
+        for (set, expected) in [(&old, outgoing), (&new, incoming)] {
+            let metadata = message_id_metadata(&message, &[&set[0].0, &set[1].0]);
+            let res = verify_message(deps.as_ref(), env.clone(), metadata, hex(RAW_MESSAGE));
+
+            match expected {
+                true => assert_eq!(res.unwrap(), VerifyResponse { verified: true }),
+                false => assert!(matches!(
+                    res.unwrap_err(),
+                    ContractError::UnknownSigner { .. }
+                )),
+            }
+        }
+
+        let info = get_verify_info(deps.as_ref(), env.clone(), hex(RAW_MESSAGE)).unwrap();
+        assert_eq!(
+            info.validators,
+            if incoming { addrs(&new) } else { addrs(&old) }
+        );
+
+        let res = get_pending_rotation(deps.as_ref(), env, message.origin_domain).unwrap();
+        assert_eq!(res.activated, incoming);
+    }
 }
@@ -1,6 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, HexBinary};
-use cw_storage_plus::Map;
+use cw_storage_plus::{Item, Map};
+use hpl_interface::ism::{multisig::ValidatorSetRotation, IsmType};
 
 #[cw_serde]
 pub struct Config {
@@ -13,3 +14,20 @@ pub const VALIDATORS: Map<u32, Vec<HexBinary>> = Map::new(VALIDATORS_PREFIX);
 
 pub const THRESHOLD_PREFIX: &str = "threshold";
 pub const THRESHOLD: Map<u32, u8> = Map::new(THRESHOLD_PREFIX);
+
+pub const DEFAULT_WEIGHT: u64 = 1;
+
+pub const WEIGHTS_PREFIX: &str = "weights";
+pub const WEIGHTS: Map<(u32, Vec<u8>), u64> = Map::new(WEIGHTS_PREFIX);
+
+pub const THRESHOLD_WEIGHT_PREFIX: &str = "threshold_weight";
+pub const THRESHOLD_WEIGHT: Map<u32, u64> = Map::new(THRESHOLD_WEIGHT_PREFIX);
+
+pub const MODULE_TYPE_KEY: &str = "module_type";
+pub const MODULE_TYPE: Item<IsmType> = Item::new(MODULE_TYPE_KEY);
+
+pub const REQUIRE_ORDERED_SIGNATURES_KEY: &str = "require_ordered_signatures";
+pub const REQUIRE_ORDERED_SIGNATURES: Item<bool> = Item::new(REQUIRE_ORDERED_SIGNATURES_KEY);
+
+pub const ROTATIONS_PREFIX: &str = "rotations";
+pub const ROTATIONS: Map<u32, ValidatorSetRotation> = Map::new(ROTATIONS_PREFIX);
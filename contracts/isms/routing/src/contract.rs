@@ -6,7 +6,9 @@ use cosmwasm_std::{
 use cw2::set_contract_version;
 use hpl_interface::{
     ism::{
-        routing::{ExecuteMsg, InstantiateMsg, QueryMsg, RouteResponse, RoutingIsmQueryMsg},
+        routing::{
+            ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RouteResponse, RoutingIsmQueryMsg,
+        },
         IsmQueryMsg, ModuleTypeResponse, VerifyResponse,
     },
     types::Message,
@@ -126,3 +128,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
         },
     }
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
@@ -3,10 +3,12 @@ use crate::state::{Config, CONFIG, VERIFIED_IDS};
 use crate::wormhole::{ParsedVAA, WormholeQueryMsg};
 use crate::{ContractError, CONTRACT_NAME, CONTRACT_VERSION, WORMHOLE_CORE};
 use cosmwasm_std::{
-    ensure_eq, Binary, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo, QueryResponse, Response,
+    ensure_eq, Binary, Deps, DepsMut, Env, HexBinary, MessageInfo, QueryResponse, Response,
 };
 use cw2::set_contract_version;
-use hpl_interface::ism::wormhole::{ExecuteMsg, InstantiateMsg, QueryMsg, WormholeIsmQueryMsg};
+use hpl_interface::ism::wormhole::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, WormholeIsmQueryMsg,
+};
 use hpl_interface::ism::IsmQueryMsg::{ModuleType, Verify, VerifyInfo};
 use hpl_interface::ism::{IsmType, ModuleTypeResponse, VerifyInfoResponse, VerifyResponse};
 use hpl_interface::to_binary;
@@ -201,6 +203,11 @@ fn verify_info(deps: Deps, _message: HexBinary) -> Result<VerifyInfoResponse, Co
 }
 
 #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
 }
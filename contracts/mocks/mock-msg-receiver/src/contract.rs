@@ -41,20 +41,34 @@ pub fn instantiate(
 pub fn execute(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: ExpectedHandleMsg,
 ) -> StdResult<Response> {
     match msg {
-        ExpectedHandleMsg::Handle(msg) => Ok(Response::default().add_event(
-            Event::new("mailbox_msg_received").add_attributes(vec![
+        ExpectedHandleMsg::Handle(msg) => {
+            let mut event = Event::new("mailbox_msg_received").add_attributes(vec![
                 attr(
                     "sender",
                     bech32_encode(&HRP.load(deps.storage)?, &msg.sender)?,
                 ),
                 attr("origin", msg.origin.to_string()),
                 attr("body", std::str::from_utf8(&msg.body)?),
-            ]),
-        )),
+            ]);
+
+            // value forwarded by the mailbox along with the message
+            if !info.funds.is_empty() {
+                let funds = info
+                    .funds
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                event = event.add_attribute("funds", funds);
+            }
+
+            Ok(Response::default().add_event(event))
+        }
     }
 }
 
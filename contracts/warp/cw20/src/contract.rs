@@ -14,7 +14,7 @@ use hpl_interface::{
     types::bech32_encode,
     warp::{
         self,
-        cw20::{ExecuteMsg, InstantiateMsg, QueryMsg},
+        cw20::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
         TokenMode, TokenModeMsg, TokenModeResponse, TokenTypeResponse,
     },
 };
@@ -264,6 +264,16 @@ fn get_token_mode(deps: Deps) -> Result<TokenModeResponse, ContractError> {
     Ok(TokenModeResponse { mode })
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, ensure_eq, CosmosMsg, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo, QueryResponse,
-    Reply, Response, SubMsg, Uint128, Uint256,
+    ensure, ensure_eq, CosmosMsg, Deps, DepsMut, Env, HexBinary, MessageInfo, QueryResponse, Reply,
+    Response, SubMsg, Uint128, Uint256,
 };
 use hpl_connection::{get_hook, get_ism};
 use hpl_interface::{
@@ -12,7 +12,7 @@ use hpl_interface::{
     types::bech32_encode,
     warp::{
         self,
-        native::{ExecuteMsg, InstantiateMsg, QueryMsg},
+        native::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
     },
     warp::{TokenMode, TokenModeMsg, TokenModeResponse, TokenTypeResponse},
 };
@@ -275,8 +275,13 @@ fn get_token_mode(deps: Deps) -> Result<TokenModeResponse, ContractError> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    Ok(Response::new())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(hpl_migration::migrate(
+        deps.storage,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        &[],
+    )?)
 }
 
 #[cfg(test)]
@@ -58,6 +58,7 @@ impl Ism {
                 codes.ism_multisig,
                 &hpl_interface::ism::multisig::InstantiateMsg {
                     owner: owner.address(),
+                    module_type: None,
                 },
                 None,
                 None,
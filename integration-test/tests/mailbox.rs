@@ -4,7 +4,7 @@ mod contracts;
 mod event;
 mod validator;
 
-use cosmwasm_std::{attr, coin, Attribute, Binary, Uint128};
+use cosmwasm_std::{attr, coin, Attribute, Binary, HexBinary, Uint128, Uint256};
 use ethers::{
     prelude::parse_log, providers::Middleware, signers::Signer, types::TransactionReceipt,
 };
@@ -15,9 +15,12 @@ use osmosis_test_tube::{
 };
 
 use hpl_interface::{
-    core::mailbox::{self, DispatchMsg},
+    core::mailbox::{self, DispatchMsg, DispatchQuoteResponse, MailboxHookQueryMsg},
     igp::oracle::RemoteGasDataConfig,
-    types::{bech32_decode, bech32_encode, bech32_to_h256, AggregateMetadata},
+    types::{
+        bech32_decode, bech32_encode, bech32_to_h256, AggregateMetadata, MessageValue,
+        StandardHookMetadata,
+    },
 };
 use test_tube::{ExecuteResponse, Runner};
 
@@ -62,6 +65,7 @@ where
             msg_body: msg_body.into(),
             hook: None,
             metadata: None,
+            refund_address: None,
         }),
         &[coin(56_000_000, "uosmo")],
         &from.acc_tester,
@@ -93,11 +97,16 @@ where
     let receiver = bech32_to_h256(&to.core.msg_receiver)?;
     let msg_body = b"hello world";
 
-    // dispatch
+    // dispatch. the value to be forwarded to the recipient is committed in the body
+    let value = coin(1_000, "uosmo");
+    let body = HexBinary::try_from(MessageValue {
+        value: value.clone(),
+        body: msg_body.to_vec().into(),
+    })?;
     let dispatch_tx_call = from
         .core
         .mailbox
-        .dispatch(DOMAIN_OSMO, receiver, msg_body.into());
+        .dispatch(DOMAIN_OSMO, receiver, body.to_vec().into());
     let dispatch_res = dispatch_tx_call.send().await?.await?.unwrap();
 
     let dispatch: DispatchFilter = parse_log(dispatch_res.logs[0].clone())?;
@@ -117,14 +126,14 @@ where
         multisig_ism_metadata.into(),
     )]);
 
-    // process
+    // process. attached funds must match the value and are forwarded to the recipient
     let process_res = Wasm::new(to.app).execute(
         &to.core.mailbox,
         &mailbox::ExecuteMsg::Process {
             metadata: aggregate_ism_metadata.into(),
             message: dispatch.message.to_vec().into(),
         },
-        &[],
+        &[value.clone()],
         &to.acc_owner,
     )?;
     let process_recv_evt = process_res
@@ -146,6 +155,7 @@ where
                 from.core.mailbox.local_domain().await?.to_string()
             ),
             attr("body", std::str::from_utf8(msg_body)?),
+            attr("funds", value.to_string()),
         ]),
     );
 
@@ -203,3 +213,77 @@ async fn test_mailbox_evm_to_cw() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_mailbox_cw_to_evm_value() -> eyre::Result<()> {
+    // init Osmosis env
+    let osmo_app = OsmosisTestApp::new();
+    let osmo = cw::setup_env(
+        &osmo_app,
+        |app, coins| app.init_account(coins).unwrap(),
+        None::<&str>,
+        "osmo",
+        DOMAIN_OSMO,
+        &[TestValidators::new(DOMAIN_EVM, 5, 3)],
+        &[RemoteGasDataConfig {
+            remote_domain: DOMAIN_EVM,
+            token_exchange_rate: Uint128::from(10u128.pow(4)),
+            gas_price: Uint128::from(10u128.pow(9)),
+        }],
+    )?;
+
+    let wasm = Wasm::new(osmo.app);
+
+    // the value is committed in the body and announced in the standard hook metadata
+    let value = coin(1_000, "uosmo");
+    let gas_limit = Uint256::from(200_000u128);
+    let dispatch_msg = DispatchMsg {
+        dest_domain: DOMAIN_EVM,
+        recipient_addr: [1u8; 32].to_vec().into(),
+        msg_body: HexBinary::try_from(MessageValue {
+            value: value.clone(),
+            body: b"hello world".to_vec().into(),
+        })?,
+        hook: None,
+        metadata: Some(HexBinary::try_from(StandardHookMetadata {
+            variant: StandardHookMetadata::VARIANT,
+            msg_value: Uint256::from(value.amount),
+            gas_limit,
+            refund_address: bech32_decode(osmo.acc_tester.address().as_str())?.into(),
+        })?),
+        refund_address: None,
+    };
+
+    // the igp in the required hook quotes the gas limit of the metadata
+    let quote: DispatchQuoteResponse = wasm.query(
+        &osmo.core.mailbox,
+        &mailbox::QueryMsg::Hook(MailboxHookQueryMsg::QuoteDispatch(dispatch_msg.clone())),
+    )?;
+
+    // paying exactly the quote is enough
+    let dispatch_res = wasm.execute(
+        &osmo.core.mailbox,
+        &mailbox::ExecuteMsg::Dispatch(dispatch_msg),
+        &quote.total,
+        &osmo.acc_tester,
+    )?;
+
+    let pay_for_gas_evt = dispatch_res
+        .events
+        .iter()
+        .find(|v| v.ty == "wasm-igp-core-pay-for-gas")
+        .unwrap();
+    let attr_value = |key: &str| {
+        pay_for_gas_evt
+            .attributes
+            .iter()
+            .find(|v| v.key == key)
+            .map(|v| v.value.clone())
+            .unwrap()
+    };
+
+    assert_eq!(attr_value("gas_amount"), gas_limit.to_string());
+    assert_eq!(attr_value("gas_refunded"), "0");
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use cosmwasm_std::{
-    testing::{mock_dependencies, mock_info},
+    testing::{mock_dependencies, mock_env, mock_info},
     Binary, HexBinary,
 };
 use ethers::types::{Address, H160};
@@ -208,8 +208,12 @@ fn test_validator() {
         )
         .unwrap();
 
-    let res =
-        hpl_ism_multisig::query::verify_message(deps.as_ref(), metadata.into(), message.into())
-            .unwrap();
+    let res = hpl_ism_multisig::query::verify_message(
+        deps.as_ref(),
+        mock_env(),
+        metadata.into(),
+        message.into(),
+    )
+    .unwrap();
     assert!(res.verified);
 }
@@ -26,7 +26,7 @@ fn new_event(name: &str) -> Event {
 
 pub fn handle<C: CustomQuery>(
     deps: DepsMut<'_, C>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ConnectionMsg,
 ) -> StdResult<Response> {
@@ -41,8 +41,17 @@ pub fn handle<C: CustomQuery>(
     match msg {
         SetMailbox { mailbox } => {
             let mailbox_addr = deps.api.addr_validate(&mailbox)?;
+            let old_mailbox = MAILBOX.may_load(deps.storage)?;
 
             MAILBOX.save(deps.storage, &mailbox_addr)?;
+            hpl_ownable::record_audit(
+                deps.storage,
+                &env,
+                &info.sender,
+                "set_mailbox",
+                old_mailbox.map(|v| v.into()),
+                Some(mailbox_addr.into()),
+            )?;
 
             Ok(event_to_resp(
                 new_event("set_mailbox").add_attribute("mailbox", mailbox),
@@ -50,8 +59,17 @@ pub fn handle<C: CustomQuery>(
         }
         SetIsm { ism } => {
             let ism_addr = deps.api.addr_validate(&ism)?;
+            let old_ism = ISM.may_load(deps.storage)?;
 
             ISM.save(deps.storage, &ism_addr)?;
+            hpl_ownable::record_audit(
+                deps.storage,
+                &env,
+                &info.sender,
+                "set_ism",
+                old_ism.map(|v| v.into()),
+                Some(ism_addr.into()),
+            )?;
 
             Ok(event_to_resp(
                 new_event("set_ism").add_attribute("ism", ism),
@@ -59,8 +77,17 @@ pub fn handle<C: CustomQuery>(
         }
         SetHook { hook } => {
             let hook_addr = deps.api.addr_validate(&hook)?;
+            let old_hook = HOOK.may_load(deps.storage)?;
 
             HOOK.save(deps.storage, &hook_addr)?;
+            hpl_ownable::record_audit(
+                deps.storage,
+                &env,
+                &info.sender,
+                "set_hook",
+                old_hook.map(|v| v.into()),
+                Some(hook_addr.into()),
+            )?;
 
             Ok(event_to_resp(
                 new_event("set_hook").add_attribute("hook", hook),
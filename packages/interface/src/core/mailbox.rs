@@ -1,11 +1,16 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{wasm_execute, Addr, Api, Coin, CosmosMsg, HexBinary, StdResult};
+use cosmwasm_std::{
+    wasm_execute, Addr, Api, BankMsg, Coin, CosmosMsg, CustomQuery, HexBinary, MessageInfo,
+    QuerierWrapper, StdResult, Storage, Timestamp,
+};
+use cw_storage_plus::Item;
 
 #[allow(unused_imports)]
 use crate::{
-    hook::QuoteDispatchResponse,
+    ism::IsmType,
     ownable::{OwnableMsg, OwnableQueryMsg},
-    types,
+    pausable::{PausableMsg, PausableQueryMsg},
+    types, Order,
 };
 
 #[cw_serde]
@@ -15,6 +20,10 @@ pub struct InstantiateMsg {
     pub domain: u32,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct DispatchMsg {
     pub dest_domain: u32,
@@ -22,6 +31,7 @@ pub struct DispatchMsg {
     pub msg_body: HexBinary,
     pub hook: Option<String>,
     pub metadata: Option<HexBinary>,
+    pub refund_address: Option<String>,
 }
 
 impl DispatchMsg {
@@ -36,6 +46,7 @@ impl DispatchMsg {
             msg_body: msg_body.into(),
             hook: None,
             metadata: None,
+            refund_address: None,
         }
     }
 
@@ -49,6 +60,11 @@ impl DispatchMsg {
         self
     }
 
+    pub fn with_refund_address(mut self, refund_address: impl Into<String>) -> Self {
+        self.refund_address = Some(refund_address.into());
+        self
+    }
+
     pub fn to_msg(
         self,
         version: u8,
@@ -56,15 +72,58 @@ impl DispatchMsg {
         origin_domain: u32,
         sender: impl Into<String>,
     ) -> StdResult<types::Message> {
-        Ok(types::Message {
+        let sender = types::bech32_to_h256(&sender.into())?.to_vec();
+
+        Ok(self.to_msg_with_sender(version, nonce, origin_domain, sender.into()))
+    }
+
+    /// Same as `to_msg`, with the sender given as a raw 32-byte address.
+    pub fn to_msg_with_sender(
+        self,
+        version: u8,
+        nonce: u32,
+        origin_domain: u32,
+        sender: HexBinary,
+    ) -> types::Message {
+        types::Message {
             version,
             nonce,
             origin_domain,
-            sender: types::bech32_to_h256(&sender.into())?.to_vec().into(),
+            sender,
             dest_domain: self.dest_domain,
             recipient: self.recipient_addr,
             body: self.msg_body,
-        })
+        }
+    }
+
+    /// Digest `signer` signs to authorize `ExecuteMsg::DispatchSigned`. The refund address
+    /// is not covered, as the surplus belongs to whoever submits and pays for the dispatch.
+    pub fn signed_digest(
+        &self,
+        local_domain: u32,
+        mailbox: &HexBinary,
+        signer: &HexBinary,
+        nonce: u64,
+    ) -> StdResult<HexBinary> {
+        let mut domain = vec![];
+        domain.extend_from_slice(&local_domain.to_be_bytes());
+        domain.extend_from_slice(mailbox);
+        domain.extend_from_slice("HYPERLANE_DISPATCH_SIGNED".as_bytes());
+
+        let mut bz = types::keccak256_hash(&domain).to_vec();
+        bz.extend_from_slice(signer);
+        bz.extend_from_slice(&nonce.to_be_bytes());
+        bz.extend_from_slice(&self.dest_domain.to_be_bytes());
+        bz.extend_from_slice(&self.recipient_addr);
+        bz.extend_from_slice(&types::keccak256_hash(&self.msg_body));
+        bz.extend_from_slice(&types::keccak256_hash(
+            self.hook.as_deref().unwrap_or_default().as_bytes(),
+        ));
+        bz.extend_from_slice(&types::keccak256_hash(
+            &self.metadata.clone().unwrap_or_default(),
+        ));
+
+        types::eth_hash(types::keccak256_hash(&bz))
     }
 
     pub fn get_hook_addr(&self, api: &dyn Api, default: Addr) -> StdResult<Addr> {
@@ -75,12 +134,74 @@ impl DispatchMsg {
             .transpose()?
             .unwrap_or(default))
     }
+
+    pub fn get_refund_addr(&self, api: &dyn Api, default: Addr) -> StdResult<Addr> {
+        Ok(self
+            .refund_address
+            .as_ref()
+            .map(|v| api.addr_validate(v))
+            .transpose()?
+            .unwrap_or(default))
+    }
+}
+
+#[cw_serde]
+pub struct DispatchTarget {
+    pub dest_domain: u32,
+    pub recipient_addr: HexBinary,
+}
+
+#[cw_serde]
+pub enum DispatchManyMsg {
+    /// Sends the same body to every target, sharing the hook and the metadata.
+    Multicast {
+        targets: Vec<DispatchTarget>,
+        msg_body: HexBinary,
+        hook: Option<String>,
+        metadata: Option<HexBinary>,
+        refund_address: Option<String>,
+    },
+
+    /// Sends independent messages. They must share the refund address, where any surplus goes.
+    Messages { msgs: Vec<DispatchMsg> },
+}
+
+impl DispatchManyMsg {
+    pub fn into_msgs(self) -> Vec<DispatchMsg> {
+        match self {
+            Self::Multicast {
+                targets,
+                msg_body,
+                hook,
+                metadata,
+                refund_address,
+            } => targets
+                .into_iter()
+                .map(|target| DispatchMsg {
+                    dest_domain: target.dest_domain,
+                    recipient_addr: target.recipient_addr,
+                    msg_body: msg_body.clone(),
+                    hook: hook.clone(),
+                    metadata: metadata.clone(),
+                    refund_address: refund_address.clone(),
+                })
+                .collect(),
+            Self::Messages { msgs } => msgs,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct ProcessMsg {
+    pub metadata: HexBinary,
+    pub message: HexBinary,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     // overrides
     Ownable(OwnableMsg),
+    Pausable(PausableMsg),
 
     // Mailbox
     SetDefaultIsm {
@@ -96,11 +217,98 @@ pub enum ExecuteMsg {
     },
 
     Dispatch(DispatchMsg),
+    DispatchMany(DispatchManyMsg),
+
+    /// Dispatches `msg` on behalf of `signer`, an ethereum address. The message sender is
+    /// the signer, left-padded to 32 bytes, while the submitter pays the hook fees.
+    DispatchSigned {
+        signer: HexBinary,
+        nonce: u64,
+        signature: HexBinary,
+        msg: DispatchMsg,
+    },
 
+    /// Delivers `message` to its recipient. If the message body is a `MessageValue`, the
+    /// call must carry exactly its value, which is forwarded to the recipient's handle.
     Process {
         metadata: HexBinary,
         message: HexBinary,
     },
+
+    /// Delivers `msgs` in order. With `skip_failed`, messages failing verification are
+    /// skipped and recipients failing to handle theirs are recorded as failed deliveries,
+    /// instead of reverting the whole batch.
+    ProcessBatch {
+        msgs: Vec<ProcessMsg>,
+        skip_failed: bool,
+    },
+
+    SetHandleIsolation {
+        enabled: bool,
+    },
+
+    /// Delivers local-domain dispatches to the recipient in the same transaction.
+    /// They're still posted to, and pay, the required hook. Only the default or
+    /// custom hook is bypassed.
+    SetLocalDelivery {
+        enabled: bool,
+    },
+
+    /// Falls back to the default isms when `recipient` fails the ism specifier query.
+    /// Nested query errors are redacted on chain, so any error counts as specifying no ism.
+    /// Disabled by default, so such recipients fail to process.
+    SetIsmFallback {
+        recipient: String,
+        enabled: bool,
+    },
+
+    RetryHandle {
+        id: HexBinary,
+    },
+
+    /// Keeps only the latest `retention` dispatches. Entries already outside a lowered
+    /// window are evicted a few per dispatch, `PruneDispatches` drops them at once.
+    SetDispatchRetention {
+        retention: Option<u32>,
+    },
+
+    PruneDispatches {
+        before_nonce: u32,
+        limit: Option<u32>,
+    },
+
+    Pause {
+        target: PauseTarget,
+    },
+
+    Release {
+        target: PauseTarget,
+    },
+
+    SetRecipientIsm {
+        recipient: String,
+        ism: String,
+    },
+
+    UnsetRecipientIsm {
+        recipient: String,
+    },
+
+    SetOriginDefaultIsm {
+        origin: u32,
+        ism: String,
+    },
+
+    UnsetOriginDefaultIsm {
+        origin: u32,
+    },
+}
+
+#[cw_serde]
+pub enum PauseTarget {
+    Dispatch {},
+    Process {},
+    Origin { domain: u32 },
 }
 
 pub fn dispatch(
@@ -120,6 +328,7 @@ pub fn dispatch(
             msg_body,
             hook,
             metadata,
+            refund_address: None,
         }),
         funds,
     )?
@@ -132,17 +341,130 @@ pub fn process(mailbox: impl Into<String>, metadata: HexBinary, message: HexBina
         .into()
 }
 
+/// Returns true if `id` was dispatched by the latest dispatch call of `mailbox`.
+/// Only the last message of a `DispatchMany` call is the latest dispatch,
+/// so hooks fall back to this lookup for the earlier ones.
+pub fn in_latest_dispatch_batch<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    mailbox: impl Into<String>,
+    id: &HexBinary,
+) -> bool {
+    querier
+        .query_wasm_smart::<LatestDispatchBatchResponse>(
+            mailbox,
+            &MailboxQueryMsg::LatestDispatchBatch {}.wrap(),
+        )
+        .map(|v| v.message_ids.contains(id))
+        .unwrap_or(false)
+}
+
+/// Where a message posted to a hook stands against the latest one it was posted.
+#[derive(Debug, PartialEq)]
+pub enum PostedNonce {
+    /// Newer than the latest one. It's recorded as the latest.
+    New,
+    /// The latest one, posted again.
+    Repeated,
+    /// Older than the latest one.
+    Replayed,
+}
+
+/// Replay guard of hooks that act on each message once.
+///
+/// Messages are posted in nonce order, so a replayed one is never newer. The latest one
+/// is posted again when a hook is reached twice for a dispatch, e.g. as the required hook
+/// and within an aggregate default hook. That post is to be skipped, see [`skip_repeated_post`].
+pub fn record_posted_nonce(
+    storage: &mut dyn Storage,
+    latest_posted: &Item<u32>,
+    nonce: u32,
+) -> StdResult<PostedNonce> {
+    match latest_posted.may_load(storage)? {
+        Some(latest) if latest == nonce => Ok(PostedNonce::Repeated),
+        Some(latest) if latest > nonce => Ok(PostedNonce::Replayed),
+        _ => {
+            latest_posted.save(storage, &nonce)?;
+            Ok(PostedNonce::New)
+        }
+    }
+}
+
+/// Returns the funds attached to a repeated post to its caller.
+/// The message has been acted on and paid for by the first post.
+pub fn skip_repeated_post(info: MessageInfo) -> Option<BankMsg> {
+    (!info.funds.is_empty()).then(|| BankMsg::Send {
+        to_address: info.sender.into(),
+        amount: info.funds,
+    })
+}
+
+/// Seeds the replay guard of a hook deployed before it, starting from `mailbox`'s latest
+/// dispatch. Otherwise that message could be posted again.
+pub fn seed_latest_posted_nonce<C: CustomQuery>(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper<C>,
+    mailbox: impl Into<String>,
+    latest_posted: &Item<u32>,
+) -> StdResult<()> {
+    if latest_posted.may_load(storage)?.is_some() {
+        return Ok(());
+    }
+
+    let NonceResponse { nonce } =
+        querier.query_wasm_smart(mailbox, &MailboxQueryMsg::Nonce {}.wrap())?;
+
+    // the mailbox's nonce is the one of its next dispatch
+    if let Some(latest) = nonce.checked_sub(1) {
+        latest_posted.save(storage, &latest)?;
+    }
+
+    Ok(())
+}
+
 #[cw_serde]
 pub struct DispatchResponse {
     pub message_id: HexBinary,
 }
 
+#[cw_serde]
+pub struct DispatchManyResponse {
+    pub message_ids: Vec<HexBinary>,
+}
+
+#[cw_serde]
+pub struct DispatchQuoteResponse {
+    pub required_hook_fee: Option<Coin>,
+    pub hook_fee: Option<Coin>,
+    pub total: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct DispatchManyQuoteResponse {
+    pub quotes: Vec<DispatchQuoteResponse>,
+    pub total: Vec<Coin>,
+}
+
+/// Result of a message of a process batch. A recipient whose handle fails in a reply
+/// is reported by the reply's data, which replaces the data of the batch.
+#[cw_serde]
+pub struct ProcessResult {
+    pub message_id: HexBinary,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct ProcessBatchResponse {
+    pub results: Vec<ProcessResult>,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 #[query_responses(nested)]
 pub enum QueryMsg {
     // overrides
     Ownable(OwnableQueryMsg),
+    Pausable(PausableQueryMsg),
 
     Hook(MailboxHookQueryMsg),
 
@@ -153,8 +475,12 @@ pub enum QueryMsg {
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum MailboxHookQueryMsg {
-    #[returns(QuoteDispatchResponse)]
+    /// Required and target hook fees, separately and merged by denom.
+    #[returns(DispatchQuoteResponse)]
     QuoteDispatch(DispatchMsg),
+
+    #[returns(DispatchManyQuoteResponse)]
+    QuoteDispatchMany(DispatchManyMsg),
 }
 
 #[cw_serde]
@@ -181,11 +507,106 @@ pub enum MailboxQueryMsg {
     #[returns(NonceResponse)]
     Nonce {},
 
+    #[returns(SignedNonceResponse)]
+    SignedNonce { signer: HexBinary },
+
     #[returns(RecipientIsmResponse)]
-    RecipientIsm { recipient_addr: String },
+    RecipientIsm {
+        recipient_addr: String,
+        origin: Option<u32>,
+    },
 
     #[returns(LatestDispatchedIdResponse)]
     LatestDispatchId {},
+
+    /// Ids of every message dispatched by the latest `Dispatch`, `DispatchMany` or
+    /// `DispatchSigned` call.
+    #[returns(LatestDispatchBatchResponse)]
+    LatestDispatchBatch {},
+
+    #[returns(DispatchedMessageResponse)]
+    DispatchedMessage { nonce: u32 },
+
+    #[returns(DispatchedMessageResponse)]
+    DispatchedMessageById { id: HexBinary },
+
+    #[returns(DispatchedMessagesResponse)]
+    DispatchedMessages {
+        offset: Option<u32>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+
+    #[returns(DispatchRetentionResponse)]
+    DispatchRetention {},
+
+    #[returns(HandleIsolationResponse)]
+    HandleIsolation {},
+
+    #[returns(LocalDeliveryResponse)]
+    LocalDelivery {},
+
+    #[returns(IsmFallbackResponse)]
+    IsmFallback { recipient: String },
+
+    #[returns(FailedDeliveryResponse)]
+    FailedDelivery { id: HexBinary },
+
+    #[returns(FailedDeliveriesResponse)]
+    FailedDeliveries {
+        offset: Option<HexBinary>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+
+    #[returns(DeliveryResponse)]
+    Delivery { id: HexBinary },
+
+    #[returns(DeliveriesResponse)]
+    DeliveriesByOrigin {
+        origin: u32,
+        offset: Option<HexBinary>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+
+    #[returns(DeliveriesResponse)]
+    DeliveriesByRecipient {
+        recipient: String,
+        offset: Option<HexBinary>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+
+    #[returns(PauseStateResponse)]
+    PauseState {},
+
+    #[returns(RecipientIsmOverridesResponse)]
+    RecipientIsmOverrides {
+        offset: Option<String>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+
+    #[returns(OriginDefaultIsmsResponse)]
+    OriginDefaultIsms {
+        offset: Option<u32>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+
+    #[returns(DiagnosticsResponse)]
+    Diagnostics {},
+
+    /// Messages dispatched to each destination and processed from each origin.
+    /// Without `domains`, the domains seen so far are listed a page at a time.
+    #[returns(StatsResponse)]
+    Stats {
+        domains: Option<Vec<u32>>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
 }
 impl MailboxQueryMsg {
     pub fn wrap(self) -> QueryMsg {
@@ -233,7 +654,164 @@ pub struct NonceResponse {
     pub nonce: u32,
 }
 
+#[cw_serde]
+pub struct SignedNonceResponse {
+    pub nonce: u64,
+}
+
 #[cw_serde]
 pub struct LatestDispatchedIdResponse {
     pub message_id: HexBinary,
 }
+
+#[cw_serde]
+pub struct LatestDispatchBatchResponse {
+    pub message_ids: Vec<HexBinary>,
+}
+
+#[cw_serde]
+pub struct DispatchedMessageResponse {
+    pub nonce: u32,
+    pub id: HexBinary,
+    pub message: HexBinary,
+    pub sender: String,
+    pub dest_domain: u32,
+    pub block_number: u64,
+}
+
+#[cw_serde]
+pub struct DispatchedMessagesResponse {
+    pub messages: Vec<DispatchedMessageResponse>,
+}
+
+#[cw_serde]
+pub struct DispatchRetentionResponse {
+    pub retention: Option<u32>,
+}
+
+#[cw_serde]
+pub struct HandleIsolationResponse {
+    pub enabled: bool,
+}
+
+#[cw_serde]
+pub struct LocalDeliveryResponse {
+    pub enabled: bool,
+}
+
+#[cw_serde]
+pub struct IsmFallbackResponse {
+    pub enabled: bool,
+}
+
+#[cw_serde]
+pub struct FailedDeliveryResponse {
+    pub id: HexBinary,
+    pub message: HexBinary,
+    pub error: String,
+    pub block_number: u64,
+    pub value: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct FailedDeliveriesResponse {
+    pub deliveries: Vec<FailedDeliveryResponse>,
+}
+
+#[cw_serde]
+pub struct DeliveryResponse {
+    pub id: HexBinary,
+    pub sender: String,
+    pub block_number: u64,
+    pub block_time: Option<Timestamp>,
+    pub ism: Option<String>,
+    pub recipient: Option<String>,
+    pub origin_domain: Option<u32>,
+    pub value: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct DeliveriesResponse {
+    pub deliveries: Vec<DeliveryResponse>,
+}
+
+#[cw_serde]
+pub struct PauseStateResponse {
+    pub paused: bool,
+    pub dispatch_paused: bool,
+    pub process_paused: bool,
+    pub paused_origins: Vec<u32>,
+}
+
+#[cw_serde]
+pub struct RecipientIsmOverride {
+    pub recipient: String,
+    pub ism: String,
+}
+
+#[cw_serde]
+pub struct RecipientIsmOverridesResponse {
+    pub overrides: Vec<RecipientIsmOverride>,
+}
+
+#[cw_serde]
+pub struct OriginDefaultIsm {
+    pub origin: u32,
+    pub ism: String,
+}
+
+#[cw_serde]
+pub struct OriginDefaultIsmsResponse {
+    pub isms: Vec<OriginDefaultIsm>,
+}
+
+#[cw_serde]
+pub struct HookDiagnostic {
+    pub hook: Option<String>,
+    pub mailbox: Option<String>,
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct IsmDiagnostic {
+    pub ism: Option<String>,
+    pub module_type: Option<IsmType>,
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct OriginIsmDiagnostic {
+    pub origin: u32,
+    pub ism: IsmDiagnostic,
+}
+
+#[cw_serde]
+pub struct RecipientIsmDiagnostic {
+    pub recipient: String,
+    pub ism: IsmDiagnostic,
+}
+
+#[cw_serde]
+pub struct DomainCount {
+    pub domain: u32,
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    pub outbound: Vec<DomainCount>,
+    pub inbound: Vec<DomainCount>,
+    /// nonce of the first dispatch counted in `outbound`. mailboxes upgraded from before the
+    /// counters existed start counting at the upgrade, inbound counts included.
+    pub start_nonce: u32,
+}
+
+#[cw_serde]
+pub struct DiagnosticsResponse {
+    pub healthy: bool,
+    pub default_ism: IsmDiagnostic,
+    pub default_hook: HookDiagnostic,
+    pub required_hook: HookDiagnostic,
+    pub origin_default_isms: Vec<OriginIsmDiagnostic>,
+    pub recipient_isms: Vec<RecipientIsmDiagnostic>,
+}
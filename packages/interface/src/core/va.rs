@@ -1,12 +1,18 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::HexBinary;
 
+use crate::Order;
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub hrp: String,
     pub mailbox: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Announce {
@@ -14,16 +20,86 @@ pub enum ExecuteMsg {
         signature: HexBinary,
         storage_location: String,
     },
+
+    /// Announces with a cosmos secp256k1 key. `validator` is the bech32 address of `pubkey`,
+    /// and `signature` is an ADR-36 signature over the announcement digest.
+    AnnounceCosmos {
+        validator: String,
+        pubkey: HexBinary,
+        signature: HexBinary,
+        storage_location: String,
+    },
+
+    /// Removes an announced location. A revoked location can't be announced again.
+    Revoke {
+        validator: HexBinary,
+        signature: HexBinary,
+        storage_location: String,
+    },
+
+    /// Swaps an announced location for a new one, keeping its position.
+    Replace {
+        validator: HexBinary,
+        signature: HexBinary,
+        old_storage_location: String,
+        new_storage_location: String,
+    },
+
+    /// Stores a checkpoint of this chain's merkle tree hook, signed by an announced validator.
+    /// The signature is checked against this chain's local domain, and checkpoints are kept
+    /// per merkle tree hook.
+    /// Only eth-style signatures are accepted, as the multisig ism verifies no other. Validators
+    /// announced through `AnnounceCosmos` can't post checkpoints.
+    /// A validator serving checkpoints from here announces `cosmwasm://{this contract}`
+    /// as its storage location.
+    PostCheckpoint {
+        validator: HexBinary,
+        checkpoint: Checkpoint,
+        signature: HexBinary,
+    },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    /// Storage locations of the given validators, in the order asked, up to `MAX_LIMIT` of them.
+    /// Without `validators`, the announced validators are listed a page at a time.
     #[returns(GetAnnounceStorageLocationsResponse)]
-    GetAnnounceStorageLocations { validators: Vec<HexBinary> },
+    GetAnnounceStorageLocations {
+        validators: Option<Vec<HexBinary>>,
+        offset: Option<HexBinary>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
 
     #[returns(GetAnnouncedValidatorsResponse)]
-    GetAnnouncedValidators {},
+    GetAnnouncedValidators {
+        offset: Option<HexBinary>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+
+    #[returns(CheckpointResponse)]
+    GetCheckpoint {
+        validator: HexBinary,
+        merkle_tree_hook: HexBinary,
+        index: u32,
+    },
+
+    #[returns(CheckpointResponse)]
+    LatestCheckpoint {
+        validator: HexBinary,
+        merkle_tree_hook: HexBinary,
+    },
+
+    #[returns(ListCheckpointsResponse)]
+    ListCheckpoints {
+        validator: HexBinary,
+        merkle_tree_hook: HexBinary,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
 }
 
 #[cw_serde]
@@ -34,4 +110,47 @@ pub struct GetAnnounceStorageLocationsResponse {
 #[cw_serde]
 pub struct GetAnnouncedValidatorsResponse {
     pub validators: Vec<String>,
+    /// `validators` along with their key type, in the same order.
+    pub validator_infos: Vec<ValidatorInfo>,
+}
+
+#[cw_serde]
+pub enum ValidatorKeyType {
+    /// Identified by the ethereum address of its key.
+    Ethereum,
+    /// Identified by the ripemd160(sha256(pubkey)) address of its key.
+    Cosmos,
+}
+
+#[cw_serde]
+pub struct ValidatorInfo {
+    pub validator: String,
+    pub key_type: ValidatorKeyType,
+    /// The bech32 address, for cosmos validators.
+    pub address: Option<String>,
+}
+
+#[cw_serde]
+pub struct Checkpoint {
+    /// The merkle tree hook, as 32 bytes
+    pub merkle_tree_hook: HexBinary,
+    pub root: HexBinary,
+    pub index: u32,
+    pub message_id: HexBinary,
+}
+
+#[cw_serde]
+pub struct SignedCheckpoint {
+    pub checkpoint: Checkpoint,
+    pub signature: HexBinary,
+}
+
+#[cw_serde]
+pub struct CheckpointResponse {
+    pub checkpoint: Option<SignedCheckpoint>,
+}
+
+#[cw_serde]
+pub struct ListCheckpointsResponse {
+    pub checkpoints: Vec<SignedCheckpoint>,
 }
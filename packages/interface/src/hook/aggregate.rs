@@ -12,6 +12,10 @@ pub struct InstantiateMsg {
     pub hooks: Vec<String>,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
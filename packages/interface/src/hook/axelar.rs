@@ -14,6 +14,10 @@ pub struct InstantiateMsg {
     pub gas_token: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct RegisterDestinationISMMsg {
     pub destination_ism: String,
@@ -13,6 +13,10 @@ pub struct InstantiateMsg {
     pub owner: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
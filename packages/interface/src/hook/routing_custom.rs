@@ -14,6 +14,10 @@ pub struct InstantiateMsg {
     pub owner: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct RegisterCustomHookMsg {
     pub dest_domain: u32,
@@ -17,6 +17,10 @@ pub struct InstantiateMsg {
     pub beneficiary: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct GasOracleConfig {
     pub remote_domain: u32,
@@ -8,6 +8,10 @@ pub struct InstantiateMsg {
     pub owner: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct RemoteGasDataConfig {
     pub remote_domain: u32,
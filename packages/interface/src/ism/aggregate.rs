@@ -11,6 +11,10 @@ pub struct InstantiateMsg {
     pub threshold: u8,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
@@ -22,6 +22,10 @@ pub struct InstantiateMsg {
     pub origin_chain: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
@@ -6,7 +6,10 @@ pub mod axelar;
 pub mod wormhole;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, CustomQuery, HexBinary, QuerierWrapper, StdResult};
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_vec, Addr, ContractResult, CustomQuery, HexBinary,
+    QuerierWrapper, QueryRequest, StdError, StdResult, SystemResult, WasmQuery,
+};
 
 #[cw_serde]
 #[repr(u32)]
@@ -92,16 +95,36 @@ pub struct InterchainSecurityModuleResponse {
     pub ism: Option<Addr>,
 }
 
+/// Returns `None` if the recipient specifies no ism. With `fallback`, any error the
+/// recipient returns for `IsmSpecifierQueryMsg` is treated the same way.
+///
+/// wasmd redacts the errors of nested queries, so a recipient that doesn't implement
+/// the specifier can't be told apart from one that fails for another reason. The fallback
+/// is therefore meant to be opted into per recipient, never enabled globally.
+/// Querier system errors, such as a missing contract, are always surfaced.
 pub fn recipient<C: CustomQuery>(
     querier: &QuerierWrapper<C>,
     recipient: impl Into<String>,
+    fallback: bool,
 ) -> StdResult<Option<Addr>> {
-    let res = querier.query_wasm_smart::<InterchainSecurityModuleResponse>(
-        recipient,
-        &IsmSpecifierQueryMsg::InterchainSecurityModule().wrap(),
-    )?;
-
-    Ok(res.ism)
+    let request: QueryRequest<C> = WasmQuery::Smart {
+        contract_addr: recipient.into(),
+        msg: to_json_binary(&IsmSpecifierQueryMsg::InterchainSecurityModule().wrap())?,
+    }
+    .into();
+
+    match querier.raw_query(&to_json_vec(&request)?) {
+        SystemResult::Err(err) => Err(StdError::generic_err(format!(
+            "Querier system error: {err}"
+        ))),
+        SystemResult::Ok(ContractResult::Err(_)) if fallback => Ok(None),
+        SystemResult::Ok(ContractResult::Err(err)) => Err(StdError::generic_err(format!(
+            "Querier contract error: {err}"
+        ))),
+        SystemResult::Ok(ContractResult::Ok(res)) => {
+            Ok(from_json::<InterchainSecurityModuleResponse>(&res)?.ism)
+        }
+    }
 }
 
 pub fn verify<C: CustomQuery>(
@@ -117,3 +140,61 @@ pub fn verify<C: CustomQuery>(
 
     Ok(verify_resp.verified)
 }
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{testing::mock_dependencies, Binary, Empty};
+    use rstest::rstest;
+
+    use super::*;
+
+    fn ism_response(ism: Option<&str>) -> ContractResult<Binary> {
+        let res = InterchainSecurityModuleResponse {
+            ism: ism.map(Addr::unchecked),
+        };
+
+        ContractResult::Ok(to_json_binary(&res).unwrap())
+    }
+
+    #[rstest]
+    #[case(ism_response(Some("ism")), false, Ok(Some(Addr::unchecked("ism"))))]
+    #[case(ism_response(None), false, Ok(None))]
+    #[case(
+        ContractResult::Err("Error parsing into type QueryMsg: unknown variant `ism_specifier`".into()),
+        true,
+        Ok(None)
+    )]
+    #[case(
+        ContractResult::Err("Error parsing into type QueryMsg: unknown variant `ism_specifier`".into()),
+        false,
+        Err("Generic error: Querier contract error: Error parsing into type QueryMsg: unknown variant `ism_specifier`".into())
+    )]
+    // wasmd only forwards the error code of a nested query
+    #[case(
+        ContractResult::Err("codespace: wasm, code: 9".into()),
+        true,
+        Ok(None)
+    )]
+    #[case(
+        ContractResult::Err("codespace: wasm, code: 9".into()),
+        false,
+        Err("Generic error: Querier contract error: codespace: wasm, code: 9".into())
+    )]
+    fn test_recipient(
+        #[case] res: ContractResult<Binary>,
+        #[case] fallback: bool,
+        #[case] expected: Result<Option<Addr>, String>,
+    ) {
+        let mut deps = mock_dependencies();
+
+        deps.querier
+            .update_wasm(move |_| SystemResult::Ok(res.clone()));
+
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+
+        assert_eq!(
+            recipient(&querier, "recipient", fallback).map_err(|e| e.to_string()),
+            expected
+        );
+    }
+}
@@ -1,23 +1,54 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::HexBinary;
+use cosmwasm_std::{HexBinary, Timestamp};
 
-use crate::ownable::{OwnableMsg, OwnableQueryMsg};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    Order,
+};
 
-use super::IsmQueryMsg;
+use super::{IsmQueryMsg, IsmType};
 #[allow(unused_imports)]
 use super::{ModuleTypeResponse, VerifyInfoResponse, VerifyResponse};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
+    /// `MessageIdMultisig` (default) or `MerkleRootMultisig`
+    pub module_type: Option<IsmType>,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct ValidatorSet {
     pub domain: u32,
     pub validator: HexBinary,
 }
 
+#[cw_serde]
+pub struct WeightedValidatorSet {
+    pub domain: u32,
+    pub validator: HexBinary,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub enum RotationActivation {
+    Height(u64),
+    Time(Timestamp),
+}
+
+#[cw_serde]
+pub struct ValidatorSetRotation {
+    pub validators: Vec<HexBinary>,
+    pub threshold: u8,
+    pub activation: RotationActivation,
+    /// Blocks or seconds, following `activation`, during which the outgoing set is still accepted
+    pub overlap: u64,
+}
+
 #[cw_serde]
 pub struct ThresholdSet {
     pub domain: u32,
@@ -28,12 +59,67 @@ pub struct ThresholdSet {
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
 
-    EnrollValidator { set: ValidatorSet },
-    EnrollValidators { set: Vec<ValidatorSet> },
-    UnenrollValidator { domain: u32, validator: HexBinary },
+    EnrollValidator {
+        set: ValidatorSet,
+    },
+    EnrollValidators {
+        set: Vec<ValidatorSet>,
+    },
+    UnenrollValidator {
+        domain: u32,
+        validator: HexBinary,
+    },
+
+    SetThreshold {
+        set: ThresholdSet,
+    },
+    SetThresholds {
+        set: Vec<ThresholdSet>,
+    },
+
+    /// Replaces the domain's validators and threshold in one step.
+    /// An empty set with a zero threshold removes the domain.
+    SetValidatorsAndThreshold {
+        domain: u32,
+        validators: Vec<HexBinary>,
+        threshold: u8,
+    },
+
+    /// Enrolls a validator with a voting power. Validators enrolled without one weigh 1.
+    EnrollWeightedValidator {
+        set: WeightedValidatorSet,
+    },
+    UpdateValidatorWeight {
+        set: WeightedValidatorSet,
+    },
 
-    SetThreshold { set: ThresholdSet },
-    SetThresholds { set: Vec<ThresholdSet> },
+    /// Once set, messages from `domain` need signers weighing at least `threshold_weight`
+    /// instead of `threshold` signers.
+    SetThresholdWeight {
+        domain: u32,
+        threshold_weight: u64,
+    },
+
+    /// Queues a new validator set for `domain`. Both sets are accepted during the overlap window.
+    /// The enrolled set can't be changed until the rotation is finalized or cancelled.
+    /// Domains with a threshold weight can't be rotated.
+    ScheduleValidatorSetRotation {
+        domain: u32,
+        rotation: ValidatorSetRotation,
+    },
+    /// Drops the pending rotation of `domain`, even after it has activated.
+    CancelValidatorSetRotation {
+        domain: u32,
+    },
+    /// Makes a rotation whose overlap window has passed the enrolled set. Callable by anyone.
+    FinalizeValidatorSetRotation {
+        domain: u32,
+    },
+
+    /// Requires signatures to be sorted by signer address, as the solidity multisig ISMs do.
+    SetRequireOrderedSignatures {
+        enabled: bool,
+    },
 }
 
 #[cw_serde]
@@ -50,6 +136,22 @@ pub enum QueryMsg {
 pub enum MultisigIsmQueryMsg {
     #[returns(EnrolledValidatorsResponse)]
     EnrolledValidators { domain: u32 },
+
+    #[returns(WeightedValidatorsResponse)]
+    WeightedValidators { domain: u32 },
+
+    #[returns(RequireOrderedSignaturesResponse)]
+    RequireOrderedSignatures {},
+
+    #[returns(ListValidatorSetsResponse)]
+    ListValidatorSets {
+        offset: Option<u32>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+
+    #[returns(PendingRotationResponse)]
+    PendingRotation { domain: u32 },
 }
 
 #[cw_serde]
@@ -58,6 +160,43 @@ pub struct EnrolledValidatorsResponse {
     pub threshold: u8,
 }
 
+#[cw_serde]
+pub struct WeightedValidator {
+    pub validator: HexBinary,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct WeightedValidatorsResponse {
+    pub validators: Vec<WeightedValidator>,
+    /// `None` if the domain still counts signers
+    pub threshold_weight: Option<u64>,
+}
+
+#[cw_serde]
+pub struct DomainValidatorSet {
+    pub domain: u32,
+    pub validators: Vec<HexBinary>,
+    pub threshold: u8,
+}
+
+#[cw_serde]
+pub struct ListValidatorSetsResponse {
+    pub sets: Vec<DomainValidatorSet>,
+}
+
+#[cw_serde]
+pub struct PendingRotationResponse {
+    pub rotation: Option<ValidatorSetRotation>,
+    /// Whether the rotated set is already accepted
+    pub activated: bool,
+}
+
+#[cw_serde]
+pub struct RequireOrderedSignaturesResponse {
+    pub enabled: bool,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -19,6 +19,10 @@ pub struct InstantiateMsg {
     pub isms: Vec<IsmSet>,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
@@ -12,6 +12,10 @@ pub struct InstantiateMsg {
     pub emitter_address: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
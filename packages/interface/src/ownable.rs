@@ -1,6 +1,8 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::Addr;
 
+use crate::Order;
+
 #[cw_serde]
 pub enum OwnableMsg {
     InitOwnershipTransfer { next_owner: String },
@@ -16,6 +18,13 @@ pub enum OwnableQueryMsg {
 
     #[returns(PendingOwnerResponse)]
     GetPendingOwner {},
+
+    #[returns(AuditLogResponse)]
+    GetAuditLog {
+        offset: Option<u64>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
 }
 
 #[cw_serde]
@@ -27,3 +36,19 @@ pub struct OwnerResponse {
 pub struct PendingOwnerResponse {
     pub pending_owner: Option<Addr>,
 }
+
+/// A privileged change, recorded with the values before and after it.
+#[cw_serde]
+pub struct AuditEntry {
+    pub id: u64,
+    pub action: String,
+    pub actor: Addr,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditEntry>,
+}
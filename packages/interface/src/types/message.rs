@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, HexBinary, StdResult};
+use cosmwasm_std::{Addr, Coin, HexBinary, StdError, StdResult};
 
 use super::bech32_encode;
 
@@ -15,6 +15,18 @@ pub struct Message {
 }
 
 impl Message {
+    /// version | nonce | origin_domain | sender | dest_domain | recipient
+    pub const HEADER_LENGTH: usize = 77;
+
+    /// Returns `None` if `v` is shorter than the message header.
+    pub fn parse(v: &HexBinary) -> Option<Self> {
+        if v.len() < Self::HEADER_LENGTH {
+            return None;
+        }
+
+        Some(v.clone().into())
+    }
+
     pub fn id(&self) -> HexBinary {
         super::keccak256_hash(&HexBinary::from(self.clone()))
     }
@@ -58,11 +70,63 @@ impl From<HexBinary> for Message {
     }
 }
 
+/// A message body carrying value for the recipient. The value is part of the message, so
+/// it is covered by the message id and verified by the ISM along with the rest of it.
+#[cw_serde]
+pub struct MessageValue {
+    pub value: Coin,
+    pub body: HexBinary,
+}
+
+impl MessageValue {
+    pub const TAG: &'static [u8; 32] = b"hyperlane-cosmwasm/message-value";
+
+    /// Returns `None` if `v` isn't a body carrying value.
+    pub fn parse(v: &HexBinary) -> Option<Self> {
+        let v = v.strip_prefix(Self::TAG.as_slice())?;
+        if v.len() < 17 {
+            return None;
+        }
+
+        let amount = u128::from_be_bytes(v[0..16].try_into().unwrap());
+        let denom_len = v[16] as usize;
+        let denom = v.get(17..17 + denom_len)?;
+
+        Some(Self {
+            value: Coin::new(amount, String::from_utf8(denom.to_vec()).ok()?),
+            body: v[17 + denom_len..].to_vec().into(),
+        })
+    }
+}
+
+impl TryFrom<MessageValue> for HexBinary {
+    type Error = StdError;
+
+    /// tag | amount | denom length | denom | body
+    fn try_from(v: MessageValue) -> Result<Self, Self::Error> {
+        let denom = v.value.denom.as_bytes();
+        let denom_len: u8 = denom
+            .len()
+            .try_into()
+            .map_err(|_| StdError::generic_err(format!("invalid denom length: {}", denom.len())))?;
+
+        Ok(MessageValue::TAG
+            .iter()
+            .chain(v.value.amount.u128().to_be_bytes().iter())
+            .chain([denom_len].iter())
+            .chain(denom.iter())
+            .chain(v.body.to_vec().iter())
+            .cloned()
+            .collect::<Vec<u8>>()
+            .into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::HexBinary;
+    use cosmwasm_std::{coin, HexBinary};
 
-    use super::Message;
+    use super::{Message, MessageValue};
 
     #[test]
     fn test_encode_decode() {
@@ -84,6 +148,9 @@ mod tests {
 
         assert_eq!(decode_expected, decode_actual);
         assert_eq!(encode_expected, encode_actual);
+
+        assert_eq!(Message::parse(&encode_expected), Some(decode_expected));
+        assert_eq!(Message::parse(&encode_expected[..76].into()), None);
     }
 
     #[test]
@@ -93,4 +160,27 @@ mod tests {
 
         let _msg: Message = no.into();
     }
+
+    #[test]
+    fn test_message_value() {
+        let value = MessageValue {
+            value: coin(1000, "uosmo"),
+            body: HexBinary::from(b"hello world".to_vec()),
+        };
+
+        let value_bz = HexBinary::try_from(value.clone()).unwrap();
+        assert_eq!(value_bz.len(), 32 + 16 + 1 + 5 + 11);
+        assert_eq!(MessageValue::parse(&value_bz), Some(value.clone()));
+
+        // plain bodies and truncated envelopes carry no value
+        assert_eq!(MessageValue::parse(&value.body), None);
+        assert_eq!(MessageValue::parse(&value_bz[..52].into()), None);
+
+        // denoms longer than 255 bytes can't be encoded
+        let value = MessageValue {
+            value: coin(1000, "u".repeat(256)),
+            ..value
+        };
+        assert!(HexBinary::try_from(value).is_err());
+    }
 }
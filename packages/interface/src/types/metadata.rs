@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, HexBinary, Uint256};
+use cosmwasm_std::{Addr, HexBinary, StdError, Uint256};
 
 use super::bech32_encode;
 
@@ -11,11 +11,12 @@ const SIGNATURE_LENGTH: usize = 65;
 pub struct MerkleRootMultisigIsmMetadata {
     pub origin_mailbox: HexBinary,
     // bytes32
-    pub checkpoint_index: u32,
+    pub message_index: u32,
     pub message_id: HexBinary,
     // bytes32
     pub proof: HexBinary,
     // bytes32[32]
+    pub signed_checkpoint_index: u32,
     pub signatures: HexBinary, // threshold * 65
 }
 
@@ -24,9 +25,10 @@ impl From<MerkleRootMultisigIsmMetadata> for HexBinary {
         v.origin_mailbox
             .to_vec()
             .iter()
-            .chain(v.checkpoint_index.to_be_bytes().iter())
+            .chain(v.message_index.to_be_bytes().iter())
             .chain(v.message_id.to_vec().iter())
             .chain(v.proof.to_vec().iter())
+            .chain(v.signed_checkpoint_index.to_be_bytes().iter())
             .chain(v.signatures.to_vec().iter())
             .cloned()
             .collect::<Vec<u8>>()
@@ -38,10 +40,11 @@ impl From<HexBinary> for MerkleRootMultisigIsmMetadata {
     fn from(v: HexBinary) -> Self {
         Self {
             origin_mailbox: v[0..32].to_vec().into(),
-            checkpoint_index: u32::from_be_bytes(v[32..36].try_into().unwrap()),
+            message_index: u32::from_be_bytes(v[32..36].try_into().unwrap()),
             message_id: v[36..68].to_vec().into(),
             proof: v[68..1092].to_vec().into(),
-            signatures: v[1092..].to_vec().into(),
+            signed_checkpoint_index: u32::from_be_bytes(v[1092..1096].try_into().unwrap()),
+            signatures: v[1096..].to_vec().into(),
         }
     }
 }
@@ -237,6 +240,14 @@ impl From<IGPMetadata> for HexBinary {
 
 impl From<HexBinary> for IGPMetadata {
     fn from(v: HexBinary) -> Self {
+        // the standard layout carries the gas limit after the variant and message value
+        if let Some(standard) = StandardHookMetadata::parse(&v) {
+            return Self {
+                gas_limit: standard.gas_limit,
+                refund_address: standard.refund_address,
+            };
+        }
+
         Self {
             gas_limit: Uint256::from_be_bytes(v[0..32].try_into().unwrap()),
             refund_address: v[32..].to_vec().into(),
@@ -264,6 +275,62 @@ impl IGPMetadata {
         bech32_encode(hrp, &raw_addr).unwrap()
     }
 }
+
+/// Hook metadata in the standard variant-1 layout used across hyperlane deployments:
+/// `variant (u16) | msg_value (u256) | gas_limit (u256) | refund_address (20 bytes)`
+#[cw_serde]
+pub struct StandardHookMetadata {
+    pub variant: u16,
+    pub msg_value: Uint256,
+    pub gas_limit: Uint256,
+    pub refund_address: HexBinary,
+}
+
+impl StandardHookMetadata {
+    pub const VARIANT: u16 = 1;
+    pub const LENGTH: usize = 86;
+
+    /// Returns `None` if `v` isn't encoded in the standard layout.
+    pub fn parse(v: &HexBinary) -> Option<Self> {
+        if v.len() < Self::LENGTH || u16::from_be_bytes([v[0], v[1]]) != Self::VARIANT {
+            return None;
+        }
+
+        Some(Self {
+            variant: Self::VARIANT,
+            msg_value: Uint256::from_be_bytes(v[2..34].try_into().unwrap()),
+            gas_limit: Uint256::from_be_bytes(v[34..66].try_into().unwrap()),
+            refund_address: v[66..86].to_vec().into(),
+        })
+    }
+}
+
+impl TryFrom<StandardHookMetadata> for HexBinary {
+    type Error = StdError;
+
+    fn try_from(v: StandardHookMetadata) -> Result<Self, Self::Error> {
+        if v.refund_address.len() > 20 {
+            return Err(StdError::generic_err(format!(
+                "invalid refund address length: {}",
+                v.refund_address.len()
+            )));
+        }
+
+        let mut refund_address = [0u8; 20];
+        refund_address[20 - v.refund_address.len()..].copy_from_slice(&v.refund_address);
+
+        Ok(v.variant
+            .to_be_bytes()
+            .iter()
+            .chain(v.msg_value.to_be_bytes().iter())
+            .chain(v.gas_limit.to_be_bytes().iter())
+            .chain(refund_address.iter())
+            .cloned()
+            .collect::<Vec<u8>>()
+            .into())
+    }
+}
+
 #[cw_serde]
 pub struct AxelarMetadata {
     pub gas_amount: u128,
@@ -327,4 +394,66 @@ mod test {
 
         assert_eq!(recovered, testdata);
     }
+
+    #[test]
+    fn test_standard_hook_metadata() {
+        let metadata = StandardHookMetadata {
+            variant: StandardHookMetadata::VARIANT,
+            msg_value: Uint256::from(1_000_000u128),
+            gas_limit: Uint256::from(200_000u128),
+            refund_address: gen_bz(20),
+        };
+
+        let metadata_bz: HexBinary = metadata.clone().try_into().unwrap();
+        assert_eq!(metadata_bz.len(), StandardHookMetadata::LENGTH);
+        assert_eq!(
+            StandardHookMetadata::parse(&metadata_bz),
+            Some(metadata.clone())
+        );
+
+        // other layouts are not mistaken for the standard one
+        assert_eq!(StandardHookMetadata::parse(&gen_bz(4)), None);
+        assert_eq!(
+            StandardHookMetadata::parse(&[vec![0, 2], gen_bz(84).to_vec()].concat().into()),
+            None
+        );
+
+        // refund addresses longer than 20 bytes can't be encoded
+        let metadata = StandardHookMetadata {
+            refund_address: gen_bz(32),
+            ..metadata
+        };
+        assert!(HexBinary::try_from(metadata).is_err());
+    }
+
+    #[test]
+    fn test_igp_metadata_standard_layout() {
+        let refund_address = gen_bz(20);
+        let metadata_bz: HexBinary = StandardHookMetadata {
+            variant: StandardHookMetadata::VARIANT,
+            msg_value: Uint256::from(1_000_000u128),
+            gas_limit: Uint256::from(200_000u128),
+            refund_address: refund_address.clone(),
+        }
+        .try_into()
+        .unwrap();
+
+        // the igp reads the gas limit, not the variant and value ahead of it
+        let metadata: IGPMetadata = metadata_bz.into();
+        assert_eq!(
+            metadata,
+            IGPMetadata {
+                gas_limit: Uint256::from(200_000u128),
+                refund_address,
+            }
+        );
+
+        // the igp layout is still read as before
+        let legacy = IGPMetadata {
+            gas_limit: Uint256::from(300_000u128),
+            refund_address: gen_bz(32),
+        };
+        let legacy_bz: HexBinary = legacy.clone().into();
+        assert_eq!(IGPMetadata::from(legacy_bz), legacy);
+    }
 }
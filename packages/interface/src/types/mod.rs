@@ -3,9 +3,11 @@ mod crypto;
 mod merkle;
 mod message;
 mod metadata;
+mod signature;
 
 pub use crate::types::bech32::{bech32_decode, bech32_encode, bech32_to_h256};
 pub use crate::types::crypto::*;
 pub use crate::types::merkle::MerkleTree;
-pub use crate::types::message::Message;
+pub use crate::types::message::{Message, MessageValue};
 pub use crate::types::metadata::*;
+pub use crate::types::signature::*;
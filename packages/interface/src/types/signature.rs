@@ -0,0 +1,164 @@
+use cosmwasm_std::{Api, HexBinary};
+use thiserror::Error;
+
+use super::eth_addr;
+
+/// Half of the secp256k1 curve order. Signatures with a larger `s` are malleable.
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SignatureError {
+    #[error("invalid signature length: {len}. expected 64 or 65")]
+    InvalidLength { len: usize },
+
+    #[error("invalid recovery id: {v}")]
+    InvalidRecoveryId { v: u8 },
+
+    #[error("signature s value is not in the lower half of the curve order")]
+    HighS {},
+
+    #[error("failed to recover pubkey: {reason}")]
+    RecoverFailed { reason: String },
+}
+
+/// A secp256k1 signature split into its `r || s` part and the recovery id (0 or 1).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoverableSignature {
+    pub rs: [u8; 64],
+    pub recovery_id: u8,
+}
+
+impl RecoverableSignature {
+    /// Accepts `r || s || v` with `v` in 0, 1, 27 or 28, and 64-byte EIP-2098 compact
+    /// signatures where the recovery id is carried in the top bit of `s`.
+    pub fn parse(bz: &[u8]) -> Result<Self, SignatureError> {
+        let mut rs = [0u8; 64];
+
+        let recovery_id = match bz.len() {
+            65 => {
+                rs.copy_from_slice(&bz[..64]);
+
+                match bz[64] {
+                    v @ (0 | 1) => v,
+                    v @ (27 | 28) => v - 27,
+                    v => return Err(SignatureError::InvalidRecoveryId { v }),
+                }
+            }
+            64 => {
+                rs.copy_from_slice(bz);
+
+                let recovery_id = rs[32] >> 7;
+                rs[32] &= 0x7f;
+
+                recovery_id
+            }
+            len => return Err(SignatureError::InvalidLength { len }),
+        };
+
+        if rs[32..] > SECP256K1_HALF_ORDER[..] {
+            return Err(SignatureError::HighS {});
+        }
+
+        Ok(Self { rs, recovery_id })
+    }
+
+    /// Recovers the ethereum address that signed `hash`.
+    pub fn recover_eth_addr(
+        &self,
+        api: &dyn Api,
+        hash: &[u8],
+    ) -> Result<HexBinary, SignatureError> {
+        let pubkey = api
+            .secp256k1_recover_pubkey(hash, &self.rs, self.recovery_id)
+            .map_err(|e| SignatureError::RecoverFailed {
+                reason: e.to_string(),
+            })?;
+
+        eth_addr(pubkey.into()).map_err(|e| SignatureError::RecoverFailed {
+            reason: e.to_string(),
+        })
+    }
+}
+
+/// Parses `signature` and recovers the ethereum address that signed `hash`.
+pub fn recover_eth_addr(
+    api: &dyn Api,
+    hash: &[u8],
+    signature: &[u8],
+) -> Result<HexBinary, SignatureError> {
+    RecoverableSignature::parse(signature)?.recover_eth_addr(api, hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockApi;
+    use ibcx_test_utils::hex;
+    use rstest::rstest;
+
+    use crate::types::{eth_hash, keccak256_hash};
+
+    use super::*;
+
+    const VALIDATOR: &str = "05a9b5efe9f61f9142453d8e9f61565f333c6768";
+    const SIGNATURE: &str = "dc47d48744fdb42b983f0244ed397feac08ee556eb48416582b5b638ada7b5322c8822e56a9020de7fe663ad43070f04b341514faf430ebf880bb1932434027d1c";
+
+    // digest of a validator announcement signed by VALIDATOR
+    fn digest() -> HexBinary {
+        let mut domain = 5u32.to_be_bytes().to_vec();
+        domain.extend(
+            hex("00000000000000000000000049cfd6ef774acab14814d699e3f7ee36fdfba932").to_vec(),
+        );
+        domain.extend(b"HYPERLANE_ANNOUNCEMENT");
+
+        let mut announcement = keccak256_hash(&domain).to_vec();
+        announcement.extend(b"s3://hyperlane-testnet4-goerli-validator-0/us-east-1");
+
+        eth_hash(keccak256_hash(&announcement)).unwrap()
+    }
+
+    fn with_v(v: u8) -> HexBinary {
+        let mut bz = hex(SIGNATURE).to_vec();
+        bz[64] = v;
+        bz.into()
+    }
+
+    fn compact() -> HexBinary {
+        let bz = hex(SIGNATURE);
+        let mut compact = bz[..64].to_vec();
+        compact[32] |= (bz[64] - 27) << 7;
+        compact.into()
+    }
+
+    #[rstest]
+    #[case::eth_v(hex(SIGNATURE))]
+    #[case::raw_v(with_v(1))]
+    #[case::compact(compact())]
+    fn test_recover(#[case] signature: HexBinary) {
+        let recovered = recover_eth_addr(&MockApi::default(), &digest(), &signature).unwrap();
+
+        assert_eq!(recovered, hex(VALIDATOR));
+    }
+
+    #[rstest]
+    #[case(hex(SIGNATURE)[..63].to_vec(), SignatureError::InvalidLength { len: 63 })]
+    #[case(with_v(29).to_vec(), SignatureError::InvalidRecoveryId { v: 29 })]
+    #[case(with_v(2).to_vec(), SignatureError::InvalidRecoveryId { v: 2 })]
+    fn test_parse_invalid(#[case] signature: Vec<u8>, #[case] err: SignatureError) {
+        assert_eq!(RecoverableSignature::parse(&signature).unwrap_err(), err);
+    }
+
+    #[test]
+    fn test_parse_high_s() {
+        let mut signature = hex(SIGNATURE).to_vec();
+        signature[32..64].copy_from_slice(&[0xff; 32]);
+        signature[32] = 0x7f;
+
+        assert_eq!(
+            RecoverableSignature::parse(&signature).unwrap_err(),
+            SignatureError::HighS {}
+        );
+    }
+}
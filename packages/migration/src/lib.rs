@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod test;
+
+use cosmwasm_std::{ensure, ensure_eq, Event, Response, StdError, StdResult, Storage};
+use semver::Version;
+
+/// A state migration that brings the storage layout up to `version`.
+pub struct Migration {
+    pub version: &'static str,
+    pub migrate: fn(&mut dyn Storage) -> StdResult<()>,
+}
+
+fn new_event(name: &str) -> Event {
+    Event::new(format!("hpl_migration::{}", name))
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    Version::parse(version)
+        .map_err(|e| StdError::generic_err(format!("invalid version {version}: {e}")))
+}
+
+/// Checks the stored cw2 info against `name` and `version`, runs every migration
+/// newer than the stored version up to `version` in order, and stores the new version.
+///
+/// Re-running a migration to the version already stored is a no-op.
+pub fn migrate(
+    storage: &mut dyn Storage,
+    name: &str,
+    version: &str,
+    migrations: &[Migration],
+) -> StdResult<Response> {
+    let stored = cw2::get_contract_version(storage)?;
+
+    ensure_eq!(
+        stored.contract,
+        name,
+        StdError::generic_err(format!(
+            "invalid contract name. expected: {name}, stored: {}",
+            stored.contract
+        ))
+    );
+
+    let from = parse_version(&stored.version)?;
+    let to = parse_version(version)?;
+
+    ensure!(
+        from <= to,
+        StdError::generic_err(format!("cannot downgrade from {from} to {to}"))
+    );
+
+    let mut applied = vec![];
+    let mut prev: Option<Version> = None;
+
+    for migration in migrations {
+        let target = parse_version(migration.version)?;
+
+        ensure!(
+            prev.as_ref().map(|v| v < &target).unwrap_or(true),
+            StdError::generic_err(format!("migrations out of order at {target}"))
+        );
+        prev = Some(target.clone());
+
+        if from < target && target <= to {
+            (migration.migrate)(storage)?;
+            applied.push(target.to_string());
+        }
+    }
+
+    cw2::set_contract_version(storage, name, version)?;
+
+    Ok(Response::new().add_event(
+        new_event("migrate")
+            .add_attribute("contract", name)
+            .add_attribute("from_version", from.to_string())
+            .add_attribute("to_version", to.to_string())
+            .add_attribute("applied", applied.join(",")),
+    ))
+}
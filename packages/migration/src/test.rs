@@ -0,0 +1,104 @@
+use cosmwasm_std::{testing::MockStorage, StdResult, Storage};
+use cw_storage_plus::Item;
+use rstest::rstest;
+
+use crate::{migrate, new_event, Migration};
+
+const NAME: &str = "hpl-test";
+
+const APPLIED: Item<Vec<String>> = Item::new("applied");
+
+fn record(storage: &mut dyn Storage, version: &str) -> StdResult<()> {
+    let mut applied = APPLIED.may_load(storage)?.unwrap_or_default();
+    applied.push(version.to_string());
+    APPLIED.save(storage, &applied)
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: "0.0.6-rc6",
+        migrate: |storage| record(storage, "0.0.6-rc6"),
+    },
+    Migration {
+        version: "0.0.6",
+        migrate: |storage| record(storage, "0.0.6"),
+    },
+    Migration {
+        version: "0.1.0",
+        migrate: |storage| record(storage, "0.1.0"),
+    },
+];
+
+#[rstest]
+#[case("0.0.6-rc5", "0.1.0", vec!["0.0.6-rc6", "0.0.6", "0.1.0"])]
+#[case("0.0.6-rc6", "0.0.6", vec!["0.0.6"])]
+#[case("0.0.6", "0.0.7", vec![])]
+#[case("0.1.0", "0.1.0", vec![])]
+fn test_migrate(#[case] from: &str, #[case] to: &str, #[case] expected: Vec<&str>) {
+    let mut storage = MockStorage::new();
+
+    cw2::set_contract_version(&mut storage, NAME, from).unwrap();
+
+    let res = migrate(&mut storage, NAME, to, MIGRATIONS).unwrap();
+
+    assert_eq!(
+        res.events,
+        vec![new_event("migrate")
+            .add_attribute("contract", NAME)
+            .add_attribute("from_version", from)
+            .add_attribute("to_version", to)
+            .add_attribute("applied", expected.join(","))]
+    );
+    assert_eq!(
+        APPLIED.may_load(&storage).unwrap().unwrap_or_default(),
+        expected
+    );
+    assert_eq!(cw2::get_contract_version(&storage).unwrap().version, to);
+}
+
+#[rstest]
+#[case("hpl-other", "0.0.6", "0.1.0", "invalid contract name")]
+#[case(NAME, "0.1.0", "0.0.6", "cannot downgrade from 0.1.0 to 0.0.6")]
+#[case(NAME, "0.1.0", "0.2.0-rc1", "")]
+#[case(NAME, "0.1.0", "latest", "invalid version latest")]
+fn test_migrate_rejected(
+    #[case] stored_name: &str,
+    #[case] from: &str,
+    #[case] to: &str,
+    #[case] expected: &str,
+) {
+    let mut storage = MockStorage::new();
+
+    cw2::set_contract_version(&mut storage, stored_name, from).unwrap();
+
+    let res = migrate(&mut storage, NAME, to, MIGRATIONS);
+
+    if expected.is_empty() {
+        assert!(res.is_ok());
+        return;
+    }
+
+    assert!(res.unwrap_err().to_string().contains(expected));
+    assert_eq!(cw2::get_contract_version(&storage).unwrap().version, from);
+}
+
+#[test]
+fn test_migrate_out_of_order() {
+    let mut storage = MockStorage::new();
+
+    cw2::set_contract_version(&mut storage, NAME, "0.0.1").unwrap();
+
+    let migrations = [
+        Migration {
+            version: "0.1.0",
+            migrate: |_| Ok(()),
+        },
+        Migration {
+            version: "0.0.6",
+            migrate: |_| Ok(()),
+        },
+    ];
+
+    let err = migrate(&mut storage, NAME, "0.1.0", &migrations).unwrap_err();
+    assert!(err.to_string().contains("migrations out of order at 0.0.6"));
+}
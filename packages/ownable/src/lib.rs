@@ -5,8 +5,14 @@ use cosmwasm_std::{
     ensure, ensure_eq, to_binary, Addr, CustomQuery, Deps, DepsMut, Env, Event, MessageInfo,
     QueryResponse, Response, StdError, StdResult, Storage,
 };
-use cw_storage_plus::Item;
-use hpl_interface::ownable::{OwnableMsg, OwnableQueryMsg, OwnerResponse, PendingOwnerResponse};
+use cw_storage_plus::{Item, Map};
+use hpl_interface::{
+    ownable::{
+        AuditEntry, AuditLogResponse, OwnableMsg, OwnableQueryMsg, OwnerResponse,
+        PendingOwnerResponse,
+    },
+    range_option, Order,
+};
 
 const OWNER_KEY: &str = "owner";
 const OWNER: Item<Addr> = Item::new(OWNER_KEY);
@@ -14,6 +20,12 @@ const OWNER: Item<Addr> = Item::new(OWNER_KEY);
 const PENDING_OWNER_KEY: &str = "pending_owner";
 const PENDING_OWNER: Item<Addr> = Item::new(PENDING_OWNER_KEY);
 
+const AUDIT_LOG_PREFIX: &str = "audit_log";
+const AUDIT_LOG: Map<u64, AuditEntry> = Map::new(AUDIT_LOG_PREFIX);
+
+const AUDIT_LOG_COUNT_KEY: &str = "audit_log_count";
+const AUDIT_LOG_COUNT: Item<u64> = Item::new(AUDIT_LOG_COUNT_KEY);
+
 fn event_to_resp(event: Event) -> Response {
     Response::new().add_event(event)
 }
@@ -30,7 +42,7 @@ pub fn initialize(storage: &mut dyn Storage, owner: &Addr) -> StdResult<()> {
 
 pub fn handle<C: CustomQuery>(
     deps: DepsMut<'_, C>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: OwnableMsg,
 ) -> StdResult<Response> {
@@ -38,27 +50,82 @@ pub fn handle<C: CustomQuery>(
 
     match msg {
         InitOwnershipTransfer { next_owner } => {
-            let event = init_ownership_transfer(
+            let next_owner = deps.api.addr_validate(&next_owner)?;
+            let pending_owner = get_pending_owner(deps.storage)?;
+            let event = init_ownership_transfer(deps.storage, &info.sender, &next_owner)?;
+
+            record_audit(
                 deps.storage,
+                &env,
                 &info.sender,
-                &deps.api.addr_validate(&next_owner)?,
+                "init_ownership_transfer",
+                pending_owner.map(|v| v.into()),
+                Some(next_owner.into()),
             )?;
 
             Ok(event_to_resp(event))
         }
         RevokeOwnershipTransfer {} => {
+            let pending_owner = get_pending_owner(deps.storage)?;
             let event = revoke_ownership_transfer(deps.storage, &info.sender)?;
 
+            record_audit(
+                deps.storage,
+                &env,
+                &info.sender,
+                "revoke_ownership_transfer",
+                pending_owner.map(|v| v.into()),
+                None,
+            )?;
+
             Ok(event_to_resp(event))
         }
         ClaimOwnership {} => {
+            let owner = get_owner(deps.storage)?;
             let event = claim_ownership(deps.storage, &info.sender)?;
 
+            record_audit(
+                deps.storage,
+                &env,
+                &info.sender,
+                "claim_ownership",
+                Some(owner.into()),
+                Some(info.sender.to_string()),
+            )?;
+
             Ok(event_to_resp(event))
         }
     }
 }
 
+/// Appends a privileged change to the audit log and returns its id.
+pub fn record_audit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    actor: &Addr,
+    action: &str,
+    old_value: Option<String>,
+    new_value: Option<String>,
+) -> StdResult<u64> {
+    let id = AUDIT_LOG_COUNT.may_load(storage)?.unwrap_or_default();
+
+    AUDIT_LOG.save(
+        storage,
+        id,
+        &AuditEntry {
+            id,
+            action: action.to_string(),
+            actor: actor.clone(),
+            old_value,
+            new_value,
+            height: env.block.height,
+        },
+    )?;
+    AUDIT_LOG_COUNT.save(storage, &(id + 1))?;
+
+    Ok(id)
+}
+
 pub fn init_ownership_transfer(
     storage: &mut dyn Storage,
     sender: &Addr,
@@ -129,6 +196,13 @@ pub fn handle_query<C: CustomQuery>(
         OwnableQueryMsg::GetPendingOwner {} => to_binary(&PendingOwnerResponse {
             pending_owner: get_pending_owner(deps.storage)?,
         }),
+        OwnableQueryMsg::GetAuditLog {
+            offset,
+            limit,
+            order,
+        } => to_binary(&AuditLogResponse {
+            entries: get_audit_log(deps.storage, offset, limit, order)?,
+        }),
     }
 }
 
@@ -143,3 +217,18 @@ pub fn get_pending_owner(storage: &dyn Storage) -> StdResult<Option<Addr>> {
 
     Ok(pending_owner)
 }
+
+pub fn get_audit_log(
+    storage: &dyn Storage,
+    offset: Option<u64>,
+    limit: Option<u32>,
+    order: Option<Order>,
+) -> StdResult<Vec<AuditEntry>> {
+    let ((min, max), limit, order) = range_option(offset, limit, order)?;
+
+    AUDIT_LOG
+        .range(storage, min, max, order.into())
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect()
+}
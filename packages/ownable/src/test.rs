@@ -3,7 +3,9 @@ use cosmwasm_std::{
     testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
     Addr, CustomQuery, Empty, Env, MessageInfo, OwnedDeps, Response, StdError, StdResult,
 };
-use hpl_interface::ownable::{OwnableMsg, OwnableQueryMsg, OwnerResponse, PendingOwnerResponse};
+use hpl_interface::ownable::{
+    AuditEntry, AuditLogResponse, OwnableMsg, OwnableQueryMsg, OwnerResponse, PendingOwnerResponse,
+};
 use rstest::rstest;
 use serde::de::DeserializeOwned;
 
@@ -64,6 +66,16 @@ where
 
         Ok(resp.pending_owner)
     }
+
+    pub fn audit_log(&self) -> StdResult<Vec<AuditEntry>> {
+        let resp: AuditLogResponse = self.query(OwnableQueryMsg::GetAuditLog {
+            offset: None,
+            limit: None,
+            order: None,
+        })?;
+
+        Ok(resp.entries)
+    }
 }
 
 fn ownable_default() -> Ownable {
@@ -112,6 +124,50 @@ fn test_handle() -> anyhow::Result<()> {
     assert_eq!(ownable.owner()?, next_owner.to_string());
     assert_eq!(ownable.pending_owner()?, None);
 
+    // every step is recorded in the audit log
+    let entry =
+        |id, action: &str, actor: &Addr, old: Option<&Addr>, new: Option<&Addr>| AuditEntry {
+            id,
+            action: action.to_string(),
+            actor: actor.clone(),
+            old_value: old.map(|v| v.to_string()),
+            new_value: new.map(|v| v.to_string()),
+            height: ownable.env.block.height,
+        };
+    assert_eq!(
+        ownable.audit_log()?,
+        vec![
+            entry(
+                0,
+                "init_ownership_transfer",
+                &deployer,
+                None,
+                Some(&next_owner)
+            ),
+            entry(
+                1,
+                "revoke_ownership_transfer",
+                &deployer,
+                Some(&next_owner),
+                None
+            ),
+            entry(
+                2,
+                "init_ownership_transfer",
+                &deployer,
+                None,
+                Some(&next_owner)
+            ),
+            entry(
+                3,
+                "claim_ownership",
+                &next_owner,
+                Some(&deployer),
+                Some(&next_owner)
+            ),
+        ]
+    );
+
     Ok(())
 }
 
@@ -27,7 +27,7 @@ pub fn initialize(storage: &mut dyn Storage, paused: &bool) -> StdResult<()> {
 
 pub fn handle<C: CustomQuery>(
     deps: DepsMut<'_, C>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: PausableMsg,
 ) -> StdResult<Response> {
@@ -37,15 +37,26 @@ pub fn handle<C: CustomQuery>(
         StdError::generic_err("unauthorized")
     );
 
-    match msg {
-        PausableMsg::Pause {} => Ok(event_to_resp(pause(deps.storage, &info.sender)?)),
-        PausableMsg::Release {} => Ok(event_to_resp(release(deps.storage, &info.sender)?)),
-    }
+    let (event, paused) = match msg {
+        PausableMsg::Pause {} => (pause(deps.storage, &info.sender)?, true),
+        PausableMsg::Release {} => (release(deps.storage, &info.sender)?, false),
+    };
+
+    hpl_ownable::record_audit(
+        deps.storage,
+        &env,
+        &info.sender,
+        "paused",
+        Some((!paused).to_string()),
+        Some(paused.to_string()),
+    )?;
+
+    Ok(event_to_resp(event))
 }
 
 pub fn pause(storage: &mut dyn Storage, sender: &Addr) -> StdResult<Event> {
     ensure!(
-        !PAUSE.load(storage)?,
+        !get_pause_info(storage)?,
         StdError::generic_err("already paused")
     );
 
@@ -55,7 +66,7 @@ pub fn pause(storage: &mut dyn Storage, sender: &Addr) -> StdResult<Event> {
 
 pub fn release(storage: &mut dyn Storage, sender: &Addr) -> StdResult<Event> {
     ensure!(
-        PAUSE.load(storage)?,
+        get_pause_info(storage)?,
         StdError::generic_err("already released")
     );
 
@@ -75,6 +86,8 @@ pub fn handle_query<C: CustomQuery>(
     }
 }
 
+/// Contracts that were deployed before pausable was wired in have no state yet,
+/// which is treated as released.
 pub fn get_pause_info(storage: &dyn Storage) -> StdResult<bool> {
-    PAUSE.load(storage)
+    Ok(PAUSE.may_load(storage)?.unwrap_or_default())
 }
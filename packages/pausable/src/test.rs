@@ -97,6 +97,12 @@ fn test_pause(
     // validate
     if res.is_ok() {
         assert!(pausable.pause_info()?);
+
+        let log = hpl_ownable::get_audit_log(pausable.deps.as_ref().storage, None, None, None)?;
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].action, "paused");
+        assert_eq!(log[0].old_value, Some("false".to_string()));
+        assert_eq!(log[0].new_value, Some("true".to_string()));
     }
 
     Ok(())
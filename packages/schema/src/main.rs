@@ -4,7 +4,6 @@ use std::{
 };
 
 use cosmwasm_schema::generate_api;
-use cosmwasm_std::Empty;
 use hpl_interface::{
     core as hpl_core, hook as hpl_hook, igp as hpl_igp, ism as hpl_ism, warp as hpl_warp,
 };
@@ -18,7 +17,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_mailbox",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -30,7 +29,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_validator_announce",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -42,7 +41,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_hook_merkle",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -54,7 +53,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_hook_pausable",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -66,7 +65,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_hook_routing",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -78,7 +77,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_hook_routing_custom",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -90,7 +89,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_hook_routing_fallback",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -102,7 +101,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_igp",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -114,7 +113,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_igp_oracle",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -126,7 +125,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_ism_multisig",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -138,7 +137,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_ism_routing",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -150,7 +149,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_warp_cw20",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -162,7 +161,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_warp_native",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });